@@ -1,6 +1,6 @@
 // The OrbiterSDK detection code for this build script is from the emgre/orbiter-rs repository
 
-#[cfg(not(feature = "docs"))]
+#[cfg(all(feature = "sdk", not(feature = "docs")))]
 macro_rules! error {
     ($($args:tt)+) => ({
         let msg = format!($($args)*);
@@ -9,11 +9,21 @@ macro_rules! error {
     })
 }
 
-#[cfg(feature = "docs")]
-fn main() {}
-
-#[cfg(not(feature = "docs"))]
 fn main() {
+    // `orbiter_sdk` selects the cxx bridge in `ffi.rs`. Otherwise the crate is built against
+    // the in-memory stand-in from the `mock` module, which needs neither Orbiter nor Windows.
+    println!("cargo:rustc-check-cfg=cfg(orbiter_sdk)");
+    if cfg!(feature = "sdk") && !cfg!(feature = "mock") {
+        println!("cargo:rustc-cfg=orbiter_sdk");
+
+        // The docs.rs build does not link against the Orbiter SDK
+        #[cfg(all(feature = "sdk", not(feature = "docs")))]
+        build_sdk_bridge();
+    }
+}
+
+#[cfg(all(feature = "sdk", not(feature = "docs")))]
+fn build_sdk_bridge() {
     use std::env;
     use std::path::PathBuf;
 
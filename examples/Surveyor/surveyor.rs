@@ -1,13 +1,15 @@
-use lazy_static::lazy_static;
+#![allow(non_snake_case)]
 /// Surveyor spacecraft implementation using orbiter-rs
 ///
 /// This is a port of Surveyor.cpp to Rust
 ///
 use orbiter_rs::{
     debug_string, init_logging, init_vessel, oapi_create_vessel, FileHandle, Key, KeyStates,
-    OrbiterVessel, PropellantHandle, SDKVessel, ThrusterGroupType, ThrusterHandle, Vector3,
+    OrbiterVessel, PropellantHandle, ThrusterGroupType, ThrusterHandle, Vector3, VesselApi,
     VesselStatus, V,
 };
+use orbiter_rs::attitude::VesselAttitude;
+use orbiter_rs::units::{Force, Mass, Time, VesselUnits};
 
 use log::info;
 
@@ -25,10 +27,8 @@ const RCS_Z: f64 = -0.5;
 const RCS_SPACE: f64 = 0.1;
 
 const RETRO_PROP_MASS: f64 = 560.64;
-const RETRO_THRUST: f64 = 39140.0;
-const RETRO_BURNTIME: f64 = 40.5;
-const RETRO_ITOT: f64 = RETRO_THRUST * RETRO_BURNTIME;
-const RETRO_ISP: f64 = RETRO_ITOT / RETRO_PROP_MASS;
+const RETRO_THRUST: Force = Force::newtons(39140.0);
+const RETRO_BURNTIME: Time = Time::seconds(40.5);
 const RETRO_Z: f64 = -0.75;
 
 const LANDER_EMPTY_MASS: f64 = 289.10; //Basic bus plus payload minus AMR minus retro case
@@ -38,42 +38,26 @@ const AMR_MASS: f64 = 3.82;
 const LEG_RAD: f64 = 1.5;
 const LEG_Z: f64 = -0.6;
 
-lazy_static! {
-    static ref THRUSTER1_POS: Vector3 =
-        Vector3::new(0.0 * VERNIER_RAD, 1.0 * VERNIER_RAD, VERNIER_Z);
-    static ref THRUSTER2_POS: Vector3 = Vector3::new(
-        (60.0f64).to_radians().sin() * VERNIER_RAD,
-        -0.5 * VERNIER_RAD,
-        VERNIER_Z
-    );
-    static ref THRUSTER3_POS: Vector3 = Vector3::new(
-        -(60.0f64).to_radians().sin() * VERNIER_RAD,
-        -0.5 * VERNIER_RAD,
-        VERNIER_Z
-    );
-    static ref DIR_X_PLUS: Vector3 = Vector3::new(1., 0., 0.);
-    static ref DIR_X_MINUS: Vector3 = Vector3::new(-1., 0., 0.);
-    static ref DIR_Y_PLUS: Vector3 = Vector3::new(0., 1., 0.);
-    static ref DIR_Y_MINUS: Vector3 = Vector3::new(0., -1., 0.);
-    static ref DIR_Z_PLUS: Vector3 = Vector3::new(0., 0., 1.);
-    static ref DIR_Z_MINUS: Vector3 = Vector3::new(0., 0., 1.);
-}
+const SIN_60: f64 = 0.866_025_403_784_438_6;
+const THRUSTER1_POS: Vector3 = V!(0.0 * VERNIER_RAD, 1.0 * VERNIER_RAD, VERNIER_Z);
+const THRUSTER2_POS: Vector3 = V!(SIN_60 * VERNIER_RAD, -0.5 * VERNIER_RAD, VERNIER_Z);
+const THRUSTER3_POS: Vector3 = V!(-SIN_60 * VERNIER_RAD, -0.5 * VERNIER_RAD, VERNIER_Z);
+const DIR_X_PLUS: Vector3 = V!(1., 0., 0.);
+const DIR_X_MINUS: Vector3 = V!(-1., 0., 0.);
+const DIR_Z_PLUS: Vector3 = V!(0., 0., 1.);
+const DIR_Z_MINUS: Vector3 = V!(0., 0., -1.);
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 enum SurveyorState {
+    #[default]
     BeforeRetroIgnition,
     RetroFiring,
     AfterRetro,
 }
-impl Default for SurveyorState {
-    fn default() -> Self {
-        Self::BeforeRetroIgnition
-    }
-}
 
 #[derive(Debug)]
-pub struct Surveyor {
-    ctx: SDKVessel,
+pub struct Surveyor<V: VesselApi> {
+    ctx: V,
     th_vernier: Vec<ThrusterHandle>,
     th_rcs: Vec<ThrusterHandle>,
     th_retro: ThrusterHandle,
@@ -82,8 +66,8 @@ pub struct Surveyor {
     ph_rcs: PropellantHandle,
     vehicle_state: SurveyorState,
 }
-impl Surveyor {
-    pub fn new(vessel: SDKVessel) -> Self {
+impl<V: VesselApi> Surveyor<V> {
+    pub fn new(vessel: V) -> Self {
         Self {
             ctx: vessel,
             th_vernier: Vec::new(),
@@ -97,10 +81,11 @@ impl Surveyor {
     }
     fn setup_meshes(&mut self) {
         self.ctx.ClearMeshes();
-        let mut meshes = Vec::new();
-        meshes.push(("Surveyor-AMR", Vector3::new(0., 0., -0.6)));
-        meshes.push(("Surveyor-Retro", Vector3::new(0., 0., -0.5)));
-        meshes.push(("Surveyor-Lander", Vector3::new(0., 0.3, 0.)));
+        let meshes = [
+            ("Surveyor-AMR", Vector3::new(0., 0., -0.6)),
+            ("Surveyor-Retro", Vector3::new(0., 0., -0.5)),
+            ("Surveyor-Lander", Vector3::new(0., 0.3, 0.)),
+        ];
 
         let meshes_used = match self.vehicle_state {
             SurveyorState::BeforeRetroIgnition => &meshes[0..],
@@ -108,7 +93,7 @@ impl Surveyor {
             SurveyorState::AfterRetro => &meshes[2..],
         };
         for (mesh, ofs) in meshes_used {
-            self.ctx.AddMeshWithOffset(mesh.to_string(), &ofs);
+            self.ctx.AddMeshWithOffset(mesh.to_string(), ofs);
         }
     }
     fn calc_empty_mass(&self) -> f64 {
@@ -122,7 +107,7 @@ impl Surveyor {
             empty_mass += RETRO_EMPTY_MASS;
         }
         empty_mass += LANDER_EMPTY_MASS;
-        return empty_mass;
+        empty_mass
     }
     fn spawn_object(&self, classname: &str, ext: &str, offset: &Vector3) {
         let mut vs = VesselStatus::default();
@@ -153,7 +138,7 @@ impl Surveyor {
         self.setup_meshes();
     }
 }
-impl OrbiterVessel for Surveyor {
+impl<V: VesselApi> OrbiterVessel for Surveyor<V> {
     fn set_class_caps(&mut self, _cfg: &FileHandle) {
         init_logging(log::Level::Info);
         info!("Surveyor::set_class_caps()");
@@ -288,7 +273,7 @@ impl OrbiterVessel for Surveyor {
 
         th_group[0] = self.th_rcs[1]; // -X
         self.ctx
-            .CreateThrusterGroup(&th_group, ThrusterGroupType::AttBankleft);
+            .CreateThrusterGroup(&th_group[..1], ThrusterGroupType::AttBankleft);
 
         th_group[0] = self.th_rcs[3]; // -Z #1
         th_group[1] = self.th_rcs[4]; // +Z #2
@@ -304,12 +289,13 @@ impl OrbiterVessel for Surveyor {
             self.ctx.AddExhaust(*th, 0.1, 0.05);
         }
 
-        self.th_retro = self.ctx.CreateThruster(
+        let retro_isp = RETRO_THRUST * RETRO_BURNTIME / Mass::kilograms(RETRO_PROP_MASS);
+        self.th_retro = self.ctx.create_thruster(
             &V!(0.0, 0.0, RETRO_Z),
             &DIR_Z_PLUS,
             RETRO_THRUST,
             self.ph_retro,
-            RETRO_ISP,
+            retro_isp,
         );
         self.ctx.AddExhaust(self.th_retro, 2.0, 0.3);
 
@@ -322,24 +308,9 @@ impl OrbiterVessel for Surveyor {
     fn on_pre_step(&mut self, _sim_t: f64, _sim_dt: f64, _mjd: f64) {
         self.ctx.SetEmptyMass(self.calc_empty_mass());
 
-        let pitch = self
-            .ctx
-            .GetThrusterGroupLevelByType(ThrusterGroupType::AttPitchup)
-            - self
-                .ctx
-                .GetThrusterGroupLevelByType(ThrusterGroupType::AttPitchdown);
-        let yaw = self
-            .ctx
-            .GetThrusterGroupLevelByType(ThrusterGroupType::AttYawright)
-            - self
-                .ctx
-                .GetThrusterGroupLevelByType(ThrusterGroupType::AttYawleft);
-        let roll = self
-            .ctx
-            .GetThrusterGroupLevelByType(ThrusterGroupType::AttBankright)
-            - self
-                .ctx
-                .GetThrusterGroupLevelByType(ThrusterGroupType::AttBankleft);
+        // Levels of the RCS groups, with yaw positive to the right
+        let levels = self.ctx.rotation_levels();
+        let (pitch, yaw, roll) = (levels.x(), -levels.y(), levels.z());
 
         // Differential thrusting for attitude control
         self.ctx.SetThrusterDir(
@@ -372,9 +343,7 @@ impl OrbiterVessel for Surveyor {
         debug_string!("Pitch: {}, Yaw: {}, Roll: {}", pitch, yaw, roll);
     }
     fn consume_buffered_key(&mut self, key: Key, down: bool, kstate: KeyStates) -> i32 {
-        if !down {
-            0
-        } else if kstate.shift() {
+        if !down || kstate.shift() {
             0
         } else {
             // unmodified keys
@@ -1,3 +1,4 @@
+#![allow(non_snake_case)]
 use orbiter_rs::{debug_string, init_vessel, FileHandle, OrbiterVessel, SDKVessel};
 pub struct MinimalPB {
     ctx: SDKVessel,
@@ -8,7 +9,7 @@ impl MinimalPB {
     }
 }
 impl OrbiterVessel for MinimalPB {
-    fn set_class_caps(&mut self, _cfg: FileHandle) {
+    fn set_class_caps(&mut self, _cfg: &FileHandle) {
         self.ctx.SetSize(1.0);
         self.ctx.AddMesh("ShuttlePB".into());
     }
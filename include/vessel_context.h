@@ -14,6 +14,8 @@ using c_void = void;
 
 // Wrapper for oapiCreateVessel
 OBJHANDLE oapi_create_vessel(rust::String name, rust::String classname, const VESSELSTATUS &status);
+// Wrapper for oapiGetPlanetObliquityMatrix
+void oapi_get_planet_obliquity_matrix(OBJHANDLE hPlanet, MATRIX3 &mat);
 // VESSEL *vessel_ovcInit(OBJHANDLE hvessel, int flightmodel, BoxDynVessel box_vessel);
 VESSEL *vessel_ovcInit(OBJHANDLE hvessel, int flightmodel, VesselInitFn fn);
 void vessel_ovcExit(VESSEL *vessel);
@@ -46,6 +48,8 @@ public:
 
     rust::Str GetName() const;
     OBJHANDLE GetSurfaceRef() const;
+    bool GetElements(OBJHANDLE hRef, ELEMENTS &el, ORBITPARAM &prm, double mjd_ref, int frame) const;
+    bool SetElements(OBJHANDLE hRef, const ELEMENTS &el, ORBITPARAM &prm, double mjd_ref, int frame) const;
 private:
     BoxDynVessel rust_spacecraft_;
     VesselInitFn rust_init_fn_;
@@ -0,0 +1,217 @@
+//! Thruster allocation for arbitrary thruster layouts
+//!
+//! A [ThrusterLayout] records the position, direction and maximum thrust of the vessel's thrusters as they
+//! are created with [ThrusterLayout::create_thruster]. Given the force and torque wanted on the vessel,
+//! [ThrusterLayout::allocate] finds the thrust levels between 0 and 1 that come closest to them in the
+//! least-squares sense. When the request is out of reach, the thrusters saturate and the returned
+//! [Allocation] reports what was actually achieved.
+//!
+//! Torques follow the convention of Orbiter's left-handed frames, `F × r` for a force `F` applied at `r`,
+//! so that a positive torque about `x` pitches up like
+//! [GetAngularVel](crate::VesselContext::GetAngularVel). Forces and torques are weighted equally in the
+//! fit, i.e. an error of 1 N·m counts as much as an error of 1 N.
+//!
+//! ```
+//! use orbiter_rs::allocation::{ThrusterLayout, ThrusterSpec};
+//! use orbiter_rs::{THRUSTER_HANDLE, V};
+//!
+//! // Two 100 N engines on either side of the tail, steering by differential thrust
+//! let mut layout = ThrusterLayout::new();
+//! for (id, x) in [(1, 1.0), (2, -1.0)] {
+//!     layout.push(ThrusterSpec::new(THRUSTER_HANDLE(id), V!(x, 0.0, -2.0), V!(0.0, 0.0, 1.0), 100.0));
+//! }
+//!
+//! let allocation = layout.allocate(&V!(0.0, 0.0, 150.0), &V!(0.0, 20.0, 0.0));
+//! assert!((allocation.levels[0] - 0.85).abs() < 1e-9);
+//! assert!((allocation.levels[1] - 0.65).abs() < 1e-9);
+//!
+//! // More thrust than available: both engines saturate
+//! let allocation = layout.allocate(&V!(0.0, 0.0, 300.0), &V!(0.0, 0.0, 0.0));
+//! assert_eq!(allocation.levels, [1.0, 1.0]);
+//! assert!((allocation.force.z() - 200.0).abs() < 1e-9);
+//! ```
+use crate::{PropellantHandle, ThrusterHandle, Vector3, VesselApi};
+
+/// Maximum number of sweeps over the thrusters when solving for the levels
+const MAX_SWEEPS: usize = 1000;
+/// Largest change of any level in a sweep at which the solution is considered converged
+const LEVEL_TOLERANCE: f64 = 1e-12;
+
+/// A thruster as defined with `CreateThruster`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrusterSpec {
+    /// Handle of the thruster
+    pub handle: ThrusterHandle,
+    /// Thrust attack point in vessel coordinates \[**m**\]
+    pub pos: Vector3,
+    /// Unit thrust direction in vessel coordinates
+    pub dir: Vector3,
+    /// Vacuum thrust rating \[**N**\]
+    pub max_thrust: f64,
+    /// Propellant resource feeding the thruster
+    pub propellant: PropellantHandle,
+    /// Vacuum fuel-specific impulse \[**m/s**\]
+    pub isp: f64,
+}
+
+impl ThrusterSpec {
+    /// Build a new `ThrusterSpec` with no propellant resource and ISP
+    pub fn new(handle: ThrusterHandle, pos: Vector3, dir: Vector3, max_thrust: f64) -> Self {
+        Self {
+            handle,
+            pos,
+            dir,
+            max_thrust,
+            propellant: PropellantHandle::default(),
+            isp: 0.0,
+        }
+    }
+    /// Returns the force of the thruster at full thrust in vessel coordinates \[**N**\]
+    pub fn force(&self) -> Vector3 {
+        self.dir * self.max_thrust
+    }
+    /// Returns the torque of the thruster at full thrust about the centre of mass \[**N·m**\]
+    pub fn torque(&self) -> Vector3 {
+        // Orbiter's frames are left-handed, so the torque is F × r
+        self.force().cross(&self.pos)
+    }
+}
+
+/// Thrust levels computed by [ThrusterLayout::allocate]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    /// Level of each thruster of the layout, in the order they were added
+    pub levels: Vec<f64>,
+    /// Force produced by the thrusters at these levels \[**N**\]
+    pub force: Vector3,
+    /// Torque produced by the thrusters at these levels \[**N·m**\]
+    pub torque: Vector3,
+}
+
+/// The thrusters of a vessel, recorded as they are created
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThrusterLayout {
+    thrusters: Vec<ThrusterSpec>,
+}
+
+impl ThrusterLayout {
+    /// Build an empty `ThrusterLayout`
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Creates a thruster on `vessel` with `CreateThruster` and adds it to the layout
+    ///
+    /// `pos` and `dir` are in local vessel coordinates, `dir` must be a unit vector.
+    pub fn create_thruster<V: VesselApi + ?Sized>(
+        &mut self,
+        vessel: &V,
+        pos: &Vector3,
+        dir: &Vector3,
+        max_thrust: f64,
+        ph: PropellantHandle,
+        isp: f64,
+    ) -> ThrusterHandle {
+        let handle = vessel.CreateThruster(pos, dir, max_thrust, ph, isp);
+        self.push(ThrusterSpec {
+            handle,
+            pos: *pos,
+            dir: *dir,
+            max_thrust,
+            propellant: ph,
+            isp,
+        });
+        handle
+    }
+    /// Adds a thruster defined elsewhere to the layout
+    pub fn push(&mut self, thruster: ThrusterSpec) {
+        self.thrusters.push(thruster);
+    }
+    /// Returns the thrusters of the layout, in the order they were added
+    pub fn thrusters(&self) -> &[ThrusterSpec] {
+        &self.thrusters
+    }
+    /// Returns the thruster with handle `th`, if it is part of the layout
+    pub fn thruster(&self, th: ThrusterHandle) -> Option<&ThrusterSpec> {
+        self.thrusters.iter().find(|thruster| thruster.handle == th)
+    }
+    /// Returns the thrusters drawing from the propellant resource `ph`
+    pub fn thrusters_fed_by(&self, ph: PropellantHandle) -> impl Iterator<Item = &ThrusterSpec> {
+        self.thrusters.iter().filter(move |thruster| thruster.propellant == ph)
+    }
+    /// Returns the propellant resources feeding the thrusters, in the order they first appear
+    pub fn propellants(&self) -> Vec<PropellantHandle> {
+        let mut propellants = Vec::new();
+        for thruster in &self.thrusters {
+            if !propellants.contains(&thruster.propellant) {
+                propellants.push(thruster.propellant);
+            }
+        }
+        propellants
+    }
+    /// Resets the direction of thruster `th` on `vessel` with `SetThrusterDir` and in the layout
+    pub fn set_thruster_dir<V: VesselApi + ?Sized>(&mut self, vessel: &V, th: ThrusterHandle, dir: &Vector3) {
+        vessel.SetThrusterDir(th, dir);
+        if let Some(thruster) = self.thrusters.iter_mut().find(|thruster| thruster.handle == th) {
+            thruster.dir = *dir;
+        }
+    }
+
+    /// Returns the levels producing the `force` and `torque` closest to the ones requested, in vessel
+    /// coordinates \[**N**, **N·m**\]
+    ///
+    /// The search starts with all thrusters off and only raises a level while it reduces the error, so
+    /// opposing thrusters are normally not fired against each other.
+    pub fn allocate(&self, force: &Vector3, torque: &Vector3) -> Allocation {
+        let columns: Vec<[f64; 6]> = self
+            .thrusters
+            .iter()
+            .map(|thruster| {
+                let (f, t) = (thruster.force(), thruster.torque());
+                [f.x(), f.y(), f.z(), t.x(), t.y(), t.z()]
+            })
+            .collect();
+        let target = [force.x(), force.y(), force.z(), torque.x(), torque.y(), torque.z()];
+        let dot = |a: &[f64; 6], b: &[f64; 6]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>();
+
+        // Minimise |C l - b|² for 0 <= l <= 1 by cyclic coordinate descent on the normal equations
+        let n = columns.len();
+        let gram: Vec<Vec<f64>> = columns
+            .iter()
+            .map(|a| columns.iter().map(|b| dot(a, b)).collect())
+            .collect();
+        let rhs: Vec<f64> = columns.iter().map(|a| dot(a, &target)).collect();
+        let mut levels = vec![0.0; n];
+        for _ in 0..MAX_SWEEPS {
+            let mut max_change: f64 = 0.0;
+            for i in 0..n {
+                if gram[i][i] <= 0.0 {
+                    continue;
+                }
+                let residual = rhs[i] - (0..n).map(|j| gram[i][j] * levels[j]).sum::<f64>();
+                let level = (levels[i] + residual / gram[i][i]).clamp(0.0, 1.0);
+                max_change = max_change.max((level - levels[i]).abs());
+                levels[i] = level;
+            }
+            if max_change < LEVEL_TOLERANCE {
+                break;
+            }
+        }
+
+        let mut allocation = Allocation {
+            levels,
+            force: Vector3::default(),
+            torque: Vector3::default(),
+        };
+        for (thruster, level) in self.thrusters.iter().zip(&allocation.levels) {
+            allocation.force += thruster.force() * *level;
+            allocation.torque += thruster.torque() * *level;
+        }
+        allocation
+    }
+    /// Sets the levels of `allocation` on `vessel` for the current time step with `SetThrusterLevel_SingleStep`
+    pub fn apply<V: VesselApi + ?Sized>(&self, vessel: &V, allocation: &Allocation) {
+        for (thruster, level) in self.thrusters.iter().zip(&allocation.levels) {
+            vessel.SetThrusterLevel_SingleStep(thruster.handle, *level);
+        }
+    }
+}
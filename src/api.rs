@@ -0,0 +1,341 @@
+//! This module defines the `VesselApi` trait covering the `VESSEL` methods bound by this crate
+#![allow(non_snake_case)]
+use std::ops::Deref;
+use std::pin::Pin;
+
+use crate::trace::RecordingVessel;
+use crate::{
+    Elements, Matrix3, OrbitParam, PropellantHandle, ReferenceFrame, ThrustGroupHandle,
+    ThrusterGroupType, ThrusterHandle, Vector3, VesselContext, VesselStatus, OBJHANDLE,
+};
+
+/// Interface to the Orbiter `VESSEL` API
+///
+/// The methods mirror the ones available on [VesselContext] and have the same names and semantics.
+/// Addon logic written against this trait instead of [SDKVessel](crate::SDKVessel) can be reused with
+/// other implementations such as test doubles, replay drivers or headless simulators.
+///
+/// The trait is implemented by [VesselContext], by [SDKVessel](crate::SDKVessel), by references to an
+/// implementation and by pinned pointers:
+///
+/// ```no_run
+/// use orbiter_rs::{FileHandle, OrbiterVessel, VesselApi, V};
+///
+/// pub struct Probe<V: VesselApi> {
+///     ctx: V,
+/// }
+/// impl<V: VesselApi> OrbiterVessel for Probe<V> {
+///     fn set_class_caps(&mut self, _cfg: &FileHandle) {
+///         self.ctx.SetSize(1.0);
+///         self.ctx.SetPMI(&V!(0.5, 0.5, 0.5));
+///     }
+/// }
+/// ```
+pub trait VesselApi {
+    /// Performs a transformation from local vessel coordinates to the ecliptic frame centered at the vessel's reference body
+    fn Local2Rel(&self, local: &Vector3, rel: &mut Vector3);
+    /// Performs a transformation from global (ecliptic) to local vessel coordinates
+    fn Global2Local(&self, global: &Vector3, local: &mut Vector3);
+    /// Performs a transformation from local vessel to global (ecliptic) coordinates
+    fn Local2Global(&self, local: &Vector3, global: &mut Vector3);
+
+    /// Set the vessel's mean radius
+    fn SetSize(&self, size: f64);
+    /// Set the vessel's mass-normalised principal moments of inertia (PMI)
+    fn SetPMI(&self, pmi: &Vector3);
+    /// Set the vessel's empty mass (excluding propellants)
+    fn SetEmptyMass(&self, empty_mass: f64);
+    /// Set the camera position for internal (cockpit) view.
+    fn SetCameraOffset(&self, camera_offset: &Vector3);
+    /// Defines the three points defining the vessel's ground contact plane
+    fn SetTouchdownPoints(&self, pt1: &Vector3, pt2: &Vector3, pt3: &Vector3);
+    /// Reset the force direction of a thruster
+    fn SetThrusterDir(&self, th: ThrusterHandle, dir: &Vector3);
+    /// Set thrust level for a thruster
+    fn SetThrusterLevel(&self, th: ThrusterHandle, level: f64);
+    /// Set the thrust level of a thruster for the current time step only
+    fn SetThrusterLevel_SingleStep(&self, th: ThrusterHandle, level: f64);
+    /// Set the thrust level for all thrusters of a default thruster group type
+    fn SetThrusterGroupLevelByType(&self, thgroup_type: ThrusterGroupType, level: f64);
+
+    /// Load a mesh definition for the vessel from a file
+    fn AddMesh(&self, mesh_name: String);
+    /// Load a mesh definition for the vessel from a file displaced by offset `ofs`
+    fn AddMeshWithOffset(&self, mesh_name: String, ofs: &Vector3);
+    /// Add an exhaust render definition for a thruster
+    fn AddExhaust(&self, th: ThrusterHandle, lscale: f64, wscale: f64) -> usize;
+    /// Remove all mesh definitions for the vessel
+    fn ClearMeshes(&self);
+
+    /// Create a new propellant resource ("fuel tank")
+    fn CreatePropellantResource(&self, mass: f64) -> PropellantHandle;
+    /// Add a logical thruster definition for the vessel
+    fn CreateThruster(
+        &self,
+        pos: &Vector3,
+        dir: &Vector3,
+        maxth0: f64,
+        ph: PropellantHandle,
+        isp: f64,
+    ) -> ThrusterHandle;
+    /// Combine thrusters into a logical thruster group
+    fn CreateThrusterGroup(
+        &self,
+        thrusters: &[ThrusterHandle],
+        thgroup_type: ThrusterGroupType,
+    ) -> ThrustGroupHandle;
+
+    /// Returns the vessel's name
+    fn GetName(&self) -> &str;
+    /// Returns the vessel's current status parameters in a [VesselStatus] structure
+    fn GetStatus(&self, status: &mut VesselStatus);
+    /// Returns the current mass of a propellant resource specified by `ph`
+    fn GetPropellantMass(&self, ph: PropellantHandle) -> f64;
+    /// Get angular velocity (in rad/s) of the spacecraft around its principal axes and store it in `a_vel`
+    fn GetAngularVel(&self, a_vel: &mut Vector3);
+    /// Returns the vessel's true "airspeed" vector in the requested frame of reference
+    fn GetAirspeedVector(&self, ref_frame: ReferenceFrame, airspeed: &mut Vector3) -> bool;
+    /// Returns thrust force vector in local vessel coordinates
+    fn GetThrustVector(&self, thrust_vec: &mut Vector3) -> bool;
+    /// Returns the vessel's current total propellant mass
+    fn GetTotalPropellantMass(&self) -> f64;
+    /// Returns the vessel's empty mass, excluding propellants
+    fn GetEmptyMass(&self) -> f64;
+    /// Returns the mean thrust level for a default thruster group type
+    fn GetThrusterGroupLevelByType(&self, thgroup_type: ThrusterGroupType) -> f64;
+    /// Returns the mean thrust level for a default thruster group specified by `th`
+    fn GetThrusterGroupLevel(&self, th: ThrustGroupHandle) -> f64;
+    /// Returns the maximum vacuum thrust rating of a thruster
+    fn GetThrusterMax0(&self, th: ThrusterHandle) -> f64;
+    /// Returns the vacuum fuel-specific impulse (ISP) rating of a thruster
+    fn GetThrusterIsp0(&self, th: ThrusterHandle) -> f64;
+    /// Returns a flag indicating contact with a planetary surface
+    fn GroundContact(&self) -> bool;
+    /// Returns a handle to the surface reference object (planet or moon)
+    fn GetSurfaceRef(&self) -> OBJHANDLE;
+    /// Returns the elevation of the surface at the vessel's current longitude/latitude above the reference radius
+    fn GetSurfaceElevation(&self) -> f64;
+    /// Returns altitude above mean ellipsoid
+    fn GetAltitude(&self) -> f64;
+    /// Returns the atmospheric temperature at the vessel's position, or 0 outside an atmosphere
+    fn GetAtmTemperature(&self) -> f64;
+    /// Returns the atmospheric density at the vessel's position, or 0 outside an atmosphere
+    fn GetAtmDensity(&self) -> f64;
+    /// Returns the static atmospheric pressure at the vessel's position, or 0 outside an atmosphere
+    fn GetAtmPressure(&self) -> f64;
+    /// Returns the vessel's current velocity relative to another object in the ecliptic frame
+    fn GetRelativeVel(&self, href: OBJHANDLE, rel_vel: &mut Vector3);
+    /// Returns the vessel's current longitude, latitude and distance from the centre of its reference body
+    fn GetEquPos(&self, longitude: &mut f64, latitude: &mut f64, radius: &mut f64);
+    /// Returns the vessel's airspeed vector in the local horizon frame (x east, y up, z north)
+    fn GetHorizonAirspeedVector(&self, airspeed: &mut Vector3) -> bool;
+    /// Returns the vessel's current rotation matrix for transforming from local vessel coordinates to the global frame
+    fn GetRotationMatrix(&self, r: &mut Matrix3);
+    /// Returns the vessel's rotation drag coefficients for rotation around its principal axes
+    fn GetRotDrag(&self, rd: &mut Vector3);
+    /// Returns the osculating orbital elements of the vessel relative to the body `href`
+    ///
+    /// `L` in `el` is the mean longitude at `mjd_ref`, or at the current simulation time if `mjd_ref` is 0.
+    /// `frame` is [FRAME_ECL](crate::FRAME_ECL) or [FRAME_EQU](crate::FRAME_EQU).
+    fn GetElements(
+        &self,
+        href: OBJHANDLE,
+        el: &mut Elements,
+        prm: &mut OrbitParam,
+        mjd_ref: f64,
+        frame: i32,
+    ) -> bool;
+    /// Moves the vessel onto the orbit around the body `href` described by `el`
+    fn SetElements(
+        &self,
+        href: OBJHANDLE,
+        el: &Elements,
+        prm: &mut OrbitParam,
+        mjd_ref: f64,
+        frame: i32,
+    ) -> bool;
+}
+
+/// Generates a `VesselApi` impl that forwards every method to `$($deref)* self`
+macro_rules! forward_vessel_api {
+    ($($deref:tt)*) => {
+        fn Local2Rel(&self, local: &Vector3, rel: &mut Vector3) {
+            ($($deref)* self).Local2Rel(local, rel)
+        }
+        fn Global2Local(&self, global: &Vector3, local: &mut Vector3) {
+            ($($deref)* self).Global2Local(global, local)
+        }
+        fn Local2Global(&self, local: &Vector3, global: &mut Vector3) {
+            ($($deref)* self).Local2Global(local, global)
+        }
+        fn SetSize(&self, size: f64) {
+            ($($deref)* self).SetSize(size)
+        }
+        fn SetPMI(&self, pmi: &Vector3) {
+            ($($deref)* self).SetPMI(pmi)
+        }
+        fn SetEmptyMass(&self, empty_mass: f64) {
+            ($($deref)* self).SetEmptyMass(empty_mass)
+        }
+        fn SetCameraOffset(&self, camera_offset: &Vector3) {
+            ($($deref)* self).SetCameraOffset(camera_offset)
+        }
+        fn SetTouchdownPoints(&self, pt1: &Vector3, pt2: &Vector3, pt3: &Vector3) {
+            ($($deref)* self).SetTouchdownPoints(pt1, pt2, pt3)
+        }
+        fn SetThrusterDir(&self, th: ThrusterHandle, dir: &Vector3) {
+            ($($deref)* self).SetThrusterDir(th, dir)
+        }
+        fn SetThrusterLevel(&self, th: ThrusterHandle, level: f64) {
+            ($($deref)* self).SetThrusterLevel(th, level)
+        }
+        fn SetThrusterLevel_SingleStep(&self, th: ThrusterHandle, level: f64) {
+            ($($deref)* self).SetThrusterLevel_SingleStep(th, level)
+        }
+        fn SetThrusterGroupLevelByType(&self, thgroup_type: ThrusterGroupType, level: f64) {
+            ($($deref)* self).SetThrusterGroupLevelByType(thgroup_type, level)
+        }
+        fn AddMesh(&self, mesh_name: String) {
+            ($($deref)* self).AddMesh(mesh_name)
+        }
+        fn AddMeshWithOffset(&self, mesh_name: String, ofs: &Vector3) {
+            ($($deref)* self).AddMeshWithOffset(mesh_name, ofs)
+        }
+        fn AddExhaust(&self, th: ThrusterHandle, lscale: f64, wscale: f64) -> usize {
+            ($($deref)* self).AddExhaust(th, lscale, wscale)
+        }
+        fn ClearMeshes(&self) {
+            ($($deref)* self).ClearMeshes()
+        }
+        fn CreatePropellantResource(&self, mass: f64) -> PropellantHandle {
+            ($($deref)* self).CreatePropellantResource(mass)
+        }
+        fn CreateThruster(
+            &self,
+            pos: &Vector3,
+            dir: &Vector3,
+            maxth0: f64,
+            ph: PropellantHandle,
+            isp: f64,
+        ) -> ThrusterHandle {
+            ($($deref)* self).CreateThruster(pos, dir, maxth0, ph, isp)
+        }
+        fn CreateThrusterGroup(
+            &self,
+            thrusters: &[ThrusterHandle],
+            thgroup_type: ThrusterGroupType,
+        ) -> ThrustGroupHandle {
+            ($($deref)* self).CreateThrusterGroup(thrusters, thgroup_type)
+        }
+        fn GetName(&self) -> &str {
+            ($($deref)* self).GetName()
+        }
+        fn GetStatus(&self, status: &mut VesselStatus) {
+            ($($deref)* self).GetStatus(status)
+        }
+        fn GetPropellantMass(&self, ph: PropellantHandle) -> f64 {
+            ($($deref)* self).GetPropellantMass(ph)
+        }
+        fn GetAngularVel(&self, a_vel: &mut Vector3) {
+            ($($deref)* self).GetAngularVel(a_vel)
+        }
+        fn GetAirspeedVector(&self, ref_frame: ReferenceFrame, airspeed: &mut Vector3) -> bool {
+            ($($deref)* self).GetAirspeedVector(ref_frame, airspeed)
+        }
+        fn GetThrustVector(&self, thrust_vec: &mut Vector3) -> bool {
+            ($($deref)* self).GetThrustVector(thrust_vec)
+        }
+        fn GetTotalPropellantMass(&self) -> f64 {
+            ($($deref)* self).GetTotalPropellantMass()
+        }
+        fn GetEmptyMass(&self) -> f64 {
+            ($($deref)* self).GetEmptyMass()
+        }
+        fn GetThrusterGroupLevelByType(&self, thgroup_type: ThrusterGroupType) -> f64 {
+            ($($deref)* self).GetThrusterGroupLevelByType(thgroup_type)
+        }
+        fn GetThrusterGroupLevel(&self, th: ThrustGroupHandle) -> f64 {
+            ($($deref)* self).GetThrusterGroupLevel(th)
+        }
+        fn GetThrusterMax0(&self, th: ThrusterHandle) -> f64 {
+            ($($deref)* self).GetThrusterMax0(th)
+        }
+        fn GetThrusterIsp0(&self, th: ThrusterHandle) -> f64 {
+            ($($deref)* self).GetThrusterIsp0(th)
+        }
+        fn GroundContact(&self) -> bool {
+            ($($deref)* self).GroundContact()
+        }
+        fn GetSurfaceRef(&self) -> OBJHANDLE {
+            ($($deref)* self).GetSurfaceRef()
+        }
+        fn GetSurfaceElevation(&self) -> f64 {
+            ($($deref)* self).GetSurfaceElevation()
+        }
+        fn GetAltitude(&self) -> f64 {
+            ($($deref)* self).GetAltitude()
+        }
+        fn GetAtmTemperature(&self) -> f64 {
+            ($($deref)* self).GetAtmTemperature()
+        }
+        fn GetAtmDensity(&self) -> f64 {
+            ($($deref)* self).GetAtmDensity()
+        }
+        fn GetAtmPressure(&self) -> f64 {
+            ($($deref)* self).GetAtmPressure()
+        }
+        fn GetRelativeVel(&self, href: OBJHANDLE, rel_vel: &mut Vector3) {
+            ($($deref)* self).GetRelativeVel(href, rel_vel)
+        }
+        fn GetEquPos(&self, longitude: &mut f64, latitude: &mut f64, radius: &mut f64) {
+            ($($deref)* self).GetEquPos(longitude, latitude, radius)
+        }
+        fn GetHorizonAirspeedVector(&self, airspeed: &mut Vector3) -> bool {
+            ($($deref)* self).GetHorizonAirspeedVector(airspeed)
+        }
+        fn GetRotationMatrix(&self, r: &mut Matrix3) {
+            ($($deref)* self).GetRotationMatrix(r)
+        }
+        fn GetRotDrag(&self, rd: &mut Vector3) {
+            ($($deref)* self).GetRotDrag(rd)
+        }
+        fn GetElements(
+            &self,
+            href: OBJHANDLE,
+            el: &mut Elements,
+            prm: &mut OrbitParam,
+            mjd_ref: f64,
+            frame: i32,
+        ) -> bool {
+            ($($deref)* self).GetElements(href, el, prm, mjd_ref, frame)
+        }
+        fn SetElements(
+            &self,
+            href: OBJHANDLE,
+            el: &Elements,
+            prm: &mut OrbitParam,
+            mjd_ref: f64,
+            frame: i32,
+        ) -> bool {
+            ($($deref)* self).SetElements(href, el, prm, mjd_ref, frame)
+        }
+    };
+}
+
+// Inherent methods take precedence over trait methods, so these call into the bindings and the recorder
+impl VesselApi for VesselContext {
+    forward_vessel_api!();
+}
+impl<V: VesselApi> VesselApi for RecordingVessel<V> {
+    forward_vessel_api!();
+}
+impl<T: VesselApi + ?Sized> VesselApi for &T {
+    forward_vessel_api!(**);
+}
+impl<P> VesselApi for Pin<P>
+where
+    P: Deref,
+    P::Target: VesselApi,
+{
+    forward_vessel_api!(**);
+}
@@ -0,0 +1,360 @@
+//! Atmosphere models of Earth, Mars and Venus
+//!
+//! An [AtmosphereModel] gives the [AtmConditions] (temperature, pressure, density and speed of sound) at an
+//! altitude above the mean radius of a planet, or `None` above the top of its atmosphere:
+//!
+//! - [UsStandard1976] is the U.S. Standard Atmosphere 1976 for Earth.
+//! - [MarsAtmosphere] is the Mars model of NASA Glenn Research Center, fitted to Mars Global Surveyor data.
+//! - [Exponential] is an isothermal atmosphere with a constant scale height, set up from the surface
+//!   parameters of Orbiter's planet configuration files, e.g. [Exponential::VENUS].
+//!
+//! [VesselAtmosphere] is the atmosphere simulated by Orbiter around a vessel. At the vessel's altitude, it
+//! reads the conditions through `GetAtmTemperature`, `GetAtmPressure` and `GetAtmDensity`, and falls back to
+//! a model elsewhere. Outside of Orbiter, the [Harness](crate::harness::Harness) feeds these getters from a
+//! model, so the same code can be tested against the models:
+//!
+//! ```
+//! use orbiter_rs::atmosphere::{AtmosphereModel, UsStandard1976, VesselAtmosphere};
+//! use orbiter_rs::harness::Harness;
+//! use orbiter_rs::{Body, FileHandle, OrbiterVessel, SDKVessel, V};
+//!
+//! let sea_level = UsStandard1976.conditions(0.0).unwrap();
+//! assert!((sea_level.density - 1.225).abs() < 1e-3);
+//! assert!((sea_level.speed_of_sound - 340.3).abs() < 0.1);
+//!
+//! struct Capsule {
+//!     ctx: SDKVessel,
+//! }
+//! impl OrbiterVessel for Capsule {
+//!     fn set_class_caps(&mut self, _cfg: &FileHandle) {
+//!         self.ctx.SetEmptyMass(1000.0);
+//!     }
+//! }
+//! let mut harness = Harness::new("Capsule", Body::EARTH, |vessel| Capsule { ctx: vessel });
+//! harness.set_atmosphere(UsStandard1976);
+//! harness.set_position(V!(Body::EARTH.radius + 11e3, 0.0, 0.0));
+//!
+//! let atmosphere = VesselAtmosphere::new(&harness.vessel().ctx, UsStandard1976);
+//! let conditions = atmosphere.at_vessel().unwrap();
+//! assert!((conditions.temperature - 216.77).abs() < 0.01);
+//! assert!((conditions.pressure - 22700.0).abs() < 10.0);
+//! assert_eq!(atmosphere.conditions(11e3), Some(conditions));
+//!
+//! // Nothing above the top of the atmosphere
+//! harness.set_position(V!(Body::EARTH.radius + 2000e3, 0.0, 0.0));
+//! assert_eq!(VesselAtmosphere::new(&harness.vessel().ctx, UsStandard1976).at_vessel(), None);
+//! ```
+use crate::VesselApi;
+
+/// Standard gravity used by the U.S. Standard Atmosphere \[**m/s²**\]
+const G0: f64 = 9.80665;
+
+/// Atmospheric conditions at a given point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmConditions {
+    /// Temperature \[**K**\]
+    pub temperature: f64,
+    /// Static pressure \[**Pa**\]
+    pub pressure: f64,
+    /// Density \[**kg/m³**\]
+    pub density: f64,
+    /// Speed of sound \[**m/s**\]
+    pub speed_of_sound: f64,
+}
+
+impl AtmConditions {
+    /// Build the conditions from the temperature, pressure and density of a gas with a ratio of specific
+    /// heats `gamma`
+    pub fn new(temperature: f64, pressure: f64, density: f64, gamma: f64) -> Self {
+        Self {
+            temperature,
+            pressure,
+            density,
+            speed_of_sound: (gamma * pressure / density).sqrt(),
+        }
+    }
+    /// Returns the dynamic pressure at `airspeed` \[**Pa**\]
+    pub fn dynamic_pressure(&self, airspeed: f64) -> f64 {
+        0.5 * self.density * airspeed * airspeed
+    }
+    /// Returns the Mach number at `airspeed`
+    pub fn mach(&self, airspeed: f64) -> f64 {
+        airspeed / self.speed_of_sound
+    }
+}
+
+/// Atmospheric conditions as a function of altitude
+pub trait AtmosphereModel {
+    /// Returns the conditions at `altitude` above the mean radius \[**m**\], or `None` above the top of the
+    /// atmosphere
+    fn conditions(&self, altitude: f64) -> Option<AtmConditions>;
+    /// Returns the ratio of specific heats of the gas, which sets the speed of sound
+    fn gamma(&self) -> f64;
+}
+
+/// Isothermal atmosphere whose pressure and density fall off exponentially with altitude
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    /// Pressure at zero altitude \[**Pa**\]
+    pub surface_pressure: f64,
+    /// Density at zero altitude \[**kg/m³**\]
+    pub surface_density: f64,
+    /// Altitude over which pressure and density fall by a factor `e` \[**m**\]
+    pub scale_height: f64,
+    /// Specific gas constant \[**J/(kg K)**\]
+    pub gas_constant: f64,
+    /// Ratio of specific heats
+    pub gamma: f64,
+    /// Top of the atmosphere \[**m**\]
+    pub altitude_limit: f64,
+}
+
+impl Exponential {
+    /// Earth, from `AtmPressure0`, `AtmDensity0`, `AtmGasConstant` and `AtmGamma` in Orbiter's `Earth.cfg`
+    pub const EARTH: Self = Self::new(101.4e3, 1.293, 7.99e3, 286.91, 1.4, 200e3);
+    /// Mars, from Orbiter's `Mars.cfg`
+    pub const MARS: Self = Self::new(610.0, 0.02, 8.23e3, 188.92, 1.2941, 100e3);
+    /// Venus, from Orbiter's `Venus.cfg`
+    pub const VENUS: Self = Self::new(9.2e6, 65.0, 15.95e3, 188.92, 1.2857, 200e3);
+
+    /// Build a new `Exponential` atmosphere
+    pub const fn new(
+        surface_pressure: f64,
+        surface_density: f64,
+        scale_height: f64,
+        gas_constant: f64,
+        gamma: f64,
+        altitude_limit: f64,
+    ) -> Self {
+        Self {
+            surface_pressure,
+            surface_density,
+            scale_height,
+            gas_constant,
+            gamma,
+            altitude_limit,
+        }
+    }
+    /// Returns the temperature of the atmosphere, from the surface pressure and density \[**K**\]
+    pub fn temperature(&self) -> f64 {
+        self.surface_pressure / (self.surface_density * self.gas_constant)
+    }
+}
+
+impl AtmosphereModel for Exponential {
+    fn conditions(&self, altitude: f64) -> Option<AtmConditions> {
+        if altitude > self.altitude_limit {
+            return None;
+        }
+        let factor = (-altitude / self.scale_height).exp();
+        Some(AtmConditions::new(
+            self.temperature(),
+            self.surface_pressure * factor,
+            self.surface_density * factor,
+            self.gamma,
+        ))
+    }
+    fn gamma(&self) -> f64 {
+        self.gamma
+    }
+}
+
+/// The U.S. Standard Atmosphere 1976 for Earth, up to 1000 km
+///
+/// Below 86 km, the conditions follow the layers of constant temperature gradient of the standard. Above,
+/// the temperature follows the thermosphere profile of the standard and the density a piecewise exponential
+/// fit to its tables (Vallado, *Fundamentals of Astrodynamics and Applications*, table 8-4). The pressure
+/// there is derived from the density and temperature with the molecular weight of air at sea level, which
+/// overestimates it above about 100 km where lighter species dominate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsStandard1976;
+
+impl UsStandard1976 {
+    /// Specific gas constant of air \[**J/(kg K)**\]
+    pub const GAS_CONSTANT: f64 = 287.053;
+    /// Ratio of specific heats of air
+    pub const GAMMA: f64 = 1.4;
+    /// Top of the atmosphere \[**m**\]
+    pub const ALTITUDE_LIMIT: f64 = 1000e3;
+    /// Radius of the Earth used for geopotential altitudes \[**m**\]
+    const RADIUS: f64 = 6356766.0;
+    /// Sea-level pressure \[**Pa**\]
+    const SEA_LEVEL_PRESSURE: f64 = 101325.0;
+    /// Geopotential altitude of the base of each layer below 86 km \[**m**\] and temperature gradient \[**K/m**\]
+    const LAYERS: [(f64, f64); 7] = [
+        (0.0, -0.0065),
+        (11000.0, 0.0),
+        (20000.0, 0.001),
+        (32000.0, 0.0028),
+        (47000.0, 0.0),
+        (51000.0, -0.0028),
+        (71000.0, -0.002),
+    ];
+    /// Geometric altitude of the top of the lower atmosphere \[**m**\]
+    const LOWER_LIMIT: f64 = 86e3;
+    /// Base altitude \[**km**\], base density \[**kg/m³**\] and scale height \[**km**\] above 86 km
+    const UPPER_DENSITY: [(f64, f64, f64); 20] = [
+        (80.0, 1.905e-5, 5.799),
+        (90.0, 3.396e-6, 5.382),
+        (100.0, 5.297e-7, 5.877),
+        (110.0, 9.661e-8, 7.263),
+        (120.0, 2.438e-8, 9.473),
+        (130.0, 8.484e-9, 12.636),
+        (140.0, 3.845e-9, 16.149),
+        (150.0, 2.070e-9, 22.523),
+        (180.0, 5.464e-10, 29.740),
+        (200.0, 2.789e-10, 37.105),
+        (250.0, 7.248e-11, 45.546),
+        (300.0, 2.418e-11, 53.628),
+        (350.0, 9.518e-12, 53.298),
+        (400.0, 3.725e-12, 58.515),
+        (450.0, 1.585e-12, 60.828),
+        (500.0, 6.967e-13, 63.822),
+        (600.0, 1.454e-13, 71.835),
+        (700.0, 3.614e-14, 88.667),
+        (800.0, 1.170e-14, 124.64),
+        (900.0, 5.245e-15, 181.05),
+    ];
+
+    /// Temperature \[**K**\] and pressure \[**Pa**\] below 86 km at the geopotential altitude `h` \[**m**\]
+    fn lower(h: f64) -> (f64, f64) {
+        let (mut base_temperature, mut base_pressure) = (288.15, Self::SEA_LEVEL_PRESSURE);
+        for (i, &(base, gradient)) in Self::LAYERS.iter().enumerate() {
+            let top = Self::LAYERS.get(i + 1).map_or(f64::INFINITY, |layer| layer.0);
+            let dh = h.min(top) - base;
+            let temperature = base_temperature + gradient * dh;
+            let pressure = if gradient == 0.0 {
+                base_pressure * (-G0 * dh / (Self::GAS_CONSTANT * base_temperature)).exp()
+            } else {
+                base_pressure * (base_temperature / temperature).powf(G0 / (Self::GAS_CONSTANT * gradient))
+            };
+            if h <= top {
+                return (temperature, pressure);
+            }
+            (base_temperature, base_pressure) = (temperature, pressure);
+        }
+        (base_temperature, base_pressure)
+    }
+    /// Temperature above 86 km at the geometric altitude `z` \[**m**\]
+    fn upper_temperature(z: f64) -> f64 {
+        let z = z / 1e3;
+        if z < 91.0 {
+            186.8673
+        } else if z < 110.0 {
+            let x = (z - 91.0) / -19.9429;
+            263.1905 - 76.3232 * (1.0 - x * x).sqrt()
+        } else if z < 120.0 {
+            240.0 + 12.0 * (z - 110.0)
+        } else {
+            let r = Self::RADIUS / 1e3;
+            let xi = (z - 120.0) * (r + 120.0) / (r + z);
+            1000.0 - 640.0 * (-0.01875 * xi).exp()
+        }
+    }
+    /// Density above 86 km at the geometric altitude `z` \[**m**\]
+    fn upper_density(z: f64) -> f64 {
+        let z = z / 1e3;
+        let &(base, density, scale_height) = Self::UPPER_DENSITY
+            .iter()
+            .rev()
+            .find(|(base, _, _)| z >= *base)
+            .unwrap_or(&Self::UPPER_DENSITY[0]);
+        density * (-(z - base) / scale_height).exp()
+    }
+}
+
+impl AtmosphereModel for UsStandard1976 {
+    fn conditions(&self, altitude: f64) -> Option<AtmConditions> {
+        if altitude > Self::ALTITUDE_LIMIT {
+            return None;
+        }
+        if altitude < Self::LOWER_LIMIT {
+            let geopotential = Self::RADIUS * altitude / (Self::RADIUS + altitude);
+            let (temperature, pressure) = Self::lower(geopotential);
+            let density = pressure / (Self::GAS_CONSTANT * temperature);
+            return Some(AtmConditions::new(temperature, pressure, density, Self::GAMMA));
+        }
+        let temperature = Self::upper_temperature(altitude);
+        let density = Self::upper_density(altitude);
+        let pressure = density * Self::GAS_CONSTANT * temperature;
+        Some(AtmConditions::new(temperature, pressure, density, Self::GAMMA))
+    }
+    fn gamma(&self) -> f64 {
+        Self::GAMMA
+    }
+}
+
+/// The Mars atmosphere model of NASA Glenn Research Center, up to 100 km
+///
+/// The temperature falls linearly with altitude at two different rates below and above 7 km, and the
+/// pressure falls exponentially from 699 Pa at zero altitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarsAtmosphere;
+
+impl MarsAtmosphere {
+    /// Specific gas constant of the Martian atmosphere, as `AtmGasConstant` in Orbiter's `Mars.cfg` \[**J/(kg K)**\]
+    pub const GAS_CONSTANT: f64 = 188.92;
+    /// Ratio of specific heats, as `AtmGamma` in Orbiter's `Mars.cfg`
+    pub const GAMMA: f64 = 1.2941;
+    /// Top of the atmosphere \[**m**\]
+    pub const ALTITUDE_LIMIT: f64 = 100e3;
+}
+
+impl AtmosphereModel for MarsAtmosphere {
+    fn conditions(&self, altitude: f64) -> Option<AtmConditions> {
+        if altitude > Self::ALTITUDE_LIMIT {
+            return None;
+        }
+        let celsius = if altitude < 7000.0 {
+            -31.0 - 0.000998 * altitude
+        } else {
+            -23.4 - 0.00222 * altitude
+        };
+        let temperature = celsius + 273.15;
+        let pressure = 699.0 * (-0.00009 * altitude).exp();
+        let density = pressure / (Self::GAS_CONSTANT * temperature);
+        Some(AtmConditions::new(temperature, pressure, density, Self::GAMMA))
+    }
+    fn gamma(&self) -> f64 {
+        Self::GAMMA
+    }
+}
+
+/// The atmosphere simulated by Orbiter around a vessel
+///
+/// At the vessel's current altitude (`GetAltitude`), the conditions are Orbiter's, with the speed of sound
+/// computed from the ratio of specific heats of `model`. At any other altitude, they are the conditions of
+/// `model`, which should describe the atmosphere of the body the vessel is flying in.
+pub struct VesselAtmosphere<'a, V: ?Sized, M> {
+    vessel: &'a V,
+    model: M,
+}
+
+impl<'a, V: VesselApi + ?Sized, M: AtmosphereModel> VesselAtmosphere<'a, V, M> {
+    /// Build the atmosphere around `vessel`, falling back to `model` away from it
+    pub fn new(vessel: &'a V, model: M) -> Self {
+        Self { vessel, model }
+    }
+    /// Returns the conditions at the vessel's position, or `None` outside the atmosphere
+    pub fn at_vessel(&self) -> Option<AtmConditions> {
+        let density = self.vessel.GetAtmDensity();
+        if density <= 0.0 {
+            return None;
+        }
+        let (temperature, pressure) = (self.vessel.GetAtmTemperature(), self.vessel.GetAtmPressure());
+        Some(AtmConditions::new(temperature, pressure, density, self.gamma()))
+    }
+}
+
+impl<V: VesselApi + ?Sized, M: AtmosphereModel> AtmosphereModel for VesselAtmosphere<'_, V, M> {
+    fn conditions(&self, altitude: f64) -> Option<AtmConditions> {
+        if altitude == self.vessel.GetAltitude() {
+            self.at_vessel()
+        } else {
+            self.model.conditions(altitude)
+        }
+    }
+    fn gamma(&self) -> f64 {
+        self.model.gamma()
+    }
+}
@@ -0,0 +1,316 @@
+//! Attitude control with the default rotational thruster groups
+//!
+//! [AttitudeController] turns the attitude error of the vessel and its angular velocity into rotation levels
+//! for the `Att*` thruster groups, with either a [Pid] controller per axis or a [BangBang] controller with a
+//! deadband. The attitude is held in one of the [AttitudeMode]s, or the rotation is simply damped with
+//! [AttitudeMode::KillRotation].
+//!
+//! Rotations follow the convention of [GetAngularVel](crate::VesselContext::GetAngularVel) in Orbiter's
+//! left-handed frames: a positive rotation level about `x` pitches up, about `y` yaws left and about `z`
+//! banks right.
+//!
+//! ```
+//! use orbiter_rs::attitude::{AttitudeController, AttitudeMode, ControlLaw, VesselAttitude};
+//! use orbiter_rs::harness::Harness;
+//! use orbiter_rs::{Body, FileHandle, OrbiterVessel, SDKVessel, ThrusterGroupType, V};
+//!
+//! struct Probe {
+//!     ctx: SDKVessel,
+//!     controller: AttitudeController,
+//! }
+//! impl OrbiterVessel for Probe {
+//!     fn set_class_caps(&mut self, _cfg: &FileHandle) {
+//!         self.ctx.SetEmptyMass(100.0);
+//!         self.ctx.SetPMI(&V!(1.0, 1.0, 1.0));
+//!         let ph = self.ctx.CreatePropellantResource(10.0);
+//!         // A couple of 10 N thrusters 1 m from the centre of mass for each rotation group
+//!         let (x, y, z) = (V!(1.0, 0.0, 0.0), V!(0.0, 1.0, 0.0), V!(0.0, 0.0, 1.0));
+//!         for (group, pos, dir) in [
+//!             (ThrusterGroupType::AttPitchup, z, y),
+//!             (ThrusterGroupType::AttPitchdown, z, -y),
+//!             (ThrusterGroupType::AttYawleft, z, -x),
+//!             (ThrusterGroupType::AttYawright, z, x),
+//!             (ThrusterGroupType::AttBankleft, x, y),
+//!             (ThrusterGroupType::AttBankright, x, -y),
+//!         ] {
+//!             let th = [
+//!                 self.ctx.CreateThruster(&pos, &dir, 10.0, ph, 2000.0),
+//!                 self.ctx.CreateThruster(&-pos, &-dir, 10.0, ph, 2000.0),
+//!             ];
+//!             self.ctx.CreateThrusterGroup(&th, group);
+//!         }
+//!     }
+//!     fn on_pre_step(&mut self, _sim_t: f64, sim_dt: f64, _mjd: f64) {
+//!         self.ctx.update_attitude(&mut self.controller, sim_dt);
+//!     }
+//! }
+//!
+//! let mut harness = Harness::new("Probe", Body::EARTH, |vessel| Probe {
+//!     ctx: vessel,
+//!     controller: AttitudeController::new(AttitudeMode::Prograde, ControlLaw::pid(1.5, 0.0, 3.0)),
+//! });
+//! let radius = Body::EARTH.radius + 300e3;
+//! harness.set_position(V!(radius, 0.0, 0.0));
+//! harness.set_velocity(V!(0.0, 0.0, (Body::EARTH.gm() / radius).sqrt()));
+//! harness.set_angular_vel(V!(0.05, -0.1, 0.2));
+//! harness.run(60.0, 0.1);
+//!
+//! // The nose points prograde and the vessel only turns with the orbit, at about 0.0012 rad/s
+//! let nose = harness.state().rotation * V!(0.0, 0.0, 1.0);
+//! assert!(nose.dot(&harness.state().vel.unit()) > 1.0f64.to_radians().cos());
+//! assert!(harness.state().angular_vel.length() < 2e-3);
+//! ```
+use crate::frame::{Local, Vec3, VesselFrames};
+use crate::maneuver::VesselManeuver;
+use crate::{ThrusterGroupType, VesselApi};
+
+/// Thruster groups rotating the vessel in the positive and negative sense about its `x`, `y` and `z` axes
+const ROTATION_GROUPS: [(ThrusterGroupType, ThrusterGroupType); 3] = [
+    (ThrusterGroupType::AttPitchup, ThrusterGroupType::AttPitchdown),
+    (ThrusterGroupType::AttYawleft, ThrusterGroupType::AttYawright),
+    (ThrusterGroupType::AttBankright, ThrusterGroupType::AttBankleft),
+];
+
+/// Proportional-integral-derivative controller for a single axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pid {
+    /// Proportional gain
+    pub kp: f64,
+    /// Integral gain
+    pub ki: f64,
+    /// Derivative gain
+    pub kd: f64,
+    /// Bound on the magnitude of the integrated error, to limit windup
+    pub integral_limit: f64,
+    integral: f64,
+}
+
+impl Pid {
+    /// Build a new `Pid` from its gains, with an unbounded integral term
+    pub const fn new(kp: f64, ki: f64, kd: f64) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            integral_limit: f64::INFINITY,
+            integral: 0.0,
+        }
+    }
+    /// Returns the same controller with the integrated error bounded by `limit`
+    pub const fn with_integral_limit(mut self, limit: f64) -> Self {
+        self.integral_limit = limit;
+        self
+    }
+    /// Returns the output for `error` over a step of `dt` seconds
+    ///
+    /// The derivative term uses the measured `error_rate` rather than differencing the error, so that
+    /// steps in the setpoint do not produce spikes.
+    pub fn update(&mut self, error: f64, error_rate: f64, dt: f64) -> f64 {
+        self.integral = (self.integral + error * dt).clamp(-self.integral_limit, self.integral_limit);
+        self.kp * error + self.ki * self.integral + self.kd * error_rate
+    }
+    /// Clears the integrated error
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+    }
+}
+
+/// On-off controller switching on a linear combination of error and error rate, with a deadband
+///
+/// The output is `±level` depending on the sign of `error + time_constant * error_rate`, and zero while its
+/// magnitude stays within `deadband`. With a zero error, as when killing rotation, the rate is brought
+/// within `deadband / time_constant`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BangBang {
+    /// Half-width of the band in which no output is commanded \[**rad**\]
+    pub deadband: f64,
+    /// Weight of the error rate in the switching function \[**s**\]
+    pub time_constant: f64,
+    /// Magnitude of the output outside the deadband, between 0 and 1
+    pub level: f64,
+}
+
+impl BangBang {
+    /// Build a new `BangBang` commanding full level outside the deadband
+    pub const fn new(deadband: f64, time_constant: f64) -> Self {
+        Self {
+            deadband,
+            time_constant,
+            level: 1.0,
+        }
+    }
+    /// Returns the output for `error` changing at `error_rate`
+    pub fn output(&self, error: f64, error_rate: f64) -> f64 {
+        let switch = error + self.time_constant * error_rate;
+        if switch.abs() <= self.deadband {
+            0.0
+        } else {
+            self.level.copysign(switch)
+        }
+    }
+}
+
+/// Control law turning the attitude error about each axis into a rotation level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlLaw {
+    /// A PID controller for each of the `x`, `y` and `z` axes
+    Pid([Pid; 3]),
+    /// The same bang-bang controller on every axis
+    BangBang(BangBang),
+}
+
+impl ControlLaw {
+    /// PID control with the same gains on every axis
+    pub const fn pid(kp: f64, ki: f64, kd: f64) -> Self {
+        Self::Pid([Pid::new(kp, ki, kd); 3])
+    }
+    /// Bang-bang control with the same deadband and time constant on every axis
+    pub const fn bang_bang(deadband: f64, time_constant: f64) -> Self {
+        Self::BangBang(BangBang::new(deadband, time_constant))
+    }
+}
+
+/// Attitude to hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttitudeMode {
+    /// Damp the rotation without holding any attitude
+    KillRotation,
+    /// Point the nose (`+z`) along the orbital velocity
+    Prograde,
+    /// Point the nose against the orbital velocity
+    Retrograde,
+    /// Point the nose along the orbit normal (the angular momentum vector)
+    Normal,
+    /// Keep the vessel's `+y` axis along the local vertical, i.e. zero pitch and bank
+    HorizonLevel,
+}
+
+/// Holds an [AttitudeMode] by commanding rotation levels with a [ControlLaw]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttitudeController {
+    /// Attitude to hold
+    pub mode: AttitudeMode,
+    /// Control law used on each axis
+    pub law: ControlLaw,
+}
+
+impl AttitudeController {
+    /// Build a new `AttitudeController`
+    pub const fn new(mode: AttitudeMode, law: ControlLaw) -> Self {
+        Self { mode, law }
+    }
+    /// Switches to `mode`, clearing the integrated errors of PID control
+    pub fn set_mode(&mut self, mode: AttitudeMode) {
+        self.mode = mode;
+        if let ControlLaw::Pid(pids) = &mut self.law {
+            pids.iter_mut().for_each(Pid::reset);
+        }
+    }
+    /// Returns the rotation levels, between -1 and 1, correcting the attitude `error` while rotating at
+    /// `angular_vel` over a step of `dt` seconds
+    ///
+    /// `error` is the rotation taking the vessel to the target attitude, e.g. from [pointing_error] or
+    /// [VesselAttitude::attitude_error]. The error is assumed to change at `-angular_vel`, i.e. the
+    /// target attitude moves slowly compared to the vessel.
+    pub fn command(&mut self, error: &Vec3<Local>, angular_vel: &Vec3<Local>, dt: f64) -> Vec3<Local> {
+        let error = error.vector().0;
+        let rate = angular_vel.vector().0;
+        let levels: [f64; 3] = match &mut self.law {
+            ControlLaw::Pid(pids) => std::array::from_fn(|i| pids[i].update(error[i], -rate[i], dt)),
+            ControlLaw::BangBang(bang_bang) => std::array::from_fn(|i| bang_bang.output(error[i], -rate[i])),
+        };
+        let [x, y, z] = levels.map(|level| level.clamp(-1.0, 1.0));
+        Vec3::new(x, y, z)
+    }
+}
+
+/// Returns the rotation taking the nose of the vessel (`+z`) onto `target` in the local vessel frame
+///
+/// The result has no component about `z`, as pointing the nose leaves the bank angle free. Its length is
+/// the angle between the nose and `target` \[**rad**\].
+///
+/// ```
+/// use orbiter_rs::attitude::pointing_error;
+/// use orbiter_rs::frame::Vec3;
+///
+/// // A target above the nose needs a positive pitch
+/// let error = pointing_error(&Vec3::new(0.0, 1.0, 1.0));
+/// assert!((error - Vec3::new(std::f64::consts::FRAC_PI_4, 0.0, 0.0)).length() < 1e-12);
+/// ```
+pub fn pointing_error(target: &Vec3<Local>) -> Vec3<Local> {
+    axis_error(target, &Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 0.0))
+}
+
+/// Returns the rotation taking the vessel's `+y` axis onto `up` in the local vessel frame
+///
+/// The result has no component about `y`, as levelling the vessel leaves the heading free.
+pub fn level_error(up: &Vec3<Local>) -> Vec3<Local> {
+    axis_error(up, &Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0))
+}
+
+/// Rotation taking `axis` onto `target`, about `fallback` when they are opposite
+fn axis_error(target: &Vec3<Local>, axis: &Vec3<Local>, fallback: Vec3<Local>) -> Vec3<Local> {
+    let target = target.unit();
+    // Orbiter's frames are left-handed, so the rotation is about target × axis
+    let normal = target.cross(axis);
+    let angle = normal.length().atan2(target.dot(axis));
+    if normal.length() > 1e-12 {
+        normal * (angle / normal.length())
+    } else {
+        fallback * angle
+    }
+}
+
+/// Attitude control through the default rotational thruster groups
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselAttitude: VesselApi {
+    /// Returns the current levels of the rotational thruster groups, e.g. as set from the keyboard, as
+    /// the difference of the opposing groups about each axis
+    fn rotation_levels(&self) -> Vec3<Local> {
+        let [x, y, z] = ROTATION_GROUPS.map(|(positive, negative)| {
+            self.GetThrusterGroupLevelByType(positive) - self.GetThrusterGroupLevelByType(negative)
+        });
+        Vec3::new(x, y, z)
+    }
+    /// Sets the levels of the rotational thruster groups, firing the group matching the sign of the level
+    /// about each axis and shutting down the opposing one
+    fn set_rotation_levels(&self, levels: &Vec3<Local>) {
+        for ((positive, negative), level) in ROTATION_GROUPS.into_iter().zip(levels.vector().0) {
+            self.SetThrusterGroupLevelByType(positive, level.max(0.0));
+            self.SetThrusterGroupLevelByType(negative, (-level).max(0.0));
+        }
+    }
+    /// Returns the rotation taking the vessel to the attitude of `mode`
+    ///
+    /// Returns `None` if the target attitude is undefined, e.g. [AttitudeMode::Prograde] with no velocity
+    /// relative to the reference body.
+    fn attitude_error(&self, mode: AttitudeMode) -> Option<Vec3<Local>> {
+        let (pos, vel) = self.orbit_state();
+        let target = match mode {
+            AttitudeMode::KillRotation => return Some(Vec3::default()),
+            AttitudeMode::Prograde => vel,
+            AttitudeMode::Retrograde => -vel,
+            // Orbiter's frame is left-handed, so the angular momentum is v × r
+            AttitudeMode::Normal => vel.cross(&pos),
+            AttitudeMode::HorizonLevel => pos,
+        };
+        if target.length() == 0.0 {
+            return None;
+        }
+        let local = self.global_dir_to_local(&Vec3::from_vector(target));
+        Some(match mode {
+            AttitudeMode::HorizonLevel => level_error(&local),
+            _ => pointing_error(&local),
+        })
+    }
+    /// Runs `controller` for a step of `dt` seconds and applies the resulting rotation levels
+    ///
+    /// The rotation is only damped while the target attitude is undefined.
+    fn update_attitude(&self, controller: &mut AttitudeController, dt: f64) {
+        let error = self.attitude_error(controller.mode).unwrap_or_default();
+        let levels = controller.command(&error, &self.angular_vel(), dt);
+        self.set_rotation_levels(&levels);
+    }
+}
+impl<V: VesselApi + ?Sized> VesselAttitude for V {}
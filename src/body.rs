@@ -0,0 +1,46 @@
+//! Physical parameters of celestial bodies as defined in Orbiter's planet configuration files
+
+/// Gravitational constant used by Orbiter \[**m³/(kg s²)**\]
+pub const GGRAV: f64 = 6.67259e-11;
+
+/// Physical parameters of a celestial body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Body {
+    /// Name of the body
+    pub name: &'static str,
+    /// Mass of the body \[**kg**\]
+    pub mass: f64,
+    /// Mean radius of the body \[**m**\]
+    pub radius: f64,
+    /// Coefficients of the zonal harmonics of the gravity field, starting with `J2`
+    ///
+    /// These are the `JCoeff` values of the planet configuration, relative to the mean
+    /// [radius](Self::radius). An empty slice is a spherical body.
+    pub jcoeff: &'static [f64],
+}
+
+impl Body {
+    /// The Sun
+    pub const SUN: Body = Body::new("Sun", 1.98855e30, 6.96e8);
+    /// Earth
+    pub const EARTH: Body =
+        Body::new("Earth", 5.973698968e24, 6.37101e6).with_jcoeff(&[1.0826e-3, -2.51e-6, -1.60e-6, -1.3e-7]);
+    /// Earth's Moon
+    pub const MOON: Body = Body::new("Moon", 7.347664e22, 1.73753e6);
+    /// Mars
+    pub const MARS: Body = Body::new("Mars", 6.418542e23, 3.38992e6);
+
+    /// Define a new spherical body from its mass and mean radius
+    pub const fn new(name: &'static str, mass: f64, radius: f64) -> Self {
+        Self { name, mass, radius, jcoeff: &[] }
+    }
+    /// Sets the coefficients of the zonal harmonics, starting with `J2`
+    pub const fn with_jcoeff(mut self, jcoeff: &'static [f64]) -> Self {
+        self.jcoeff = jcoeff;
+        self
+    }
+    /// Returns the gravitational parameter (GM) of the body \[**m³/s²**\]
+    pub fn gm(&self) -> f64 {
+        GGRAV * self.mass
+    }
+}
@@ -20,6 +20,10 @@ OBJHANDLE oapi_create_vessel(rust::String name, rust::String classname, const VE
 {
     return oapiCreateVessel(name.c_str(), classname.c_str(), status);
 }
+void oapi_get_planet_obliquity_matrix(OBJHANDLE hPlanet, MATRIX3 &mat)
+{
+    oapiGetPlanetObliquityMatrix(hPlanet, &mat);
+}
 
 // VesselContext::VesselContext(OBJHANDLE hVessel, int flightmodel, BoxDynVessel& box_vessel)
 VesselContext::VesselContext(OBJHANDLE hVessel, int flightmodel, VesselInitFn fn)
@@ -66,6 +70,14 @@ OBJHANDLE VesselContext::GetSurfaceRef() const
 {
     return VESSEL4::GetSurfaceRef();
 }
+bool VesselContext::GetElements(OBJHANDLE hRef, ELEMENTS &el, ORBITPARAM &prm, double mjd_ref, int frame) const
+{
+    return VESSEL4::GetElements(hRef, el, &prm, mjd_ref, frame);
+}
+bool VesselContext::SetElements(OBJHANDLE hRef, const ELEMENTS &el, ORBITPARAM &prm, double mjd_ref, int frame) const
+{
+    return VESSEL4::SetElements(hRef, el, &prm, mjd_ref, frame);
+}
 void VesselContext::ParseScenarioLineEx(char* line, void* status) const
 {
     return VESSEL::ParseScenarioLineEx(line, status);
@@ -0,0 +1,180 @@
+//! Low-precision analytic ephemerides of the Sun, the Moon and the planets
+//!
+//! The positions are accurate enough to exercise guidance and transfer logic outside of Orbiter, not to
+//! replace Orbiter's own ephemerides. The planets follow the approximate Keplerian elements published by
+//! JPL for the years 1800 to 2050, accurate to about a thousandth of a degree for the inner planets and a
+//! few hundredths for the outer ones. The Moon uses the low-precision series of the Astronomical Almanac,
+//! accurate to a few tenths of a degree and about 0.2% in distance.
+//!
+//! All vectors are in Orbiter's global frame, the left-handed ecliptic and equinox of J2000 with `y` towards
+//! the ecliptic north pole:
+//!
+//! ```
+//! use orbiter_rs::ephemeris::{moon_state, Planet, AU};
+//!
+//! let mjd = 51982.6699;
+//! let (earth, _) = Planet::Earth.state(mjd);
+//! assert!((earth.length() / AU - 0.995).abs() < 0.005);
+//!
+//! let (moon, _) = moon_state(mjd);
+//! assert!((356e6..407e6).contains(&moon.length()));
+//! ```
+use std::f64::consts::TAU;
+
+use crate::orbit::OrbitalElements;
+use crate::time::{MJD_J2000, SECONDS_PER_DAY};
+use crate::{Body, Vector3, V};
+
+/// Astronomical unit \[**m**\]
+pub const AU: f64 = 1.495978707e11;
+
+/// Time step of the central differences used for the velocity of the Moon \[**s**\]
+const MOON_DT: f64 = 60.0;
+
+/// Julian centuries since the J2000 epoch
+fn centuries(mjd: f64) -> f64 {
+    (mjd - MJD_J2000) / 36525.0
+}
+
+/// A planet of the solar system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Planet {
+    /// Mercury
+    Mercury,
+    /// Venus
+    Venus,
+    /// Earth
+    Earth,
+    /// Mars
+    Mars,
+    /// Jupiter
+    Jupiter,
+    /// Saturn
+    Saturn,
+    /// Uranus
+    Uranus,
+    /// Neptune
+    Neptune,
+}
+
+impl Planet {
+    /// All planets in order of distance from the Sun
+    pub const ALL: [Planet; 8] = [
+        Planet::Mercury,
+        Planet::Venus,
+        Planet::Earth,
+        Planet::Mars,
+        Planet::Jupiter,
+        Planet::Saturn,
+        Planet::Uranus,
+        Planet::Neptune,
+    ];
+
+    /// Mean elements at J2000 and rates per Julian century: `a` \[**AU**\], `e`, inclination, mean longitude,
+    /// longitude of perihelion and longitude of the ascending node \[**deg**\]
+    fn mean_elements(&self) -> [[f64; 6]; 2] {
+        match self {
+            Planet::Mercury => [
+                [0.38709927, 0.20563593, 7.00497902, 252.25032350, 77.45779628, 48.33076593],
+                [0.00000037, 0.00001906, -0.00594749, 149472.67411175, 0.16047689, -0.12534081],
+            ],
+            Planet::Venus => [
+                [0.72333566, 0.00677672, 3.39467605, 181.97909950, 131.60246718, 76.67984255],
+                [0.00000390, -0.00004107, -0.00078890, 58517.81538729, 0.00268329, -0.27769418],
+            ],
+            // Earth-Moon barycentre
+            Planet::Earth => [
+                [1.00000261, 0.01671123, -0.00001531, 100.46457166, 102.93768193, 0.0],
+                [0.00000562, -0.00004392, -0.01294668, 35999.37244981, 0.32327364, 0.0],
+            ],
+            Planet::Mars => [
+                [1.52371034, 0.09339410, 1.84969142, -4.55343205, -23.94362959, 49.55953891],
+                [0.00001847, 0.00007882, -0.00813131, 19140.30268499, 0.44441088, -0.29257343],
+            ],
+            Planet::Jupiter => [
+                [5.20288700, 0.04838624, 1.30439695, 34.39644051, 14.72847983, 100.47390909],
+                [-0.00011607, -0.00013253, -0.00183714, 3034.74612775, 0.21252668, 0.20469106],
+            ],
+            Planet::Saturn => [
+                [9.53667594, 0.05386179, 2.48599187, 49.95424423, 92.59887831, 113.66242448],
+                [-0.00125060, -0.00050991, 0.00193609, 1222.49362201, -0.41897216, -0.28867794],
+            ],
+            Planet::Uranus => [
+                [19.18916464, 0.04725744, 0.77263783, 313.23810451, 170.95427630, 74.01692503],
+                [-0.00196176, -0.00004397, -0.00242939, 428.48202785, 0.40805281, 0.04240589],
+            ],
+            Planet::Neptune => [
+                [30.06992276, 0.00859048, 1.77004347, -55.12002969, 44.96476227, 131.78422574],
+                [0.00026291, 0.00005105, 0.00035372, 218.45945325, -0.32241464, -0.00508664],
+            ],
+        }
+    }
+    /// Returns the heliocentric osculating elements of the planet at `mjd`
+    ///
+    /// For the Earth, these are the elements of the Earth-Moon barycentre.
+    pub fn elements(&self, mjd: f64) -> OrbitalElements {
+        let t = centuries(mjd);
+        let [base, rate] = self.mean_elements();
+        let [a, e, i, mean_longitude, lon_peri, lan] = std::array::from_fn(|k| base[k] + rate[k] * t);
+        let (i, lon_peri, lan) = (i.to_radians(), lon_peri.to_radians(), lan.to_radians());
+        // A negative inclination is a positive one with the ascending and descending nodes swapped
+        OrbitalElements {
+            a: a * AU,
+            e,
+            i: i.abs(),
+            lan: if i < 0.0 { lan + TAU / 2.0 } else { lan },
+            arg_periapsis: if i < 0.0 { lon_peri - lan - TAU / 2.0 } else { lon_peri - lan },
+            true_anomaly: 0.0,
+        }
+        .with_mean_anomaly((mean_longitude.to_radians() - lon_peri).rem_euclid(TAU))
+    }
+    /// Returns the heliocentric position \[**m**\] and velocity \[**m/s**\] of the planet at `mjd`
+    pub fn state(&self, mjd: f64) -> (Vector3, Vector3) {
+        let (pos, vel) = self.elements(mjd).to_state(Body::SUN.gm());
+        if *self != Planet::Earth {
+            return (pos, vel);
+        }
+        // Move from the Earth-Moon barycentre to the Earth
+        let ratio = Body::MOON.mass / (Body::EARTH.mass + Body::MOON.mass);
+        let (moon_pos, moon_vel) = moon_state(mjd);
+        (pos - moon_pos * ratio, vel - moon_vel * ratio)
+    }
+}
+
+/// Returns the geocentric position \[**m**\] and velocity \[**m/s**\] of the Sun at `mjd`
+pub fn sun_state(mjd: f64) -> (Vector3, Vector3) {
+    let (pos, vel) = Planet::Earth.state(mjd);
+    (-pos, -vel)
+}
+
+/// Returns the geocentric position of the Moon at `mjd` \[**m**\]
+pub fn moon_position(mjd: f64) -> Vector3 {
+    let t = centuries(mjd);
+    let sin = |deg: f64| deg.to_radians().sin();
+    let cos = |deg: f64| deg.to_radians().cos();
+    // Longitude and latitude in the ecliptic of date, and horizontal parallax [deg]
+    let lon = 218.32 + 481267.881 * t + 6.29 * sin(135.0 + 477198.87 * t) - 1.27 * sin(259.3 - 413335.36 * t)
+        + 0.66 * sin(235.7 + 890534.22 * t)
+        + 0.21 * sin(269.9 + 954397.74 * t)
+        - 0.19 * sin(357.5 + 35999.05 * t)
+        - 0.11 * sin(186.5 + 966404.03 * t);
+    let lat = 5.13 * sin(93.3 + 483202.02 * t) + 0.28 * sin(228.2 + 960400.89 * t)
+        - 0.28 * sin(318.3 + 6003.15 * t)
+        - 0.17 * sin(217.6 - 407332.21 * t);
+    let parallax = 0.9508 + 0.0518 * cos(135.0 + 477198.87 * t) + 0.0095 * cos(259.3 - 413335.36 * t)
+        + 0.0078 * cos(235.7 + 890534.22 * t)
+        + 0.0028 * cos(269.9 + 954397.74 * t);
+    let dist = 6378.14e3 / sin(parallax);
+    // Precession of the equinox back to J2000
+    let lon = lon - 1.396971 * t;
+    V!(dist * cos(lat) * cos(lon), dist * sin(lat), dist * cos(lat) * sin(lon))
+}
+
+/// Returns the geocentric position \[**m**\] and velocity \[**m/s**\] of the Moon at `mjd`
+///
+/// The velocity is obtained by differentiating the position numerically.
+pub fn moon_state(mjd: f64) -> (Vector3, Vector3) {
+    let dt = MOON_DT / SECONDS_PER_DAY;
+    let vel = (moon_position(mjd + dt) - moon_position(mjd - dt)) / (2.0 * MOON_DT);
+    (moon_position(mjd), vel)
+}
@@ -1,102 +1,22 @@
 // This file is included directly from lib.rs as making it into a module created too many hassles
+//
+// Only compiled with the `sdk` feature. Everything in here requires the Orbiter SDK to build and link.
+use cxx::ExternType;
 use std::os::raw::c_char;
 use std::pin::Pin;
 
-mod vector;
-
-pub use vector::Vector3;
-/// Type alias for [VECTOR3](VECTOR3)
-pub type VECTOR3 = vector::Vector3;
-unsafe impl cxx::ExternType for VECTOR3 {
-    type Id = cxx::type_id!("VECTOR3");
-    type Kind = cxx::kind::Trivial;
-}
- 
-ctype_wrapper!(THRUSTER_HANDLE, usize, ThrusterHandle); 
-ctype_wrapper!(PROPELLANT_HANDLE, usize, PropellantHandle);
-ctype_wrapper!(THGROUP_HANDLE, usize, ThrustGroupHandle);
-ctype_wrapper!(OBJHANDLE, usize);
-ctype_wrapper!(DWORD, u32);
-
-mod io;
-pub use io::FileHandle;
-pub type FILEHANDLE = io::FileHandle;
-unsafe impl cxx::ExternType for FILEHANDLE {
-    type Id = cxx::type_id!("FILEHANDLE");
-    type Kind = cxx::kind::Trivial;
-}
-
-/// Binding for OrbiterSDK's `VESSELSTATUS` struct
-#[repr(C)]
-#[derive(Debug, Default)]
-pub struct VESSELSTATUS {
-    /// Position relative to rbody in ecliptic frame \[**m**\]
-    pub rpos: VECTOR3,
-
-    /// Velocity relative to rbody in ecliptic frame \[**m/s**\]
-    pub rvel: VECTOR3,
-
-    /// Rotation velocity about principal axes in ecliptic frame \[**rad/s**\]
-    pub vrot: VECTOR3,
-
-    /// Vessel orientation against ecliptic frame
-    pub arot: VECTOR3,
-
-    /// Fuel level. Between 0 and 1.
-    pub fuel: f64,
-
-    /// Main/retro engine setting. Between -1 and 1.
-    pub eng_main: f64,
-
-    /// Hover engine setting. Between 0 and 1.
-    pub eng_hovr: f64,
-
-    /// Handle of reference body
-    pub rbody: OBJHANDLE,
-
-    /// Handle of docking or landing target
-    pub base: OBJHANDLE,
-
-    /// Index of designated docking or landing port
-    pub port: i32,
-
-    /// Flight status indicator
-    /// 
-    /// - 0 = active (freeflight)
-    /// - 1 = inactive (landed)
-    pub status: i32,
-
-    /// Additional vector parameters
-    /// 
-    /// - `vdata[0]`: contains landing parameters 
-    /// 
-    ///    if `status` is equal to 1, `vdata[0]` contains the longitude, latitude, and heading of landed vessel
-    /// 
-    /// - `vdata[1]` - `vdata[9]`: not used
-    pub vdata: [VECTOR3; 10],
-
-    /// additional floating point parameters (not used)
-    pub fdata: [f64; 10],
-
-    /// Additional integer and bitflag parameters
-    ///
-    /// - `flag[0] & 1`:
-    ///   - 0: ingore eng_main and eng_hovr entries, do not change thruster settings
-    ///   - 1: set [ThrusterGroupType::Main] and [ThrusterGroupType::Retro] thruster groups from `eng_main`, and [ThrusterGroupType::Hover] from `eng_hovr`.
-    ///
-    /// - `flag[0] & 2`:
-    ///   - 0: ignore fuel level, do not change fuel levels
-    ///   - 1: set fuel level of first propellant resource from fuel
-    /// 
-    /// - `flag[1]` - `flag[9]`: not used
-    pub flag: [DWORD; 10],
-}
-unsafe impl cxx::ExternType for VESSELSTATUS {
-    type Id = cxx::type_id!("VESSELSTATUS");
-    type Kind = cxx::kind::Trivial;
+/// Declares types from the `types` module as trivial C++ types of the same name
+macro_rules! extern_type {
+    ($($r:ident),*) => {
+        $(
+            unsafe impl ExternType for $r {
+                type Id = cxx::type_id!($r);
+                type Kind = cxx::kind::Trivial;
+            }
+        )*
+    };
 }
-/// Type alias for [VESSELSTATUS]
-pub type VesselStatus = VESSELSTATUS;
+extern_type!(VECTOR3, MATRIX3, THRUSTER_HANDLE, PROPELLANT_HANDLE, THGROUP_HANDLE, OBJHANDLE, DWORD, FILEHANDLE, VESSELSTATUS, ELEMENTS, ORBITPARAM);
 
 #[doc(hidden)]
 #[cxx::bridge]
@@ -232,6 +152,7 @@ pub mod ffi {
         type VesselContext;
 
         type VECTOR3 = crate::VECTOR3;
+        type MATRIX3 = crate::MATRIX3;
         type PROPELLANT_HANDLE = crate::PropellantHandle;
         type THRUSTER_HANDLE = crate::ThrusterHandle;
         type THGROUP_HANDLE = crate::ThrustGroupHandle;
@@ -244,6 +165,8 @@ pub mod ffi {
         type AltitudeMode;
 
         type VESSELSTATUS = crate::VesselStatus;
+        type ELEMENTS = crate::Elements;
+        type ORBITPARAM = crate::OrbitParam;
         type VESSEL;
 
         unsafe fn vessel_ovcInit(hvessel: OBJHANDLE, flightmodel: i32, init_fn: fn(Pin<&'static mut VesselContext>)->BoxDynVessel) -> *mut VESSEL;
@@ -252,6 +175,10 @@ pub mod ffi {
 
         /// Create new vessel using Orbiter SDK
         fn oapi_create_vessel(name: String, classname: String, status: &VESSELSTATUS) -> OBJHANDLE;
+        /// Returns the rotation matrix from the planet's local frame to the global (ecliptic) frame
+        ///
+        /// The matrix describes the obliquity of the planet's axis and its precession, but not the daily rotation.
+        fn oapi_get_planet_obliquity_matrix(hplanet: OBJHANDLE, mat: &mut MATRIX3);
 
         // VESSEL API wrappers
         // Some of these have direct counterparts in vessel_context.h
@@ -289,6 +216,9 @@ pub mod ffi {
         fn SetThrusterLevel(self: &VesselContext, th: THRUSTER_HANDLE, level: f64);
         /// Set the thrust level of a thruster for the current time step only
         fn SetThrusterLevel_SingleStep(self: &VesselContext, th: THRUSTER_HANDLE, level: f64);
+        /// Set the thrust level for all thrusters of a default thruster group type
+        #[rust_name = "SetThrusterGroupLevelByType"]
+        fn SetThrusterGroupLevel(self: &VesselContext, thgroup_type: THGROUP_TYPE, level: f64);
 
         /// Load a mesh definition for the vessel from a file
         fn AddMesh(self: &VesselContext, mesh_name: String);
@@ -344,12 +274,18 @@ pub mod ffi {
         fn GetThrustVector(self: &VesselContext, thrust_vec: &mut VECTOR3) -> bool;
         /// Returns the vessel's current total propellant mass
         fn GetTotalPropellantMass(self: &VesselContext) -> f64;
+        /// Returns the vessel's empty mass, excluding propellants
+        fn GetEmptyMass(self: &VesselContext) -> f64;
         /// Returns the mean thrust level for a default thruster group type
         #[rust_name = "GetThrusterGroupLevelByType"]
         fn GetThrusterGroupLevel(self: &VesselContext, thgroup_type: THGROUP_TYPE) -> f64;
         /// Returns the mean thrust level for a default thruster group specified by `th`
         #[rust_name = "GetThrusterGroupLevel"]
         fn GetThrusterGroupLevel(self: &VesselContext, th: THGROUP_HANDLE) -> f64;
+        /// Returns the maximum vacuum thrust rating of a thruster
+        fn GetThrusterMax0(self: &VesselContext, th: THRUSTER_HANDLE) -> f64;
+        /// Returns the vacuum fuel-specific impulse (ISP) rating of a thruster
+        fn GetThrusterIsp0(self: &VesselContext, th: THRUSTER_HANDLE) -> f64;
         /// Returns a flag indicating contact with a planetary surface
         fn GroundContact(self: &VesselContext) -> bool;
 
@@ -361,11 +297,42 @@ pub mod ffi {
 
         /// Returns altitude above mean ellipsoid
         fn GetAltitude(self: &VesselContext) -> f64;
+        /// Returns the atmospheric temperature at the vessel's position, or 0 outside an atmosphere \[**K**\]
+        fn GetAtmTemperature(self: &VesselContext) -> f64;
+        /// Returns the atmospheric density at the vessel's position, or 0 outside an atmosphere \[**kg/m³**\]
+        fn GetAtmDensity(self: &VesselContext) -> f64;
+        /// Returns the static atmospheric pressure at the vessel's position, or 0 outside an atmosphere \[**Pa**\]
+        fn GetAtmPressure(self: &VesselContext) -> f64;
+
+        /// Returns the vessel's current rotation matrix for transforming from local vessel coordinates to the global frame
+        fn GetRotationMatrix(self: &VesselContext, r: &mut MATRIX3);
+        /// Returns the vessel's rotation drag coefficients for rotation around its principal axes
+        fn GetRotDrag(self: &VesselContext, rd: &mut VECTOR3);
+
+        /// Returns the osculating orbital elements of the vessel relative to the body `href`
+        ///
+        /// `L` in `el` is the mean longitude at the reference date `mjd_ref`, or at the current simulation time if
+        /// `mjd_ref` is 0. `frame` is [FRAME_ECL](crate::FRAME_ECL) or [FRAME_EQU](crate::FRAME_EQU).
+        /// Returns `false` if the elements could not be computed.
+        fn GetElements(self: &VesselContext, href: OBJHANDLE, el: &mut ELEMENTS, prm: &mut ORBITPARAM, mjd_ref: f64, frame: i32) -> bool;
+        /// Moves the vessel onto the orbit around the body `href` described by `el`
+        ///
+        /// The parameters have the same meaning as for `GetElements`. The secondary parameters of the new orbit are
+        /// returned in `prm`. Returns `false` if the elements could not be applied.
+        fn SetElements(self: &VesselContext, href: OBJHANDLE, el: &ELEMENTS, prm: &mut ORBITPARAM, mjd_ref: f64, frame: i32) -> bool;
 
         /// Returns the vessel's current velocity relative to another object
         /// 
         /// Results are returned in the ecliptic frame (ecliptic and equinox of J2000.0). 
         fn GetRelativeVel(self: &VesselContext, href: OBJHANDLE, rel_vel: &mut VECTOR3);
+        /// Returns the vessel's current longitude, latitude \[**rad**\] and distance from the centre \[**m**\] of
+        /// its reference body
+        fn GetEquPos(self: &VesselContext, longitude: &mut f64, latitude: &mut f64, radius: &mut f64);
+        /// Returns the vessel's airspeed vector in the local horizon frame of its reference body
+        ///
+        /// The horizon frame has `x` pointing east, `y` up and `z` north. Returns `false` if no reference body is
+        /// available.
+        fn GetHorizonAirspeedVector(self: &VesselContext, airspeed: &mut VECTOR3) -> bool;
 
         /// Pass a line read from a scenario file to Orbiter for default processing
         unsafe fn ParseScenarioLineEx(self: &VesselContext, line: *mut c_char, status: *mut c_void);
@@ -458,6 +425,7 @@ unsafe fn dyn_vessel_drop_in_place(ptr: PtrBoxDynVessel) {
 
 // trait fn shims
 fn dyn_vessel_set_class_caps(vessel: &mut Box<dyn OrbiterVessel>, cfg: &FileHandle) {
+    trace::record(|| trace::TraceEvent::SetClassCaps);
     (**vessel).set_class_caps(cfg);
 }
 fn dyn_vessel_pre_step(
@@ -466,6 +434,7 @@ fn dyn_vessel_pre_step(
     sim_dt: f64,
     mjd: f64,
 ) {
+    trace::record(|| trace::TraceEvent::PreStep { sim_t, sim_dt, mjd });
     (**vessel).on_pre_step(sim_t, sim_dt, mjd);
 }
 fn dyn_vessel_post_step(
@@ -474,6 +443,7 @@ fn dyn_vessel_post_step(
     sim_dt: f64,
     mjd: f64,
 ) {
+    trace::record(|| trace::TraceEvent::PostStep { sim_t, sim_dt, mjd });
     (**vessel).on_post_step(sim_t, sim_dt, mjd);
 }
 unsafe fn dyn_vessel_consume_buffered_key(
@@ -483,7 +453,9 @@ unsafe fn dyn_vessel_consume_buffered_key(
     kstate: *mut c_char,
 ) -> i32 {
     let kstate = crate::KeyStates::from(kstate);
-    (**vessel).consume_buffered_key(crate::Key::from(key.0 as u8), down, kstate)
+    let key = crate::Key::from(key.0 as u8);
+    trace::record_key(key, down, &kstate);
+    (**vessel).consume_buffered_key(key, down, kstate)
 }
 unsafe fn dyn_vessel_load_state_ex(
     vessel: &mut BoxDynVessel,
@@ -499,8 +471,10 @@ unsafe fn dyn_vessel_load_state_ex(
         }
 
         let line_str = std::ffi::CStr::from_ptr(line).to_string_lossy();
+        trace::record(|| trace::TraceEvent::LoadParam(line_str.to_string()));
         if !vessel.on_load_param(&line_str)
         {
+            trace::record(|| trace::TraceEvent::Call(trace::VesselCall::ParseScenarioLineEx(line_str.to_string())));
             sdk_vessel.ParseScenarioLineEx(line, status);
         }
     }
@@ -510,24 +484,11 @@ fn dyn_vessel_save_state(
     vessel: &mut BoxDynVessel,
     scn: FILEHANDLE
 ) {
+    trace::record(|| trace::TraceEvent::SaveState);
     vessel.on_save_state(&scn);
 }
-pub use ffi::VesselContext;
 impl std::fmt::Debug for VesselContext {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         fmt.debug_struct("VesselContext").finish()
     }
 }
-#[doc(hidden)]
-pub use ffi::BoxDynVessel;
-
-pub use ffi::oapi_create_vessel;
-pub use ffi::ODebug;
-
-pub use ffi::REFFRAME as ReferenceFrame;
-/// Type alias for [THGROUP_TYPE]
-pub use ffi::THGROUP_TYPE as ThrusterGroupType;
-
-/// A wrapper over the FFI interface to the Orbiter SDK
-/// This is passed to the init() function in the `[init_vessel!]` macro
-pub type SDKVessel = std::pin::Pin<&'static mut VesselContext>;
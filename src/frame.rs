@@ -0,0 +1,278 @@
+//! Vectors tagged with the frame of reference they are expressed in
+//!
+//! Most of the `VESSEL` API takes and returns plain [Vector3]s, and nothing stops a vector in local
+//! vessel coordinates from being added to a position in the ecliptic frame. [Vec3] carries the frame as a
+//! type parameter, so that such mixups fail to compile. The markers [Global], [Local], [RefLocal] and
+//! [Horizon] mirror [ReferenceFrame]; [Rel] is the ecliptic frame centered at the vessel's reference body
+//! used by `Local2Rel`.
+//!
+//! The typed counterparts of the transform bindings are provided by the [VesselFrames] extension trait,
+//! which is implemented for every [VesselApi]. Plain [Vector3]s stay available and a `Vec3` can always be
+//! converted back with [Vec3::vector]:
+//!
+//! ```no_run
+//! use orbiter_rs::frame::{Global, Local, Vec3, VesselFrames};
+//! use orbiter_rs::VesselApi;
+//!
+//! fn thruster_position<V: VesselApi>(ctx: &V) -> Vec3<Global> {
+//!     let pos: Vec3<Local> = Vec3::new(0.0, 0.86, -0.5);
+//!     ctx.local_to_global(&pos)
+//! }
+//! ```
+//!
+//! Adding a local offset to a global position does not compile:
+//!
+//! ```compile_fail
+//! use orbiter_rs::frame::{Global, Local, Vec3};
+//!
+//! let pos: Vec3<Global> = Vec3::new(1.0e6, 0.0, 0.0);
+//! let ofs: Vec3<Local> = Vec3::new(0.0, 1.0, 0.0);
+//! let _ = pos + ofs;
+//! ```
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use crate::{Matrix3, ReferenceFrame, Vector3, VesselApi};
+
+/// Marker for a frame of reference
+pub trait Frame {
+    /// Name of the frame, used by the `Debug` implementation of [Vec3]
+    const NAME: &'static str;
+}
+/// Marker for a frame of reference with a [ReferenceFrame] counterpart in the Orbiter SDK
+pub trait OrbiterFrame: Frame {
+    /// The corresponding SDK frame
+    const REFERENCE_FRAME: ReferenceFrame;
+}
+
+macro_rules! frame {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {}
+        impl Frame for $name {
+            const NAME: &'static str = stringify!($name);
+        }
+    };
+    ($(#[$meta:meta])* $name:ident => $ref_frame:ident) => {
+        frame!($(#[$meta])* $name);
+        impl OrbiterFrame for $name {
+            const REFERENCE_FRAME: ReferenceFrame = ReferenceFrame::$ref_frame;
+        }
+    };
+}
+frame!(
+    /// Global (ecliptic) frame
+    Global => Global
+);
+frame!(
+    /// Local vessel frame
+    Local => Local
+);
+frame!(
+    /// Local frame of the reference object (planet or moon)
+    RefLocal => RefLocal
+);
+frame!(
+    /// Local horizon frame
+    Horizon => Horizon
+);
+frame!(
+    /// Ecliptic frame centered at the vessel's reference body
+    Rel
+);
+
+/// A [Vector3] expressed in the frame `F`
+///
+/// Vectors can only be combined with vectors in the same frame.
+#[repr(transparent)]
+pub struct Vec3<F> {
+    vector: Vector3,
+    frame: PhantomData<F>,
+}
+
+impl<F> Vec3<F> {
+    /// Build a new `Vec3` from given `x`, `y` and `z` coordinates
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self::from_vector(Vector3::new(x, y, z))
+    }
+    /// Tags `vector` as being expressed in the frame `F`
+    pub const fn from_vector(vector: Vector3) -> Self {
+        Self {
+            vector,
+            frame: PhantomData,
+        }
+    }
+    /// Returns the untagged vector
+    pub const fn vector(&self) -> Vector3 {
+        self.vector
+    }
+    /// Returns the `x` coordinate of the vector
+    pub const fn x(&self) -> f64 {
+        self.vector.x()
+    }
+    /// Returns the `y` coordinate of the vector
+    pub const fn y(&self) -> f64 {
+        self.vector.y()
+    }
+    /// Returns the `z` coordinate of the vector
+    pub const fn z(&self) -> f64 {
+        self.vector.z()
+    }
+    /// Computes the dot product with `other`
+    pub fn dot(&self, other: &Self) -> f64 {
+        self.vector.dot(&other.vector)
+    }
+    /// Computes cross product with `other`
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::from_vector(self.vector.cross(&other.vector))
+    }
+    /// Computes the length of the vector
+    pub fn length(&self) -> f64 {
+        self.vector.length()
+    }
+    /// Computes the unit vector in the direction of this vector
+    pub fn unit(&self) -> Self {
+        Self::from_vector(self.vector.unit())
+    }
+}
+
+// Implemented by hand so that the frame markers do not need to implement these traits
+impl<F> Clone for Vec3<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<F> Copy for Vec3<F> {}
+impl<F> Default for Vec3<F> {
+    fn default() -> Self {
+        Self::from_vector(Vector3::default())
+    }
+}
+impl<F> PartialEq for Vec3<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.vector == other.vector
+    }
+}
+impl<F: Frame> fmt::Debug for Vec3<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Vec3<{}>({:?}, {:?}, {:?})", F::NAME, self.x(), self.y(), self.z())
+    }
+}
+impl<F> fmt::Display for Vec3<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.vector, f)
+    }
+}
+impl<F> From<Vec3<F>> for Vector3 {
+    fn from(v: Vec3<F>) -> Self {
+        v.vector
+    }
+}
+
+impl<F> Add for Vec3<F> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        Self::from_vector(self.vector + other.vector)
+    }
+}
+impl<F> Sub for Vec3<F> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self::Output {
+        Self::from_vector(self.vector - other.vector)
+    }
+}
+impl<F> AddAssign for Vec3<F> {
+    fn add_assign(&mut self, other: Self) {
+        self.vector += other.vector;
+    }
+}
+impl<F> SubAssign for Vec3<F> {
+    fn sub_assign(&mut self, other: Self) {
+        self.vector -= other.vector;
+    }
+}
+impl<F> Neg for Vec3<F> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::from_vector(-self.vector)
+    }
+}
+impl<F> Mul<f64> for Vec3<F> {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self::from_vector(self.vector * rhs)
+    }
+}
+impl<F> Mul<Vec3<F>> for f64 {
+    type Output = Vec3<F>;
+    fn mul(self, rhs: Vec3<F>) -> Self::Output {
+        rhs * self
+    }
+}
+impl<F> Div<f64> for Vec3<F> {
+    type Output = Self;
+    fn div(self, rhs: f64) -> Self::Output {
+        Self::from_vector(self.vector / rhs)
+    }
+}
+
+/// Frame-tagged versions of the [VesselApi] methods that take or return vectors in a specific frame
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselFrames: VesselApi {
+    /// Transforms a position from local vessel coordinates to the ecliptic frame centered at the reference body
+    fn local_to_rel(&self, local: &Vec3<Local>) -> Vec3<Rel> {
+        let mut rel = Vector3::default();
+        self.Local2Rel(&local.vector, &mut rel);
+        Vec3::from_vector(rel)
+    }
+    /// Transforms a position from local vessel coordinates to the global (ecliptic) frame
+    fn local_to_global(&self, local: &Vec3<Local>) -> Vec3<Global> {
+        let mut global = Vector3::default();
+        self.Local2Global(&local.vector, &mut global);
+        Vec3::from_vector(global)
+    }
+    /// Transforms a position from the global (ecliptic) frame to local vessel coordinates
+    fn global_to_local(&self, global: &Vec3<Global>) -> Vec3<Local> {
+        let mut local = Vector3::default();
+        self.Global2Local(&global.vector, &mut local);
+        Vec3::from_vector(local)
+    }
+    /// Rotates a direction, such as a velocity or a force, from local vessel coordinates to the global
+    /// (ecliptic) frame
+    ///
+    /// Unlike [local_to_global](VesselFrames::local_to_global), the vessel's position is not added.
+    fn local_dir_to_global(&self, local: &Vec3<Local>) -> Vec3<Global> {
+        let mut rotation = Matrix3::default();
+        self.GetRotationMatrix(&mut rotation);
+        Vec3::from_vector(rotation * local.vector)
+    }
+    /// Rotates a direction, such as a velocity or a force, from the global (ecliptic) frame to local vessel
+    /// coordinates
+    fn global_dir_to_local(&self, global: &Vec3<Global>) -> Vec3<Local> {
+        let mut rotation = Matrix3::default();
+        self.GetRotationMatrix(&mut rotation);
+        Vec3::from_vector(rotation.tmul(&global.vector))
+    }
+    /// Returns the vessel's airspeed vector in the frame `F`, or `None` if it is not available
+    fn airspeed_vector<F: OrbiterFrame>(&self) -> Option<Vec3<F>> {
+        let mut airspeed = Vector3::default();
+        self.GetAirspeedVector(F::REFERENCE_FRAME, &mut airspeed)
+            .then(|| Vec3::from_vector(airspeed))
+    }
+    /// Returns the angular velocity of the vessel around its principal axes \[**rad/s**\]
+    fn angular_vel(&self) -> Vec3<Local> {
+        let mut a_vel = Vector3::default();
+        self.GetAngularVel(&mut a_vel);
+        Vec3::from_vector(a_vel)
+    }
+    /// Returns the thrust force vector in local vessel coordinates \[**N**\]
+    fn thrust_vector(&self) -> Vec3<Local> {
+        let mut thrust_vec = Vector3::default();
+        self.GetThrustVector(&mut thrust_vec);
+        Vec3::from_vector(thrust_vec)
+    }
+}
+impl<V: VesselApi + ?Sized> VesselFrames for V {}
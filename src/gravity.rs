@@ -0,0 +1,192 @@
+//! Gravity fields of non-spherical bodies and third-body perturbations
+//!
+//! A [GravityModel] adds to the point-mass attraction of the central body the zonal harmonics `J2..Jn`
+//! of its [jcoeff](crate::Body::jcoeff), taken about the axis of its [BodyRotation], and the tidal
+//! accelerations of any number of [ThirdBody] perturbers. Positions are relative to the central body in
+//! the ecliptic frame, like [VesselStatus::rpos](crate::VesselStatus::rpos), so the model can be used to
+//! predict a trajectory on board:
+//!
+//! ```
+//! use orbiter_rs::gravity::{GravityModel, ThirdBody};
+//! use orbiter_rs::surface::BodyRotation;
+//! use orbiter_rs::{kepler, Body, V};
+//!
+//! let earth = Body::EARTH;
+//! let mjd = 51982.6699;
+//! let pos = V!(earth.radius + 400e3, 0.0, 0.0);
+//! let vel = V!(0.0, 0.0, (earth.gm() / pos.length()).sqrt());
+//!
+//! // Truncated to the point mass, the model reproduces the Keplerian orbit
+//! let point_mass = GravityModel::new(earth.clone(), BodyRotation::EARTH).with_degree(1);
+//! let (p1, _) = point_mass.propagate(&pos, &vel, mjd, 3000.0);
+//! let (p2, _) = kepler::propagate(&pos, &vel, earth.gm(), 3000.0);
+//! assert!((p1 - p2).length() < 1.0);
+//!
+//! // The oblateness of the Earth and the Moon move the vessel by kilometres over half an orbit
+//! let model = GravityModel::new(earth, BodyRotation::EARTH).with_third_body(ThirdBody::MOON_FROM_EARTH);
+//! let (p3, _) = model.propagate(&pos, &vel, mjd, 3000.0);
+//! assert!((1e3..100e3).contains(&(p3 - p2).length()));
+//! ```
+//!
+//! Like Orbiter, the harmonics are referred to the mean radius of the body.
+use crate::ephemeris::{moon_position, sun_state};
+use crate::surface::BodyRotation;
+use crate::body::GGRAV;
+use crate::time::SECONDS_PER_DAY;
+use crate::{Body, Vector3, V};
+
+/// Longest time step of [GravityModel::propagate] \[**s**\]
+const MAX_STEP: f64 = 10.0;
+
+/// Returns the acceleration at `pos` relative to a point mass of gravitational parameter `gm`
+/// \[**m/s²**\]
+pub fn point_mass(gm: f64, pos: &Vector3) -> Vector3 {
+    let r = pos.length();
+    -*pos * (gm / (r * r * r))
+}
+
+/// Returns the acceleration due to the zonal harmonics `jcoeff`, starting with `J2`, of a body of
+/// gravitational parameter `gm` and reference radius `radius` \[**m/s²**\]
+///
+/// `pos` and the returned acceleration are in a body frame with the `y` axis along the north pole. The
+/// point-mass term is not included.
+pub fn zonal(gm: f64, radius: f64, jcoeff: &[f64], pos: &Vector3) -> Vector3 {
+    let r = pos.length();
+    let dir = *pos / r;
+    let s = dir.y();
+    // Legendre polynomials P(n) and their derivatives dP(n) at s, by recurrence from P0 and P1
+    let (mut p, mut p_prev) = (s, 1.0);
+    let mut dp = 1.0;
+    let mut ratio = radius / r;
+    let (mut radial, mut polar) = (0.0, 0.0);
+    for (k, j) in jcoeff.iter().enumerate() {
+        let n = (k + 2) as f64;
+        (p, p_prev) = (((2.0 * n - 1.0) * s * p - (n - 1.0) * p_prev) / n, p);
+        dp = n * p_prev + s * dp;
+        ratio *= radius / r;
+        // Gradient of -GM Jn R^n P(n)(s) / r^(n+1)
+        radial += j * ratio * ((n + 1.0) * p + s * dp);
+        polar -= j * ratio * dp;
+    }
+    let g = gm / (r * r);
+    (dir * radial + V!(0.0, polar, 0.0)) * g
+}
+
+/// Returns the tidal acceleration at `pos` due to a third body of gravitational parameter `gm` at
+/// `body_pos`, both relative to the central body \[**m/s²**\]
+///
+/// This is the attraction of the third body on the vessel less its attraction on the central body.
+pub fn third_body(gm: f64, body_pos: &Vector3, pos: &Vector3) -> Vector3 {
+    point_mass(gm, &(*pos - *body_pos)) - point_mass(gm, &-*body_pos)
+}
+
+/// A perturbing body and its position relative to the central body
+#[derive(Debug, Clone, Copy)]
+pub struct ThirdBody {
+    /// Gravitational parameter of the body \[**m³/s²**\]
+    pub gm: f64,
+    /// Returns the position of the body relative to the central body at a given MJD, in the ecliptic
+    /// frame \[**m**\]
+    pub position: fn(f64) -> Vector3,
+}
+
+impl ThirdBody {
+    /// The Sun, for a vessel orbiting the Earth
+    pub const SUN_FROM_EARTH: ThirdBody = ThirdBody::new(Body::SUN.mass, |mjd| sun_state(mjd).0);
+    /// The Moon, for a vessel orbiting the Earth
+    pub const MOON_FROM_EARTH: ThirdBody = ThirdBody::new(Body::MOON.mass, moon_position);
+    /// The Sun, for a vessel orbiting the Moon
+    pub const SUN_FROM_MOON: ThirdBody =
+        ThirdBody::new(Body::SUN.mass, |mjd| sun_state(mjd).0 - moon_position(mjd));
+    /// The Earth, for a vessel orbiting the Moon
+    pub const EARTH_FROM_MOON: ThirdBody = ThirdBody::new(Body::EARTH.mass, |mjd| -moon_position(mjd));
+
+    /// Define a perturbing body from its mass \[**kg**\] and position relative to the central body
+    pub const fn new(mass: f64, position: fn(f64) -> Vector3) -> Self {
+        Self {
+            gm: GGRAV * mass,
+            position,
+        }
+    }
+    /// Returns the tidal acceleration of the body at `pos` at `mjd` \[**m/s²**\]
+    pub fn acceleration(&self, pos: &Vector3, mjd: f64) -> Vector3 {
+        third_body(self.gm, &(self.position)(mjd), pos)
+    }
+}
+
+/// Gravity field of a central body with zonal harmonics and third-body perturbations
+#[derive(Debug, Clone)]
+pub struct GravityModel {
+    /// The central body
+    pub body: Body,
+    /// Rotation of the central body, whose axis the zonal harmonics are symmetric about
+    pub rotation: BodyRotation,
+    /// Highest degree of the zonal harmonics used, 1 for a point mass
+    pub degree: usize,
+    /// Perturbing bodies
+    pub third_bodies: Vec<ThirdBody>,
+}
+
+impl GravityModel {
+    /// Build a new `GravityModel` using all the zonal harmonics of `body` and no perturbing bodies
+    pub fn new(body: Body, rotation: BodyRotation) -> Self {
+        Self {
+            degree: body.jcoeff.len() + 1,
+            body,
+            rotation,
+            third_bodies: Vec::new(),
+        }
+    }
+    /// Limits the zonal harmonics to `J2..Jn` for `degree` n, or none for a degree of 1
+    pub fn with_degree(mut self, degree: usize) -> Self {
+        self.degree = degree;
+        self
+    }
+    /// Adds a perturbing body
+    pub fn with_third_body(mut self, third_body: ThirdBody) -> Self {
+        self.third_bodies.push(third_body);
+        self
+    }
+
+    /// Returns the acceleration due to the central body alone at `pos` \[**m/s²**\]
+    pub fn central_acceleration(&self, pos: &Vector3) -> Vector3 {
+        let gm = self.body.gm();
+        let jcoeff = &self.body.jcoeff[..self.degree.saturating_sub(1).min(self.body.jcoeff.len())];
+        if jcoeff.is_empty() {
+            return point_mass(gm, pos);
+        }
+        // The harmonics are symmetric about the axis, so the daily rotation does not matter
+        let axis = self.rotation.obliquity_matrix();
+        point_mass(gm, pos) + axis * zonal(gm, self.body.radius, jcoeff, &axis.tmul(pos))
+    }
+    /// Returns the total gravitational acceleration at `pos` at `mjd` \[**m/s²**\]
+    pub fn acceleration(&self, pos: &Vector3, mjd: f64) -> Vector3 {
+        self.third_bodies
+            .iter()
+            .fold(self.central_acceleration(pos), |acc, third_body| acc + third_body.acceleration(pos, mjd))
+    }
+    /// Returns the position \[**m**\] and velocity \[**m/s**\] reached from `pos` and `vel` at `mjd` after
+    /// `dt` seconds, which may be negative
+    ///
+    /// The trajectory is integrated with the classic Runge-Kutta method in steps of at most 10 s. Only
+    /// gravity is taken into account.
+    pub fn propagate(&self, pos: &Vector3, vel: &Vector3, mjd: f64, dt: f64) -> (Vector3, Vector3) {
+        let steps = (dt.abs() / MAX_STEP).ceil().max(1.0);
+        let h = dt / steps;
+        let day = |t: f64| mjd + t / SECONDS_PER_DAY;
+        let (mut p, mut v) = (*pos, *vel);
+        for i in 0..steps as usize {
+            let t = i as f64 * h;
+            let a1 = self.acceleration(&p, day(t));
+            let (p2, v2) = (p + v * (h / 2.0), v + a1 * (h / 2.0));
+            let a2 = self.acceleration(&p2, day(t + h / 2.0));
+            let (p3, v3) = (p + v2 * (h / 2.0), v + a2 * (h / 2.0));
+            let a3 = self.acceleration(&p3, day(t + h / 2.0));
+            let (p4, v4) = (p + v3 * h, v + a3 * h);
+            let a4 = self.acceleration(&p4, day(t + h));
+            p += (v + (v2 + v3) * 2.0 + v4) * (h / 6.0);
+            v += (a1 + (a2 + a3) * 2.0 + a4) * (h / 6.0);
+        }
+        (p, v)
+    }
+}
@@ -0,0 +1,276 @@
+//! Powered-descent landing guidance
+//!
+//! The guidance laws take the state of a lander relative to the surface as a [LanderState] and return the
+//! [ThrustCommand] for the current step: the direction to point the engine and its throttle setting.
+//!
+//! - [GravityTurn] thrusts against the velocity relative to the surface, with the throttle set so that the
+//!   lander reaches a target altitude at a target descent rate. The trajectory bends towards the vertical
+//!   on its own, but the touchdown point is not controlled.
+//! - [EGuidance] (explicit guidance) commands the acceleration profile, linear in time, that reaches a
+//!   target altitude and velocity, and optionally a target site, after a given time to go.
+//!
+//! Both work in any frame in which the direction of "up" is known. [VesselGuidance] builds the state in the
+//! local horizon frame from `GetAltitude` and `GetAirspeedVector`, or in the local vessel frame, where the
+//! commanded direction can be used to orient the vessel. The laws can also be exercised against a simple
+//! point-mass model of the lander:
+//!
+//! ```
+//! use orbiter_rs::frame::{Horizon, Vec3};
+//! use orbiter_rs::guidance::{EGuidance, LanderState};
+//! use orbiter_rs::maneuver::Engine;
+//! use orbiter_rs::units::{Force, Mass, SpecificImpulse};
+//!
+//! let engine = Engine::new(Force::newtons(4500.0), SpecificImpulse::seconds(300.0));
+//! let mut state = LanderState::<Horizon> {
+//!     up: Vec3::new(0.0, 1.0, 0.0),
+//!     velocity: Vec3::new(0.0, -60.0, 80.0),
+//!     altitude: 2000.0,
+//!     gravity: 1.62,
+//!     mass: Mass::kilograms(1000.0),
+//! };
+//! let target_velocity = Vec3::new(0.0, -1.0, 0.0);
+//! let mut guidance = EGuidance::new(10.0, 0.0);
+//! guidance.time_to_go = EGuidance::estimate_time_to_go(&state, 10.0, &target_velocity).unwrap();
+//!
+//! let dt = 0.1;
+//! while guidance.time_to_go > 0.0 {
+//!     let command = guidance.command(&state, &target_velocity, &engine);
+//!     let accel = command.thrust(&engine) / state.mass.in_kilograms() - state.up * state.gravity;
+//!     state.velocity += accel * dt;
+//!     state.altitude += state.velocity.y() * dt;
+//!     state.mass -= Mass::kilograms(command.throttle * engine.thrust.in_newtons() / engine.isp.in_meters_per_second() * dt);
+//!     guidance.step(dt);
+//! }
+//! assert!((state.altitude - 10.0).abs() < 1.0);
+//! assert!((state.velocity - target_velocity).length() < 0.5);
+//! ```
+use crate::frame::{Frame, Horizon, Local, Vec3, VesselFrames};
+use crate::maneuver::Engine;
+use crate::units::Mass;
+use crate::{Body, VesselApi, VesselStatus};
+
+/// Time to go below which [EGuidance] only targets the velocity, as its position gains grow without
+/// bound near the end \[**s**\]
+const TERMINAL_TIME_TO_GO: f64 = 1.0;
+/// Shortest time to go over which [EGuidance] nulls the remaining velocity error \[**s**\]
+const MIN_TIME_TO_GO: f64 = 0.1;
+/// Time constant used to track the target descent rate below the target altitude \[**s**\]
+const DESCENT_RATE_TAU: f64 = 1.0;
+
+/// State of a lander relative to the surface, in the frame `F`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LanderState<F: Frame> {
+    /// Unit vector pointing away from the centre of the body
+    pub up: Vec3<F>,
+    /// Velocity relative to the surface \[**m/s**\]
+    pub velocity: Vec3<F>,
+    /// Altitude above the surface \[**m**\]
+    pub altitude: f64,
+    /// Magnitude of the gravitational acceleration \[**m/s²**\]
+    pub gravity: f64,
+    /// Current mass of the lander
+    pub mass: Mass,
+}
+
+impl<F: Frame> LanderState<F> {
+    /// Returns the vertical speed, negative when descending \[**m/s**\]
+    pub fn vertical_speed(&self) -> f64 {
+        self.velocity.dot(&self.up)
+    }
+    /// Returns the horizontal component of the velocity \[**m/s**\]
+    pub fn horizontal_velocity(&self) -> Vec3<F> {
+        self.velocity - self.up * self.vertical_speed()
+    }
+}
+
+/// Engine direction and throttle setting requested by a guidance law
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrustCommand<F: Frame> {
+    /// Unit vector along which the thrust should act
+    pub direction: Vec3<F>,
+    /// Throttle setting in `[0, 1]`
+    pub throttle: f64,
+}
+
+impl<F: Frame> ThrustCommand<F> {
+    /// Returns the command producing the thrust acceleration `accel` \[**m/s²**\], saturated to the
+    /// thrust of `engine`
+    ///
+    /// Without acceleration, the engine is pointed up and shut down.
+    pub fn from_acceleration(accel: &Vec3<F>, state: &LanderState<F>, engine: &Engine) -> Self {
+        let magnitude = accel.length();
+        if magnitude == 0.0 {
+            return Self { direction: state.up, throttle: 0.0 };
+        }
+        let throttle = magnitude * state.mass.in_kilograms() / engine.thrust.in_newtons();
+        Self {
+            direction: *accel / magnitude,
+            throttle: throttle.clamp(0.0, 1.0),
+        }
+    }
+    /// Returns the thrust force produced by `engine` \[**N**\]
+    pub fn thrust(&self, engine: &Engine) -> Vec3<F> {
+        self.direction * (self.throttle * engine.thrust.in_newtons())
+    }
+}
+
+/// Gravity turn descent: thrust against the velocity to reach `target_altitude` at `target_speed`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GravityTurn {
+    /// Altitude at which the descent should be slowed down to the target speed \[**m**\]
+    pub target_altitude: f64,
+    /// Descent rate at the target altitude and below \[**m/s**\]
+    pub target_speed: f64,
+}
+
+impl GravityTurn {
+    /// Build a new `GravityTurn` from its target
+    pub const fn new(target_altitude: f64, target_speed: f64) -> Self {
+        Self { target_altitude, target_speed }
+    }
+    /// Returns the command for the current state
+    ///
+    /// Below the target altitude, or once the lander moves up or sideways, the engine is pointed up and
+    /// throttled to hold the target descent rate.
+    pub fn command<F: Frame>(&self, state: &LanderState<F>, engine: &Engine) -> ThrustCommand<F> {
+        let height = state.altitude - self.target_altitude;
+        let vy = state.vertical_speed();
+        let speed = state.velocity.length();
+        // Cosine of the angle between the velocity and the downward vertical
+        let descent = if speed > 0.0 { -vy / speed } else { 0.0 };
+        if height <= 0.0 || descent <= 0.0 || speed <= self.target_speed {
+            let accel = state.gravity + (-self.target_speed - vy) / DESCENT_RATE_TAU;
+            return ThrustCommand::from_acceleration(&(state.up * accel.max(0.0)), state, engine);
+        }
+        // Vertical deceleration that brings the descent rate to the target over the remaining height
+        let braking = (vy * vy - self.target_speed * self.target_speed).max(0.0) / (2.0 * height);
+        let accel = (state.gravity + braking) / descent;
+        ThrustCommand::from_acceleration(&(state.velocity * (-accel / speed)), state, engine)
+    }
+}
+
+/// Explicit guidance towards a target altitude and velocity after a fixed time to go
+///
+/// The commanded acceleration varies linearly over the remaining time, which is the profile with the
+/// lowest integral of the squared acceleration. [EGuidance::step] counts down the time to go. During the
+/// last second, only the velocity is targeted.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EGuidance {
+    /// Altitude to reach at the end of the time to go \[**m**\]
+    pub target_altitude: f64,
+    /// Remaining time until the target is reached \[**s**\]
+    pub time_to_go: f64,
+}
+
+impl EGuidance {
+    /// Build a new `EGuidance` reaching `target_altitude` \[**m**\] after `time_to_go` \[**s**\]
+    pub const fn new(target_altitude: f64, time_to_go: f64) -> Self {
+        Self { target_altitude, time_to_go }
+    }
+    /// Estimates the time to go from a constant vertical deceleration, or `None` if the lander is not
+    /// descending towards the target
+    pub fn estimate_time_to_go<F: Frame>(
+        state: &LanderState<F>,
+        target_altitude: f64,
+        target_velocity: &Vec3<F>,
+    ) -> Option<f64> {
+        let height = state.altitude - target_altitude;
+        let mean_rate = -(state.vertical_speed() + target_velocity.dot(&state.up)) / 2.0;
+        (height > 0.0 && mean_rate > 0.0).then(|| height / mean_rate)
+    }
+    /// Counts down the time to go by `dt` seconds
+    pub fn step(&mut self, dt: f64) {
+        self.time_to_go -= dt;
+    }
+    /// Returns the command reaching the target altitude with `target_velocity`, without constraining the
+    /// horizontal position
+    pub fn command<F: Frame>(
+        &self,
+        state: &LanderState<F>,
+        target_velocity: &Vec3<F>,
+        engine: &Engine,
+    ) -> ThrustCommand<F> {
+        let dv = *target_velocity - state.velocity;
+        if self.time_to_go < TERMINAL_TIME_TO_GO {
+            return self.terminal_command(state, &dv, engine);
+        }
+        let t = self.time_to_go;
+        // Vertical channel: reach the altitude and the vertical speed
+        let dr = self.target_altitude - state.altitude - state.vertical_speed() * t;
+        let vertical = 6.0 * dr / (t * t) - 2.0 * dv.dot(&state.up) / t;
+        // Horizontal channel: reach the velocity with a constant acceleration
+        let horizontal = (dv - state.up * dv.dot(&state.up)) / t;
+        let accel = horizontal + state.up * (vertical + state.gravity);
+        ThrustCommand::from_acceleration(&accel, state, engine)
+    }
+    /// Returns the command reaching the target altitude above `site` with `target_velocity`
+    ///
+    /// `site` is the position of the landing site on the surface relative to the lander, so that only its
+    /// horizontal components are used.
+    pub fn command_to_site<F: Frame>(
+        &self,
+        state: &LanderState<F>,
+        site: &Vec3<F>,
+        target_velocity: &Vec3<F>,
+        engine: &Engine,
+    ) -> ThrustCommand<F> {
+        let dv = *target_velocity - state.velocity;
+        if self.time_to_go < TERMINAL_TIME_TO_GO {
+            return self.terminal_command(state, &dv, engine);
+        }
+        let t = self.time_to_go;
+        let site = *site - state.up * site.dot(&state.up);
+        let target = site + state.up * (self.target_altitude - state.altitude);
+        let dr = target - state.velocity * t;
+        let accel = dr * (6.0 / (t * t)) - dv * (2.0 / t) + state.up * state.gravity;
+        ThrustCommand::from_acceleration(&accel, state, engine)
+    }
+    /// Nulls the velocity error `dv` over the remaining time
+    fn terminal_command<F: Frame>(
+        &self,
+        state: &LanderState<F>,
+        dv: &Vec3<F>,
+        engine: &Engine,
+    ) -> ThrustCommand<F> {
+        let accel = *dv / self.time_to_go.max(MIN_TIME_TO_GO) + state.up * state.gravity;
+        ThrustCommand::from_acceleration(&accel, state, engine)
+    }
+}
+
+/// Lander state from the `VESSEL` getters
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselGuidance: VesselApi {
+    /// Returns the state of the vessel in the local horizon frame, landing on `body` with the current `mass`
+    ///
+    /// Returns `None` if the airspeed vector is not available.
+    fn horizon_lander_state(&self, body: &Body, mass: Mass) -> Option<LanderState<Horizon>> {
+        let velocity = self.airspeed_vector::<Horizon>()?;
+        let mut status = VesselStatus::default();
+        self.GetStatus(&mut status);
+        Some(LanderState {
+            up: Vec3::new(0.0, 1.0, 0.0),
+            velocity,
+            altitude: self.GetAltitude(),
+            gravity: body.gm() / status.rpos.dot(&status.rpos),
+            mass,
+        })
+    }
+    /// Returns the state of the vessel in the local vessel frame, landing on `body` with the current `mass`
+    ///
+    /// Returns `None` if the airspeed vector is not available.
+    fn local_lander_state(&self, body: &Body, mass: Mass) -> Option<LanderState<Local>> {
+        let velocity = self.airspeed_vector::<Local>()?;
+        let mut status = VesselStatus::default();
+        self.GetStatus(&mut status);
+        let up = self.global_dir_to_local(&Vec3::from_vector(status.rpos.unit()));
+        Some(LanderState {
+            up,
+            velocity,
+            altitude: self.GetAltitude(),
+            gravity: body.gm() / status.rpos.dot(&status.rpos),
+            mass,
+        })
+    }
+}
+impl<V: VesselApi + ?Sized> VesselGuidance for V {}
@@ -0,0 +1,429 @@
+//! Headless rigid-body simulation that drives `OrbiterVessel` callbacks
+//!
+//! [Harness] plays the role of Orbiter for an addon running on the [mock](crate::mock) backend.
+//! It calls [OrbiterVessel::set_class_caps] once, then advances time in fixed steps, calling
+//! [OrbiterVessel::on_pre_step] and [OrbiterVessel::on_post_step] around each integration step.
+//! The motion of the vessel is integrated from what the addon defined through the `VesselContext`:
+//!
+//! - thrusters, their levels and the propellant resources feeding them
+//! - empty mass and current propellant masses
+//! - mass-normalised principal moments of inertia
+//! - touchdown points, used to detect contact with the surface
+//!
+//! The vessel moves in the point-mass gravity field of a single [Body] fixed at the origin of the
+//! global frame. Propellant is depleted at a rate of `thrust / isp` per thruster. Aerodynamic forces,
+//! surface friction and the rotation of the body are not modelled. An [AtmosphereModel] set with
+//! [Harness::set_atmosphere] only provides the values returned by `GetAtmTemperature`,
+//! `GetAtmPressure` and `GetAtmDensity`.
+//!
+//! The callbacks are written to the active [trace](crate::trace) recording like Orbiter's are, so a session flown
+//! on the harness can be replayed with [replay](crate::trace::replay).
+//!
+//! After each step, the [MockState] of the vessel is updated so that the getters (`GetStatus`,
+//! `GetAltitude`, `GetAirspeedVector`, `GetPropellantMass`, ...) return the simulated values.
+//!
+//! ```
+//! use orbiter_rs::harness::Harness;
+//! use orbiter_rs::{Body, FileHandle, OrbiterVessel, PropellantHandle, SDKVessel, ThrusterHandle, V};
+//!
+//! struct Lander {
+//!     ctx: SDKVessel,
+//!     ph: PropellantHandle,
+//!     th: ThrusterHandle,
+//! }
+//! impl OrbiterVessel for Lander {
+//!     fn set_class_caps(&mut self, _cfg: &FileHandle) {
+//!         self.ctx.SetEmptyMass(100.0);
+//!         self.ctx.SetPMI(&V!(1.0, 1.0, 1.0));
+//!         self.ph = self.ctx.CreatePropellantResource(10.0);
+//!         self.th = self.ctx.CreateThruster(&V!(0.0, 0.0, 0.0), &V!(0.0, 0.0, 1.0), 500.0, self.ph, 3000.0);
+//!     }
+//!     fn on_pre_step(&mut self, _sim_t: f64, _sim_dt: f64, _mjd: f64) {
+//!         self.ctx.SetThrusterLevel_SingleStep(self.th, 1.0);
+//!     }
+//! }
+//!
+//! let mut harness = Harness::new("Lander", Body::MOON, |vessel| Lander {
+//!     ctx: vessel,
+//!     ph: PropellantHandle::default(),
+//!     th: ThrusterHandle::default(),
+//! });
+//! harness.set_position(V!(0.0, 0.0, -(Body::MOON.radius + 1000.0)));
+//! harness.run(1.0, 0.1);
+//!
+//! // The engine fired for one second at 500 N with an ISP of 3000 m/s
+//! let propellant = harness.mock().state().propellants[0].mass;
+//! assert!((propellant - (10.0 - 500.0 / 3000.0)).abs() < 1e-6);
+//! ```
+use crate::atmosphere::AtmosphereModel;
+use crate::mock::{KeyboardState, MockState, MockVessel};
+use crate::orbit::OrbitalElements;
+use crate::surface::EquPos;
+use crate::trace::{self, TraceEvent};
+use crate::{Body, Key, Matrix3, OrbiterVessel, ReferenceFrame, SDKVessel, Vector3};
+
+/// Dynamic state of the simulated vessel
+#[derive(Debug)]
+pub struct RigidBodyState {
+    /// Position relative to the central body in the global frame \[**m**\]
+    pub pos: Vector3,
+    /// Velocity relative to the central body in the global frame \[**m/s**\]
+    pub vel: Vector3,
+    /// Rotation matrix from the local vessel frame to the global frame
+    pub rotation: Matrix3,
+    /// Angular velocity about the vessel's principal axes \[**rad/s**\]
+    pub angular_vel: Vector3,
+}
+impl Default for RigidBodyState {
+    fn default() -> Self {
+        Self {
+            pos: Vector3::default(),
+            vel: Vector3::default(),
+            rotation: Matrix3::IDENTITY,
+            angular_vel: Vector3::default(),
+        }
+    }
+}
+
+/// Details about the first contact of the vessel with the surface
+#[derive(Debug, PartialEq)]
+pub struct Touchdown {
+    /// Simulation time of the touchdown \[**s**\]
+    pub sim_t: f64,
+    /// Speed of the vessel relative to the surface at touchdown \[**m/s**\]
+    pub speed: f64,
+}
+
+/// Headless simulation of a single vessel
+pub struct Harness<T: OrbiterVessel> {
+    vessel: T,
+    mock: MockVessel,
+    body: Body,
+    atmosphere: Option<Box<dyn AtmosphereModel>>,
+    state: RigidBodyState,
+    keyboard: KeyboardState,
+    sim_t: f64,
+    mjd: f64,
+    touchdown: Option<Touchdown>,
+}
+
+impl<T: OrbiterVessel> Harness<T> {
+    /// Creates the vessel with `init` and calls its [OrbiterVessel::set_class_caps] with an empty configuration file
+    ///
+    /// `init` receives the [SDKVessel] to be stored in the addon, just like the `init` block of
+    /// [init_vessel!](crate::init_vessel). The vessel starts at rest at the centre of `body`, so
+    /// [Harness::set_position] and [Harness::set_velocity] should be used to set up the initial state.
+    pub fn new(name: &str, body: Body, init: impl FnOnce(SDKVessel) -> T) -> Self {
+        Self::with_config(name, body, "", init)
+    }
+    /// Same as [Harness::new], passing a configuration file with the given contents to [OrbiterVessel::set_class_caps]
+    pub fn with_config(
+        name: &str,
+        body: Body,
+        config: &str,
+        init: impl FnOnce(SDKVessel) -> T,
+    ) -> Self {
+        let mock = MockVessel::new(name);
+        let mut vessel = init(mock.sdk_vessel());
+        trace::record(|| TraceEvent::SetClassCaps);
+        vessel.set_class_caps(&mock.config_file(config));
+        let harness = Self {
+            vessel,
+            mock,
+            body,
+            atmosphere: None,
+            state: RigidBodyState::default(),
+            keyboard: KeyboardState::new(),
+            sim_t: 0.0,
+            mjd: 51544.5,
+            touchdown: None,
+        };
+        harness.sync_mock();
+        harness
+    }
+
+    /// Returns the addon being simulated
+    pub fn vessel(&self) -> &T {
+        &self.vessel
+    }
+    /// Returns the addon being simulated for modification
+    pub fn vessel_mut(&mut self) -> &mut T {
+        &mut self.vessel
+    }
+    /// Returns the mocked vessel holding the state defined by the addon and the recorded calls
+    pub fn mock(&self) -> &MockVessel {
+        &self.mock
+    }
+    /// Returns the dynamic state of the vessel
+    pub fn state(&self) -> &RigidBodyState {
+        &self.state
+    }
+    /// Returns the current simulation time \[**s**\]
+    pub fn sim_t(&self) -> f64 {
+        self.sim_t
+    }
+    /// Returns the current Modified Julian Date
+    pub fn mjd(&self) -> f64 {
+        self.mjd
+    }
+    /// Returns details about the first contact with the surface, if it occurred
+    pub fn touchdown(&self) -> Option<&Touchdown> {
+        self.touchdown.as_ref()
+    }
+    /// Returns the current total mass of the vessel \[**kg**\]
+    pub fn mass(&self) -> f64 {
+        let state = self.mock.state();
+        state.empty_mass + state.propellants.iter().map(|p| p.mass).sum::<f64>()
+    }
+    /// Returns the altitude of the vessel above the mean radius of the central body \[**m**\]
+    pub fn altitude(&self) -> f64 {
+        self.state.pos.length() - self.body.radius
+    }
+
+    /// Sets the position of the vessel relative to the central body in the global frame \[**m**\]
+    pub fn set_position(&mut self, pos: Vector3) {
+        self.state.pos = pos;
+        self.sync_mock();
+    }
+    /// Sets the velocity of the vessel relative to the central body in the global frame \[**m/s**\]
+    pub fn set_velocity(&mut self, vel: Vector3) {
+        self.state.vel = vel;
+        self.sync_mock();
+    }
+    /// Sets the rotation matrix from the local vessel frame to the global frame
+    pub fn set_rotation(&mut self, rotation: Matrix3) {
+        self.state.rotation = rotation;
+        self.sync_mock();
+    }
+    /// Sets the angular velocity about the vessel's principal axes \[**rad/s**\]
+    pub fn set_angular_vel(&mut self, angular_vel: Vector3) {
+        self.state.angular_vel = angular_vel;
+        self.sync_mock();
+    }
+    /// Sets the atmosphere of the central body, used for the atmospheric conditions returned to the addon
+    pub fn set_atmosphere(&mut self, atmosphere: impl AtmosphereModel + 'static) {
+        self.atmosphere = Some(Box::new(atmosphere));
+        self.sync_mock();
+    }
+    /// Sets the Modified Julian Date passed to the step callbacks
+    pub fn set_mjd(&mut self, mjd: f64) {
+        self.mjd = mjd;
+    }
+
+    /// Simulates pressing `key` (with any keys held through [Harness::keyboard] as modifiers)
+    ///
+    /// Returns the value returned by [OrbiterVessel::consume_buffered_key]
+    pub fn press_key(&mut self, key: Key) -> i32 {
+        let result = self
+            .mock
+            .press_key(&mut self.vessel, key, true, &mut self.keyboard);
+        self.sync_mock();
+        result
+    }
+    /// Returns the keyboard state passed to [OrbiterVessel::consume_buffered_key]
+    pub fn keyboard(&mut self) -> &mut KeyboardState {
+        &mut self.keyboard
+    }
+
+    /// Advances the simulation by one time step of `dt` seconds
+    pub fn step(&mut self, dt: f64) {
+        let (sim_t, mjd) = (self.sim_t, self.mjd);
+        trace::record(|| TraceEvent::PreStep { sim_t, sim_dt: dt, mjd });
+        self.vessel.on_pre_step(sim_t, dt, mjd);
+        self.integrate(dt);
+        self.sim_t += dt;
+        self.mjd += dt / 86400.0;
+        self.sync_mock();
+        let (sim_t, mjd) = (self.sim_t, self.mjd);
+        trace::record(|| TraceEvent::PostStep { sim_t, sim_dt: dt, mjd });
+        self.vessel.on_post_step(sim_t, dt, mjd);
+        for thruster in self.mock.state_mut().thrusters.iter_mut() {
+            thruster.single_step_level = None;
+        }
+    }
+    /// Advances the simulation by `duration` seconds in steps of `dt` seconds
+    pub fn run(&mut self, duration: f64, dt: f64) {
+        let end = self.sim_t + duration;
+        while self.sim_t + 0.5 * dt < end {
+            self.step(dt);
+        }
+    }
+    /// Advances the simulation in steps of `dt` seconds until `condition` returns true
+    ///
+    /// Returns `false` if the condition was not met within `max_duration` seconds
+    pub fn run_until(
+        &mut self,
+        max_duration: f64,
+        dt: f64,
+        mut condition: impl FnMut(&Self) -> bool,
+    ) -> bool {
+        let end = self.sim_t + max_duration;
+        while self.sim_t + 0.5 * dt < end {
+            self.step(dt);
+            if condition(self) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn integrate(&mut self, dt: f64) {
+        let mut force = Vector3::default();
+        let mut torque = Vector3::default();
+        let (mass, pmi) = {
+            let mut mock = self.mock.state_mut();
+            let MockState {
+                thrusters,
+                propellants,
+                ..
+            } = &mut *mock;
+            for thruster in thrusters.iter() {
+                let level = thruster.effective_level().clamp(0.0, 1.0);
+                let propellant = match thruster.propellant.0.checked_sub(1) {
+                    Some(idx) if idx < propellants.len() => &mut propellants[idx],
+                    // Thrusters without a propellant resource are not functional
+                    _ => continue,
+                };
+                if level <= 0.0 || propellant.mass <= 0.0 {
+                    continue;
+                }
+                let mut thrust = level * thruster.max_thrust;
+                if thruster.isp > 0.0 {
+                    let consumed = thrust / thruster.isp * dt;
+                    if consumed > propellant.mass {
+                        // Tank runs dry part-way through the step
+                        thrust *= propellant.mass / consumed;
+                        propellant.mass = 0.0;
+                    } else {
+                        propellant.mass -= consumed;
+                    }
+                }
+                let f = thruster.dir * thrust;
+                // Orbiter's frames are left-handed, so the torque is F × r
+                torque += f.cross(&thruster.pos);
+                force += f;
+            }
+            let mass = mock.empty_mass + mock.propellants.iter().map(|p| p.mass).sum::<f64>();
+            (mass, mock.pmi)
+        };
+        if mass <= 0.0 {
+            return;
+        }
+
+        // Translation (semi-implicit Euler)
+        let r = self.state.pos.length();
+        let gravity = if r > 0.0 {
+            self.state.pos * (-self.body.gm() / (r * r * r))
+        } else {
+            Vector3::default()
+        };
+        let accel = (self.state.rotation * force) / mass + gravity;
+        if self.touchdown.is_some() && accel.dot(&self.state.pos) <= 0.0 {
+            // Resting on the surface
+            self.state.vel = Vector3::default();
+            self.state.angular_vel = Vector3::default();
+            return;
+        }
+        self.state.vel = accel * dt + self.state.vel;
+        self.state.pos = self.state.vel * dt + self.state.pos;
+
+        // Rotation about the principal axes (Euler's equations). Angular velocities and torques follow
+        // the left-handed frame, so the gyroscopic terms and the sense of rotation are reversed compared to
+        // the usual right-handed form: a positive rotation about x pitches the nose (z) up towards y.
+        let [px, py, pz] = pmi.0;
+        if px > 0.0 && py > 0.0 && pz > 0.0 {
+            let [wx, wy, wz] = self.state.angular_vel.0;
+            let [tx, ty, tz] = (torque / mass).0;
+            let dw = Vector3::new(
+                (tx + (pz - py) * wy * wz) / px,
+                (ty + (px - pz) * wz * wx) / py,
+                (tz + (py - px) * wx * wy) / pz,
+            );
+            self.state.angular_vel = dw * dt + self.state.angular_vel;
+            let delta = self.state.angular_vel * dt;
+            let angle = delta.length();
+            if angle > 1e-12 {
+                self.state.rotation = self.state.rotation * Matrix3::from_axis_angle(&delta, -angle);
+            }
+        }
+
+        self.check_touchdown();
+    }
+
+    fn check_touchdown(&mut self) {
+        let points: Vec<Vector3> = match &self.mock.state().touchdown_points {
+            Some(points) => points.to_vec(),
+            None => vec![Vector3::default()],
+        };
+        let penetration = points
+            .iter()
+            .map(|p| self.body.radius - (self.state.rotation * p + self.state.pos).length())
+            .fold(f64::NEG_INFINITY, f64::max);
+        if penetration < 0.0 {
+            return;
+        }
+        if self.touchdown.is_none() {
+            self.touchdown = Some(Touchdown {
+                sim_t: self.sim_t,
+                speed: self.state.vel.length(),
+            });
+        }
+        let up = self.state.pos.unit();
+        self.state.pos = up * penetration + self.state.pos;
+        self.state.vel = Vector3::default();
+        self.state.angular_vel = Vector3::default();
+    }
+
+    fn sync_mock(&self) {
+        let mut mock = self.mock.state_mut();
+        let state = &self.state;
+        let local_vel = state.rotation.tmul(&state.vel);
+        let horizon_vel = to_horizon(&state.pos, &state.vel);
+        let landed = self.touchdown.is_some();
+
+        mock.status.rpos = state.pos;
+        mock.status.rvel = state.vel;
+        mock.status.vrot = state.angular_vel;
+        mock.status.status = landed as i32;
+        mock.global_pos = state.pos;
+        mock.rotation = state.rotation;
+        mock.angular_vel = state.angular_vel;
+        mock.airspeed[ReferenceFrame::Global as usize] = state.vel;
+        mock.airspeed[ReferenceFrame::Local as usize] = local_vel;
+        mock.airspeed[ReferenceFrame::RefLocal as usize] = state.vel;
+        mock.airspeed[ReferenceFrame::Horizon as usize] = horizon_vel;
+        mock.relative_vel = state.vel;
+        // The body does not rotate and its axis is the global y axis
+        mock.equ_pos = EquPos::from_body_fixed(&state.pos);
+        mock.altitude = self.altitude();
+        let conditions = self.atmosphere.as_ref().and_then(|atm| atm.conditions(self.altitude()));
+        (mock.atm_temperature, mock.atm_pressure, mock.atm_density) =
+            conditions.map_or((0.0, 0.0, 0.0), |c| (c.temperature, c.pressure, c.density));
+        mock.ground_contact = landed;
+        if state.pos.length() > 0.0 {
+            let elements = OrbitalElements::from_state(&state.pos, &state.vel, self.body.gm());
+            mock.elements = elements.to_elements();
+            mock.orbit_param = elements.orbit_param(self.body.gm());
+        }
+    }
+}
+
+/// Expresses `vel` in the local horizon frame at `pos` (x east, y up, z north)
+///
+/// The rotation axis of the body is assumed to be the global y axis
+fn to_horizon(pos: &Vector3, vel: &Vector3) -> Vector3 {
+    if pos.length() == 0.0 {
+        return *vel;
+    }
+    let up = pos.unit();
+    let pole = Vector3::new(0.0, 1.0, 0.0);
+    let north = pole - up * up.y();
+    let north = if north.length() > 1e-9 {
+        north.unit()
+    } else {
+        // At the poles, pick an arbitrary north direction
+        Vector3::new(0.0, 0.0, 1.0)
+    };
+    let east = up.cross(&north);
+    Vector3::new(vel.dot(&east), vel.dot(&up), vel.dot(&north))
+}
@@ -439,10 +439,15 @@ pub struct KeyStates<'a> {
 }
 
 impl<'a> KeyStates<'a> {
+    #[cfg_attr(not(orbiter_sdk), allow(dead_code))]
     pub(crate) fn from(kstate: *mut c_char) -> Self {
         let slice = unsafe { std::slice::from_raw_parts_mut(kstate, 256) };
         Self { kstate: slice }
     }
+    #[cfg(not(orbiter_sdk))]
+    pub(crate) fn from_slice(kstate: &'a mut [c_char]) -> Self {
+        Self { kstate }
+    }
 
     /// Check if a key is pressed down
     pub fn is_down(&self, key: Key) -> bool {
@@ -0,0 +1,36 @@
+use glam::{DMat3, DQuat, DVec3};
+
+use crate::{Matrix3, Quaternion, Vector3};
+
+impl From<DVec3> for Vector3 {
+    fn from(v: DVec3) -> Self {
+        Vector3(v.to_array())
+    }
+}
+impl From<Vector3> for DVec3 {
+    fn from(v: Vector3) -> Self {
+        DVec3::from_array(v.0)
+    }
+}
+
+impl From<DMat3> for Matrix3 {
+    fn from(m: DMat3) -> Self {
+        Matrix3::from_cols(&m.x_axis.into(), &m.y_axis.into(), &m.z_axis.into())
+    }
+}
+impl From<Matrix3> for DMat3 {
+    fn from(m: Matrix3) -> Self {
+        DMat3::from_cols(m.col(0).into(), m.col(1).into(), m.col(2).into())
+    }
+}
+
+impl From<DQuat> for Quaternion {
+    fn from(q: DQuat) -> Self {
+        Quaternion::new(q.w, q.x, q.y, q.z)
+    }
+}
+impl From<Quaternion> for DQuat {
+    fn from(q: Quaternion) -> Self {
+        DQuat::from_xyzw(q.x, q.y, q.z, q.w)
+    }
+}
@@ -0,0 +1,5 @@
+//! Conversions to and from the types of other linear algebra crates, enabled by the feature of the same name
+#[cfg(feature = "glam")]
+mod glam;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
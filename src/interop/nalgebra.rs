@@ -0,0 +1,60 @@
+use crate::{Matrix3, Quaternion, Vector3};
+
+impl From<nalgebra::Vector3<f64>> for Vector3 {
+    fn from(v: nalgebra::Vector3<f64>) -> Self {
+        Vector3(v.into())
+    }
+}
+impl From<Vector3> for nalgebra::Vector3<f64> {
+    fn from(v: Vector3) -> Self {
+        v.0.into()
+    }
+}
+impl From<nalgebra::Point3<f64>> for Vector3 {
+    fn from(p: nalgebra::Point3<f64>) -> Self {
+        p.coords.into()
+    }
+}
+impl From<Vector3> for nalgebra::Point3<f64> {
+    fn from(v: Vector3) -> Self {
+        v.0.into()
+    }
+}
+
+impl From<nalgebra::Matrix3<f64>> for Matrix3 {
+    fn from(m: nalgebra::Matrix3<f64>) -> Self {
+        Matrix3(std::array::from_fn(|i| std::array::from_fn(|j| m[(i, j)])))
+    }
+}
+impl From<Matrix3> for nalgebra::Matrix3<f64> {
+    fn from(m: Matrix3) -> Self {
+        nalgebra::Matrix3::from_fn(|i, j| m.0[i][j])
+    }
+}
+impl From<nalgebra::Rotation3<f64>> for Matrix3 {
+    fn from(r: nalgebra::Rotation3<f64>) -> Self {
+        r.into_inner().into()
+    }
+}
+
+impl From<nalgebra::Quaternion<f64>> for Quaternion {
+    fn from(q: nalgebra::Quaternion<f64>) -> Self {
+        Quaternion::new(q.w, q.i, q.j, q.k)
+    }
+}
+impl From<Quaternion> for nalgebra::Quaternion<f64> {
+    fn from(q: Quaternion) -> Self {
+        nalgebra::Quaternion::new(q.w, q.x, q.y, q.z)
+    }
+}
+impl From<nalgebra::UnitQuaternion<f64>> for Quaternion {
+    fn from(q: nalgebra::UnitQuaternion<f64>) -> Self {
+        q.into_inner().into()
+    }
+}
+/// The quaternion is normalised in the conversion
+impl From<Quaternion> for nalgebra::UnitQuaternion<f64> {
+    fn from(q: Quaternion) -> Self {
+        nalgebra::UnitQuaternion::new_normalize(q.into())
+    }
+}
@@ -0,0 +1,163 @@
+//! Two-body propagation with universal variables
+//!
+//! [propagate] advances a position and velocity relative to a central body with gravitational parameter `gm`
+//! (see [Body::gm](crate::Body::gm)) by a given time, for elliptic, parabolic and hyperbolic orbits alike.
+//! [time_to_true_anomaly] and [time_to_radius] return the time until the vessel reaches a point on its
+//! current orbit, e.g. to predict the time of impact from the state returned by `GetStatus`:
+//!
+//! ```
+//! use orbiter_rs::kepler::{propagate, time_to_radius};
+//! use orbiter_rs::{Body, V};
+//!
+//! let moon = Body::MOON;
+//! // 100 km above the surface, falling towards the Moon
+//! let pos = V!(0.0, 0.0, moon.radius + 100e3);
+//! let vel = V!(300.0, 0.0, -1500.0);
+//!
+//! let t_impact = time_to_radius(&pos, &vel, moon.gm(), moon.radius).unwrap();
+//! let (pos_impact, _) = propagate(&pos, &vel, moon.gm(), t_impact);
+//! assert!((pos_impact.length() - moon.radius).abs() < 1e-3);
+//! ```
+//!
+//! The functions only depend on the geometry of the orbit, so the vectors may be given in any frame
+//! centred at the central body, such as the ecliptic frame of
+//! [VesselStatus::rpos](crate::VesselStatus::rpos) and [VesselStatus::rvel](crate::VesselStatus::rvel).
+use std::f64::consts::TAU;
+
+use crate::orbit::OrbitalElements;
+use crate::Vector3;
+
+/// Eccentricities closer to one than this are treated as parabolic
+const PARABOLIC_EPS: f64 = 1e-9;
+
+/// Stumpff functions `C(z)` and `S(z)`
+fn stumpff(z: f64) -> (f64, f64) {
+    if z > 1e-6 {
+        let s = z.sqrt();
+        ((1.0 - s.cos()) / z, (s - s.sin()) / (s * z))
+    } else if z < -1e-6 {
+        let s = (-z).sqrt();
+        ((s.cosh() - 1.0) / -z, (s.sinh() - s) / (s * -z))
+    } else {
+        // Series expansions around zero
+        (1.0 / 2.0 - z / 24.0 + z * z / 720.0, 1.0 / 6.0 - z / 120.0 + z * z / 5040.0)
+    }
+}
+
+/// Propagates the position \[**m**\] and velocity \[**m/s**\] by `dt` seconds on the two-body orbit around a
+/// central body with gravitational parameter `gm` \[**m³/s²**\]
+///
+/// `dt` may be negative to propagate backwards in time.
+pub fn propagate(pos: &Vector3, vel: &Vector3, gm: f64, dt: f64) -> (Vector3, Vector3) {
+    let r0 = pos.length();
+    let sqrt_gm = gm.sqrt();
+    let rv = pos.dot(vel);
+    // Reciprocal of the semi-major axis
+    let alpha = 2.0 / r0 - vel.dot(vel) / gm;
+
+    // Only propagate within one revolution of a closed orbit
+    let dt = if alpha > 0.0 {
+        let period = TAU / (gm * alpha.powi(3)).sqrt();
+        dt % period
+    } else {
+        dt
+    };
+    if dt == 0.0 {
+        return (*pos, *vel);
+    }
+
+    // Initial guesses from Vallado, "Fundamentals of Astrodynamics and Applications"
+    let mut chi = if alpha > 1e-12 / r0 {
+        sqrt_gm * dt * alpha
+    } else if alpha < -1e-12 / r0 {
+        let a = 1.0 / alpha;
+        let sign = dt.signum();
+        let arg = -2.0 * gm * alpha * dt / (rv + sign * (-gm * a).sqrt() * (1.0 - r0 * alpha));
+        sign * (-a).sqrt() * arg.ln()
+    } else {
+        sqrt_gm * dt / r0
+    };
+
+    // Newton iteration on the universal Kepler equation
+    for _ in 0..100 {
+        let z = alpha * chi * chi;
+        let (c, s) = stumpff(z);
+        let chi2 = chi * chi;
+        let t = rv / sqrt_gm * chi2 * c + (1.0 - alpha * r0) * chi2 * chi * s + r0 * chi;
+        let r = rv / sqrt_gm * chi * (1.0 - z * s) + (1.0 - alpha * r0) * chi2 * c + r0;
+        let delta = (t - sqrt_gm * dt) / r;
+        chi -= delta;
+        if delta.abs() < 1e-12 * chi.abs().max(1.0) {
+            break;
+        }
+    }
+
+    let z = alpha * chi * chi;
+    let (c, s) = stumpff(z);
+    let f = 1.0 - chi * chi / r0 * c;
+    let g = dt - chi.powi(3) / sqrt_gm * s;
+    let new_pos = *pos * f + *vel * g;
+    let r = new_pos.length();
+    let f_dot = sqrt_gm / (r * r0) * (z * s - 1.0) * chi;
+    let g_dot = 1.0 - chi * chi / r * c;
+    (new_pos, *pos * f_dot + *vel * g_dot)
+}
+
+/// Returns the time \[**s**\] until the true anomaly reaches `true_anomaly` \[**rad**\], or `None` if the
+/// orbit never gets there
+///
+/// For closed orbits, the time is in `[0, period)`. Open orbits only reach true anomalies within their
+/// asymptotes, and only if the point has not been passed yet. The true anomaly of circular orbits is
+/// measured as in [OrbitalElements].
+pub fn time_to_true_anomaly(pos: &Vector3, vel: &Vector3, gm: f64, true_anomaly: f64) -> Option<f64> {
+    let el = OrbitalElements::from_state(pos, vel, gm);
+    let e = el.e;
+    // Semi-latus rectum from the angular momentum, which stays finite for parabolic orbits
+    let p = pos.cross(vel).dot(&pos.cross(vel)) / gm;
+    if (e - 1.0).abs() < PARABOLIC_EPS {
+        // Barker's equation
+        let mean = |nu: f64| {
+            let d = (nu / 2.0).tan();
+            d + d * d * d / 3.0
+        };
+        if true_anomaly.cos() <= -1.0 {
+            return None;
+        }
+        let t = (mean(true_anomaly) - mean(el.true_anomaly)) * (p.powi(3) / gm).sqrt() / 2.0;
+        return (t >= 0.0).then_some(t);
+    }
+    let target = OrbitalElements { true_anomaly, ..el };
+    if e < 1.0 {
+        let n = (gm / target.a.powi(3)).sqrt();
+        Some((target.mean_anomaly() - el.mean_anomaly()).rem_euclid(TAU) / n)
+    } else {
+        if 1.0 + e * true_anomaly.cos() <= 0.0 {
+            return None;
+        }
+        let n = (gm / (-target.a).powi(3)).sqrt();
+        let t = (target.mean_anomaly() - el.mean_anomaly()) / n;
+        (t >= 0.0).then_some(t)
+    }
+}
+
+/// Returns the time \[**s**\] until the distance from the centre of the central body is `radius` \[**m**\],
+/// or `None` if the orbit never gets there
+///
+/// Of the two points on the orbit at that distance, the one reached first is used. For a circular orbit
+/// the radius is either never or always reached, so `None` is returned in both cases.
+pub fn time_to_radius(pos: &Vector3, vel: &Vector3, gm: f64, radius: f64) -> Option<f64> {
+    let el = OrbitalElements::from_state(pos, vel, gm);
+    let p = pos.cross(vel).dot(&pos.cross(vel)) / gm;
+    if el.e == 0.0 {
+        return None;
+    }
+    let cos_nu = (p / radius - 1.0) / el.e;
+    if !(-1.0..=1.0).contains(&cos_nu) {
+        return None;
+    }
+    let nu = cos_nu.acos();
+    [nu, TAU - nu]
+        .into_iter()
+        .filter_map(|nu| time_to_true_anomaly(pos, vel, gm, nu))
+        .reduce(f64::min)
+}
@@ -0,0 +1,313 @@
+//! Lambert solver for transfer and intercept planning
+//!
+//! [solve] finds the two-body orbits connecting two positions relative to a central body in a given time
+//! of flight, including multi-revolution transfers, using the algorithm of D. Izzo, "Revisiting Lambert's
+//! problem" (2015). [intercept] builds on it to compute the burns that bring a vessel to another vessel's
+//! future position, from the states returned by `GetStatus` or `GetRelativeVel`:
+//!
+//! ```
+//! use orbiter_rs::kepler::propagate;
+//! use orbiter_rs::lambert::{solve, Direction};
+//! use orbiter_rs::{Body, V};
+//!
+//! let gm = Body::EARTH.gm();
+//! let r1 = V!(7.0e6, 0.0, 0.0);
+//! let r2 = V!(0.0, 1.0e6, 8.0e6);
+//! let tof = 2000.0;
+//!
+//! let solutions = solve(&r1, &r2, tof, gm, Direction::Prograde, 0);
+//! let (pos, vel) = propagate(&r1, &solutions[0].v1, gm, tof);
+//! assert!((pos - r2).length() < 1e-3);
+//! assert!((vel - solutions[0].v2).length() < 1e-6);
+//! ```
+//!
+//! Vectors are given in Orbiter's ecliptic frame. The resulting velocity changes can be transformed into
+//! local vessel coordinates with the transpose of the rotation matrix, see
+//! [Matrix3::tmul](crate::Matrix3::tmul).
+use std::f64::consts::PI;
+
+use crate::kepler::propagate;
+use crate::orbit::swap_yz;
+use crate::Vector3;
+
+/// Direction of motion along a transfer orbit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Counter-clockwise when seen from the ecliptic north pole, like the planets
+    Prograde,
+    /// Clockwise when seen from the ecliptic north pole
+    Retrograde,
+}
+
+/// A transfer orbit found by [solve]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LambertSolution {
+    /// Velocity at the departure point \[**m/s**\]
+    pub v1: Vector3,
+    /// Velocity at the arrival point \[**m/s**\]
+    pub v2: Vector3,
+    /// Number of complete revolutions before arrival
+    pub revolutions: u32,
+}
+
+/// Burns needed to intercept a target, as computed by [intercept]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intercept {
+    /// Velocity change at departure \[**m/s**\]
+    pub departure_dv: Vector3,
+    /// Velocity change at arrival to match the target's velocity \[**m/s**\]
+    pub arrival_dv: Vector3,
+    /// Position of the target, and of the vessel, at arrival \[**m**\]
+    pub arrival_pos: Vector3,
+    /// Number of complete revolutions of the transfer
+    pub revolutions: u32,
+}
+
+/// Finds the transfer orbits from `r1` to `r2` \[**m**\] with a time of flight of `tof` \[**s**\] around a
+/// central body with gravitational parameter `gm` \[**m³/s²**\]
+///
+/// The single-revolution solution comes first, followed by two solutions for each number of complete
+/// revolutions up to `max_revolutions` for which a transfer exists. The result is empty if `r1` and `r2`
+/// are collinear with the central body, since the transfer plane is undefined.
+pub fn solve(
+    r1: &Vector3,
+    r2: &Vector3,
+    tof: f64,
+    gm: f64,
+    direction: Direction,
+    max_revolutions: u32,
+) -> Vec<LambertSolution> {
+    // The algorithm expects a right-handed frame to tell prograde from retrograde transfers
+    let (r1, r2) = (swap_yz(r1), swap_yz(r2));
+    let (r1_len, r2_len) = (r1.length(), r2.length());
+    let c = (r2 - r1).length();
+    let s = (r1_len + r2_len + c) / 2.0;
+    let (ir1, ir2) = (r1 / r1_len, r2 / r2_len);
+    let ih = ir1.cross(&ir2);
+    if tof <= 0.0 || ih.length() < 1e-12 {
+        return Vec::new();
+    }
+    let ih = ih.unit();
+
+    let mut lambda = (1.0 - c / s).max(0.0).sqrt();
+    let (mut it1, mut it2) = if ih.z() < 0.0 {
+        // Transfer angle larger than 180°
+        lambda = -lambda;
+        (ir1.cross(&ih), ir2.cross(&ih))
+    } else {
+        (ih.cross(&ir1), ih.cross(&ir2))
+    };
+    if direction == Direction::Retrograde {
+        lambda = -lambda;
+        it1 = -it1;
+        it2 = -it2;
+    }
+    let (it1, it2) = (it1.unit(), it2.unit());
+
+    // Non-dimensional time of flight
+    let t = (2.0 * gm / s.powi(3)).sqrt() * tof;
+    let solver = Solver { lambda };
+
+    let gamma = (gm * s / 2.0).sqrt();
+    let rho = (r1_len - r2_len) / c;
+    let sigma = (1.0 - rho * rho).max(0.0).sqrt();
+    let velocities = |x: f64, revolutions: u32| {
+        let y = solver.y(x);
+        let vr1 = gamma * ((lambda * y - x) - rho * (lambda * y + x)) / r1_len;
+        let vr2 = -gamma * ((lambda * y - x) + rho * (lambda * y + x)) / r2_len;
+        let vt = gamma * sigma * (y + lambda * x);
+        LambertSolution {
+            v1: swap_yz(&(ir1 * vr1 + it1 * (vt / r1_len))),
+            v2: swap_yz(&(ir2 * vr2 + it2 * (vt / r2_len))),
+            revolutions,
+        }
+    };
+
+    let mut solutions = Vec::new();
+    if let Some(x) = solver.householder(t, solver.initial_guess(t), 0) {
+        solutions.push(velocities(x, 0));
+    }
+    for revs in 1..=solver.max_revolutions(t).min(max_revolutions) {
+        let n = revs as f64;
+        let tmp = ((n * PI + PI) / (8.0 * t)).powf(2.0 / 3.0);
+        let x_left = (tmp - 1.0) / (tmp + 1.0);
+        let tmp = ((8.0 * t) / (n * PI)).powf(2.0 / 3.0);
+        let x_right = (tmp - 1.0) / (tmp + 1.0);
+        for x0 in [x_left, x_right] {
+            if let Some(x) = solver.householder(t, x0, revs) {
+                solutions.push(velocities(x, revs));
+            }
+        }
+    }
+    solutions
+}
+
+/// Computes the burns that take a vessel at `pos`, `vel` to the position of a target at `target_pos`,
+/// `target_vel` after `tof` seconds, and match its velocity there
+///
+/// All states are relative to the same central body with gravitational parameter `gm` \[**m³/s²**\].
+/// The transfer follows the direction of the vessel's current orbit. Of the transfers with up to
+/// `max_revolutions` complete revolutions, the one with the lowest total velocity change is returned.
+pub fn intercept(
+    pos: &Vector3,
+    vel: &Vector3,
+    target_pos: &Vector3,
+    target_vel: &Vector3,
+    gm: f64,
+    tof: f64,
+    max_revolutions: u32,
+) -> Option<Intercept> {
+    let (arrival_pos, arrival_vel) = propagate(target_pos, target_vel, gm, tof);
+    let direction = if swap_yz(pos).cross(&swap_yz(vel)).z() >= 0.0 {
+        Direction::Prograde
+    } else {
+        Direction::Retrograde
+    };
+    solve(pos, &arrival_pos, tof, gm, direction, max_revolutions)
+        .into_iter()
+        .map(|sol| Intercept {
+            departure_dv: sol.v1 - *vel,
+            arrival_dv: arrival_vel - sol.v2,
+            arrival_pos,
+            revolutions: sol.revolutions,
+        })
+        .min_by(|a, b| {
+            let total = |i: &Intercept| i.departure_dv.length() + i.arrival_dv.length();
+            total(a).total_cmp(&total(b))
+        })
+}
+
+/// Solver for the non-dimensional time of flight equation `T(x)` for a given `lambda`
+struct Solver {
+    lambda: f64,
+}
+
+impl Solver {
+    fn y(&self, x: f64) -> f64 {
+        let l2 = self.lambda * self.lambda;
+        (1.0 - l2 + l2 * x * x).sqrt()
+    }
+    /// Largest number of revolutions for which a transfer with time of flight `t` exists
+    fn max_revolutions(&self, t: f64) -> u32 {
+        let lambda = self.lambda;
+        let mut n_max = (t / PI).floor() as u32;
+        let t00 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+        if n_max > 0 && t < t00 + n_max as f64 * PI {
+            // Find the minimum time of flight for n_max revolutions with Halley's method
+            let (mut x, mut t_min) = (0.0, t00 + n_max as f64 * PI);
+            for _ in 0..12 {
+                let (dt, ddt, dddt) = self.derivatives(x, t_min);
+                if dt == 0.0 {
+                    break;
+                }
+                let x_new = x - dt * ddt / (ddt * ddt - dt * dddt / 2.0);
+                let err = (x - x_new).abs();
+                x = x_new;
+                t_min = self.tof(x, n_max);
+                if err < 1e-13 {
+                    break;
+                }
+            }
+            if t_min > t {
+                n_max -= 1;
+            }
+        }
+        n_max
+    }
+    fn initial_guess(&self, t: f64) -> f64 {
+        let lambda = self.lambda;
+        let t00 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+        let t1 = 2.0 / 3.0 * (1.0 - lambda.powi(3));
+        if t >= t00 {
+            -(t - t00) / (t - t00 + 4.0)
+        } else if t <= t1 {
+            t1 * (t1 - t) / (2.0 / 5.0 * (1.0 - lambda.powi(5)) * t) + 1.0
+        } else {
+            (t / t00).powf(std::f64::consts::LN_2 / (t1 / t00).ln()) - 1.0
+        }
+    }
+    /// Refines `x0` with Householder iterations until `T(x) = t`
+    fn householder(&self, t: f64, mut x0: f64, revs: u32) -> Option<f64> {
+        for _ in 0..50 {
+            let tof = self.tof(x0, revs);
+            let (dt, ddt, dddt) = self.derivatives(x0, tof);
+            let delta = tof - t;
+            let dt2 = dt * dt;
+            let x = x0 - delta * (dt2 - delta * ddt / 2.0) / (dt * (dt2 - delta * ddt) + dddt * delta * delta / 6.0);
+            if !x.is_finite() {
+                return None;
+            }
+            let err = (x - x0).abs();
+            x0 = x;
+            if err < 1e-13 {
+                return Some(x);
+            }
+        }
+        None
+    }
+    /// First three derivatives of `T(x)`, given `t = T(x)`
+    fn derivatives(&self, x: f64, t: f64) -> (f64, f64, f64) {
+        let l2 = self.lambda * self.lambda;
+        let l3 = l2 * self.lambda;
+        let umx2 = 1.0 - x * x;
+        let y = self.y(x);
+        let (y2, y3) = (y * y, y * y * y);
+        let dt = (3.0 * t * x - 2.0 + 2.0 * l3 * x / y) / umx2;
+        let ddt = (3.0 * t + 5.0 * x * dt + 2.0 * (1.0 - l2) * l3 / y3) / umx2;
+        let dddt = (7.0 * x * ddt + 8.0 * dt - 6.0 * (1.0 - l2) * l2 * l3 * x / y3 / y2) / umx2;
+        (dt, ddt, dddt)
+    }
+    /// Non-dimensional time of flight `T(x)` for `revs` complete revolutions
+    fn tof(&self, x: f64, revs: u32) -> f64 {
+        let lambda = self.lambda;
+        let n = revs as f64;
+        let dist = (x - 1.0).abs();
+        if dist < 0.2 && dist > 0.01 {
+            // Lagrange's expression
+            let a = 1.0 / (1.0 - x * x);
+            if a > 0.0 {
+                let alpha = 2.0 * x.acos();
+                let beta = (2.0 * (lambda * lambda / a).sqrt().asin()).copysign(lambda);
+                a * a.sqrt() * ((alpha - alpha.sin()) - (beta - beta.sin()) + 2.0 * PI * n) / 2.0
+            } else {
+                let alpha = 2.0 * x.acosh();
+                let beta = (2.0 * (-lambda * lambda / a).sqrt().asinh()).copysign(lambda);
+                -a * (-a).sqrt() * ((beta - beta.sinh()) - (alpha - alpha.sinh())) / 2.0
+            }
+        } else {
+            let k = lambda * lambda;
+            let e = x * x - 1.0;
+            let rho = e.abs();
+            let z = (1.0 + k * e).sqrt();
+            if dist < 0.01 {
+                // Battin's series expansion near the parabola
+                let eta = z - lambda * x;
+                let s1 = 0.5 * (1.0 - lambda - x * eta);
+                let q = 4.0 / 3.0 * hypergeometric(s1, 1e-11);
+                (eta.powi(3) * q + 4.0 * lambda * eta) / 2.0 + n * PI / rho.powf(1.5)
+            } else {
+                // Lancaster's expression
+                let y = rho.sqrt();
+                let g = x * z - lambda * e;
+                let d = if e < 0.0 {
+                    n * PI + g.acos()
+                } else {
+                    (y * (z - lambda * x) + g).ln()
+                };
+                (x - lambda * z - d / y) / e
+            }
+        }
+    }
+}
+
+/// Gauss hypergeometric function `2F1(3, 1, 5/2, z)`
+fn hypergeometric(z: f64, tol: f64) -> f64 {
+    let (mut sum, mut term) = (1.0, 1.0_f64);
+    let mut j = 0.0;
+    while term.abs() > tol && j < 1000.0 {
+        term *= (3.0 + j) * (1.0 + j) / (2.5 + j) * z / (j + 1.0);
+        sum += term;
+        j += 1.0;
+    }
+    sum
+}
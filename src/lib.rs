@@ -9,7 +9,7 @@
 //!
 //! ```
 //! use orbiter_rs::{
-//!    debug_string, OrbiterVessel, init_vessel, SDKVessel
+//!    debug_string, FileHandle, OrbiterVessel, init_vessel, SDKVessel
 //! };
 //! pub struct MinimalPB{
 //!     ctx: SDKVessel
@@ -22,9 +22,9 @@
 //!     }
 //! }
 //! impl OrbiterVessel for MinimalPB {
-//!     fn set_class_caps(&mut self, _cfg: FileHandle) {
+//!     fn set_class_caps(&mut self, _cfg: &FileHandle) {
 //!         self.ctx.SetSize(1.0);
-//!         self.ctx.AddMesh("ShuttlePB");
+//!         self.ctx.AddMesh("ShuttlePB".into());
 //!     }
 //!     fn on_pre_step(&mut self, sim_t: f64, _sim_dt: f64, _mjd: f64)
 //!     {
@@ -65,14 +65,85 @@
 //! `orbiter-rs` uses the [log] crate to facilitate logging directly to the Orbiter log. Any addons seeking to use this must call [`init_logging`] somewhere in their code, preferably in their [OrbiterVessel::set_class_caps] implementation.
 //! After the system is initialized, the macros [log::error], [log::warn], [log::info], [log::debug] and [log::trace] can be used. All of the [filtering features](log#compile-time-filters) of the log crate may be used as well.
 //!
-
-use cxx::ExternType;
+//! ## Features
+//!
+//! Only the `sdk` feature (enabled by default) builds the C++ bridge to Orbiter and therefore needs the `i686-pc-windows-msvc`
+//! target and an Orbiter installation. Everything else in the crate, such as [Vector3], [Key], [FileHandle] or the logging
+//! support, is plain Rust. With `default-features = false`, the crate builds on any host against an in-memory stand-in for the SDK.
+//!
+//! The `nalgebra` and `glam` features add `From` conversions between [Vector3], [Matrix3] and [Quaternion] and the
+//! corresponding `f64` types of these crates, e.g. `nalgebra::Vector3<f64>` or `glam::DVec3`.
+//!
+//! ## Testing
+//!
+//! Enabling the `mock` feature (or disabling `sdk`) replaces the Orbiter SDK with an in-memory stand-in that records every call made on
+//! [VesselContext]. This does not require an Orbiter installation and works on any platform, so the vessel logic of an
+//! addon can be unit-tested with `cargo test --features mock`. See the [mock] module for details. The [harness] module
+//! builds on this to simulate the motion of the vessel under thrust and gravity, driving the addon callbacks like Orbiter would.
+//!
+//! Sessions flown in Orbiter can be recorded with the [trace] module and replayed against the addon in a test.
+//!
+//! Addon structs may also be made generic over the [VesselApi] trait instead of holding an [SDKVessel] directly.
+//! This allows the same vessel logic to be driven by other implementations of the `VESSEL` interface.
+//!
 
 mod macros;
 
+mod vector;
+pub use vector::Vector3;
+
+mod matrix;
+pub use matrix::Matrix3;
+
+mod quaternion;
+pub use quaternion::Quaternion;
+
+mod interop;
+
+mod types;
+pub use types::*;
+
+mod io;
+pub use io::FileHandle;
+
 mod vessel;
 pub use vessel::OrbiterVessel;
 
+mod api;
+pub use api::VesselApi;
+
+pub mod frame;
+
+pub mod units;
+
+pub mod orbit;
+
+pub mod surface;
+
+pub mod kepler;
+
+pub mod lambert;
+
+pub mod maneuver;
+
+pub mod guidance;
+
+pub mod attitude;
+
+pub mod allocation;
+
+pub mod mass_properties;
+
+pub mod atmosphere;
+
+pub mod gravity;
+
+pub mod propulsion;
+
+pub mod time;
+
+pub mod ephemeris;
+
 mod input;
 pub use input::*;
 
@@ -80,5 +151,39 @@ mod logging;
 pub use logging::init_logging;
 pub use logging::OrbiterLogger;
 
+pub mod body;
+pub use body::Body;
+
+pub mod trace;
+
+#[cfg(not(orbiter_sdk))]
+pub mod mock;
+#[cfg(not(orbiter_sdk))]
+pub mod harness;
+
 // FFI interface to orbiter.rs
+#[cfg(orbiter_sdk)]
 include!("ffi.rs");
+
+// Without the Orbiter SDK, the bridge is replaced by an in-memory stand-in with the same interface
+#[cfg(not(orbiter_sdk))]
+#[doc(hidden)]
+pub use crate::mock::sdk as ffi;
+
+pub use ffi::VesselContext;
+#[doc(hidden)]
+pub use ffi::BoxDynVessel;
+
+pub use ffi::oapi_create_vessel;
+pub use ffi::oapi_get_planet_obliquity_matrix;
+pub use ffi::ODebug;
+
+pub use ffi::REFFRAME as ReferenceFrame;
+/// Type alias for [THGROUP_TYPE](ffi::THGROUP_TYPE)
+pub use ffi::THGROUP_TYPE as ThrusterGroupType;
+
+/// A wrapper over the FFI interface to the Orbiter SDK
+/// This is passed to the init() function in the `[init_vessel!]` macro
+///
+/// Calls made through it are written to the active [trace] recording, if any.
+pub type SDKVessel = trace::RecordingVessel<std::pin::Pin<&'static mut VesselContext>>;
@@ -63,7 +63,7 @@ impl Log for OrbiterLogger {
     /// Logs the `Record` based on current log level
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
-            let message = format!("{}: {}", record.level().to_string(), record.args());
+            let message = format!("{}: {}", record.level(), record.args());
             let message_cstr = CString::new(message).unwrap();
             unsafe { ffi::oapiWriteLog(message_cstr.as_ptr() as *mut c_char) }
         }
@@ -80,6 +80,8 @@ impl Default for OrbiterLogger {
 }
 
 /// Initialize orbiter logger with given minimum log level
+///
+/// Only the first call has any effect, so it is safe to call this from every vessel instance
 pub fn init_logging(level: Level) {
-    OrbiterLogger::new().level(level).init().unwrap();
+    let _ = OrbiterLogger::new().level(level).init();
 }
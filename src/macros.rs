@@ -8,6 +8,9 @@ macro_rules! V {
 /// Macro for defining ctype wrappers
 ///
 /// Adapted from [comment](https://github.com/dtolnay/cxx/issues/254#issuecomment-747860504) by Adrian Taylor
+///
+/// The wrappers are plain Rust types. The `ExternType` impls that expose them to the C++ bridge are
+/// only added when building against the Orbiter SDK.
 #[macro_export]
 macro_rules! ctype_wrapper {
     ($r:ident, $c:ty) => {
@@ -20,10 +23,6 @@ macro_rules! ctype_wrapper {
         #[allow(non_camel_case_types)]
         #[repr(transparent)]
         pub struct $r(pub $c);
-        unsafe impl cxx::ExternType for $r {
-            type Id = cxx::type_id!($r);
-            type Kind = cxx::kind::Trivial;
-        }
     };
     ($r:ident, $c:ty, $nice_name:ident) => {
         #[doc = "Newtype wrapper for `"]
@@ -35,10 +34,6 @@ macro_rules! ctype_wrapper {
         #[allow(non_camel_case_types)]
         #[repr(transparent)]
         pub struct $r(pub $c);
-        unsafe impl cxx::ExternType for $r {
-            type Id = cxx::type_id!($r);
-            type Kind = cxx::kind::Trivial;
-        }
         #[doc = "Type alias for ["]
         #[doc = stringify!($r)]
         #[doc = "]"]
@@ -63,6 +58,7 @@ macro_rules! ctype_wrapper {
 /// }
 /// fn exit() {}
 /// ```
+#[cfg(orbiter_sdk)]
 #[macro_export]
 macro_rules! init_vessel {
     (fn init($vessel_ident:ident) $init_block:block fn exit() $body_exit:block) => {
@@ -74,12 +70,34 @@ macro_rules! init_vessel {
             unsafe { $crate::ffi::vessel_ovcInit(hvessel, flightmodel, vessel_init) }
         }
         #[no_mangle]
+        #[allow(clippy::not_unsafe_ptr_arg_deref)]
         pub extern "C" fn ovcExit (vessel: *mut $crate::ffi::VESSEL)
         {
             $body_exit
             unsafe { $crate::ffi::vessel_ovcExit(vessel); }
         }
         pub fn vessel_init<'a> (vessel: std::pin::Pin<&'static mut $crate::ffi::VesselContext>) -> Box<dyn $crate::OrbiterVessel + 'static>
+        {
+            // Wrapped so that the calls made by the addon are recorded along with the callbacks
+            let $vessel_ident: $crate::SDKVessel = $crate::trace::RecordingVessel::new(vessel);
+            let spacecraft = {
+                $init_block
+            };
+            return Box::new(spacecraft);
+        }
+    };
+}
+
+/// Helper macro for defining entry point into a Vessel addon
+///
+/// Without the Orbiter SDK (the `sdk` feature is disabled or `mock` is enabled), no Orbiter entry points are exported. Instead, the macro generates
+/// `vessel_init()` and `vessel_exit()` functions that can be called from tests with an [`SDKVessel`](crate::SDKVessel)
+/// obtained from [`MockVessel::sdk_vessel`](crate::mock::MockVessel::sdk_vessel).
+#[cfg(not(orbiter_sdk))]
+#[macro_export]
+macro_rules! init_vessel {
+    (fn init($vessel_ident:ident) $init_block:block fn exit() $body_exit:block) => {
+        pub fn vessel_init(vessel: $crate::SDKVessel) -> Box<dyn $crate::OrbiterVessel + 'static>
         {
             let $vessel_ident = vessel;
             let spacecraft = {
@@ -87,6 +105,10 @@ macro_rules! init_vessel {
             };
             return Box::new(spacecraft);
         }
+        pub fn vessel_exit()
+        {
+            $body_exit
+        }
     };
 }
 
@@ -0,0 +1,269 @@
+//! Impulsive maneuver planning
+//!
+//! The functions of this module compute the delta-v of classic orbit changes around a central body with
+//! gravitational parameter `gm` (see [Body::gm](crate::Body::gm)): [hohmann] and [bi_elliptic] transfers
+//! between circular orbits, [plane_change]s, circularization and [deorbit] burns. Burns given as a vector
+//! use the same frame as the state vectors of [VesselStatus], and can be decomposed
+//! into prograde/normal/radial components with [DeltaV].
+//!
+//! An [Engine] turns a delta-v into a burn duration, from the thrust and ISP given to `CreateThruster` and
+//! the current mass of the vessel. [Engine::from_thruster] reads them back from the vessel:
+//!
+//! ```
+//! use orbiter_rs::maneuver::{hohmann, Engine};
+//! use orbiter_rs::mock::MockVessel;
+//! use orbiter_rs::units::{Force, SpecificImpulse, VesselUnits};
+//! use orbiter_rs::{Body, V};
+//!
+//! let earth = Body::EARTH;
+//! let transfer = hohmann(earth.gm(), earth.radius + 300e3, 42164e3);
+//! assert!((transfer.delta_vs[0] - 2427.8).abs() < 1.0);
+//!
+//! let mock = MockVessel::new("Tug");
+//! let vessel = mock.sdk_vessel();
+//! vessel.SetEmptyMass(4000.0);
+//! let ph = vessel.CreatePropellantResource(6000.0);
+//! let (thrust, isp) = (Force::kilonewtons(20.0), SpecificImpulse::seconds(310.0));
+//! let th = vessel.create_thruster(&V!(0., 0., -2.), &V!(0., 0., 1.), thrust, ph, isp);
+//!
+//! let engine = Engine::from_thruster(&vessel, th);
+//! assert_eq!(engine, Engine::new(thrust, isp));
+//! let mass = vessel.empty_mass() + vessel.total_propellant_mass();
+//! let burn_time = engine.burn_time(transfer.delta_vs[0], mass);
+//! assert!((burn_time.in_seconds() - 836.1).abs() < 1.0);
+//! ```
+//!
+//! The [VesselManeuver] extension trait converts burns between the orbital and the local vessel frame.
+use std::f64::consts::PI;
+
+use crate::frame::{Local, Vec3, VesselFrames};
+use crate::orbit::OrbitalElements;
+use crate::units::{Force, Mass, SpecificImpulse, Time, VesselUnits};
+use crate::{ThrusterHandle, Vector3, VesselApi, VesselStatus};
+
+/// A sequence of impulsive burns along the velocity vector
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Transfer {
+    /// Delta-v of each burn, positive prograde and negative retrograde \[**m/s**\]
+    pub delta_vs: Vec<f64>,
+    /// Time from the first to the last burn \[**s**\]
+    pub time_of_flight: f64,
+}
+
+impl Transfer {
+    /// Returns the sum of the magnitudes of all burns \[**m/s**\]
+    pub fn total_delta_v(&self) -> f64 {
+        self.delta_vs.iter().map(|dv| dv.abs()).sum()
+    }
+}
+
+/// Returns the Hohmann transfer between the coplanar circular orbits of radius `r1` and `r2` \[**m**\]
+pub fn hohmann(gm: f64, r1: f64, r2: f64) -> Transfer {
+    let a = (r1 + r2) / 2.0;
+    Transfer {
+        delta_vs: vec![
+            vis_viva(gm, r1, a) - vis_viva(gm, r1, r1),
+            vis_viva(gm, r2, r2) - vis_viva(gm, r2, a),
+        ],
+        time_of_flight: PI * (a.powi(3) / gm).sqrt(),
+    }
+}
+
+/// Returns the bi-elliptic transfer between the coplanar circular orbits of radius `r1` and `r2` through
+/// the intermediate apoapsis `rb` \[**m**\]
+///
+/// It needs less delta-v than the Hohmann transfer when `r2 / r1` is above about 11.94 and `rb` is large enough.
+pub fn bi_elliptic(gm: f64, r1: f64, rb: f64, r2: f64) -> Transfer {
+    let a1 = (r1 + rb) / 2.0;
+    let a2 = (rb + r2) / 2.0;
+    Transfer {
+        delta_vs: vec![
+            vis_viva(gm, r1, a1) - vis_viva(gm, r1, r1),
+            vis_viva(gm, rb, a2) - vis_viva(gm, rb, a1),
+            vis_viva(gm, r2, r2) - vis_viva(gm, r2, a2),
+        ],
+        time_of_flight: PI * ((a1.powi(3) / gm).sqrt() + (a2.powi(3) / gm).sqrt()),
+    }
+}
+
+/// Returns the delta-v \[**m/s**\] to turn a velocity of magnitude `speed` by `angle` \[**rad**\] without
+/// changing its magnitude
+pub fn plane_change(speed: f64, angle: f64) -> f64 {
+    2.0 * speed * (angle / 2.0).sin().abs()
+}
+
+/// Returns the delta-v \[**m/s**\] to turn a velocity of magnitude `v1` by `angle` \[**rad**\] while
+/// changing its magnitude to `v2`
+pub fn combined_plane_change(v1: f64, v2: f64, angle: f64) -> f64 {
+    (v1 * v1 + v2 * v2 - 2.0 * v1 * v2 * angle.cos()).max(0.0).sqrt()
+}
+
+/// Returns the burn vector \[**m/s**\] that makes the orbit circular at the current position, or `None`
+/// if the velocity is purely radial
+///
+/// `pos` and `vel` are relative to the central body. The burn keeps the direction of horizontal motion.
+pub fn circularize(pos: &Vector3, vel: &Vector3, gm: f64) -> Option<Vector3> {
+    let horizontal = horizontal(pos, vel)?;
+    let r = pos.length();
+    Some(horizontal * (gm / r).sqrt() - *vel)
+}
+
+/// Returns the prograde delta-v \[**m/s**\] at apoapsis that makes the orbit circular, or `None` for open orbits
+pub fn circularize_at_apoapsis(elements: &OrbitalElements, gm: f64) -> Option<f64> {
+    if elements.e >= 1.0 {
+        return None;
+    }
+    let r = elements.apoapsis();
+    Some(vis_viva(gm, r, r) - vis_viva(gm, r, elements.a))
+}
+
+/// Returns the retrograde (negative) delta-v \[**m/s**\] at periapsis that makes the orbit circular
+pub fn circularize_at_periapsis(elements: &OrbitalElements, gm: f64) -> f64 {
+    let r = elements.periapsis();
+    vis_viva(gm, r, r) - vis_viva(gm, r, elements.a)
+}
+
+/// Returns the burn vector \[**m/s**\] that lowers the periapsis to `periapsis` \[**m**\], or `None` if
+/// the target is not below the current position or the velocity is purely radial
+///
+/// The burn only changes the horizontal velocity, so it is performed along the local horizon.
+pub fn deorbit(pos: &Vector3, vel: &Vector3, gm: f64, periapsis: f64) -> Option<Vector3> {
+    let r = pos.length();
+    if periapsis >= r || periapsis <= 0.0 {
+        return None;
+    }
+    let horizontal = horizontal(pos, vel)?;
+    let v_radial = vel.dot(pos) / r;
+    let v_horizontal = vel.dot(&horizontal);
+    // Equal angular momentum and energy at the current position and at the new periapsis
+    let k = r / periapsis;
+    let target = ((v_radial * v_radial + 2.0 * gm * (1.0 / periapsis - 1.0 / r)) / (k * k - 1.0)).sqrt();
+    Some(horizontal * (target - v_horizontal))
+}
+
+/// Speed on an orbit with semi-major axis `a` at radius `r`
+fn vis_viva(gm: f64, r: f64, a: f64) -> f64 {
+    (gm * (2.0 / r - 1.0 / a)).sqrt()
+}
+
+/// Unit vector of the horizontal component of `vel`
+fn horizontal(pos: &Vector3, vel: &Vector3) -> Option<Vector3> {
+    let up = pos.unit();
+    let horizontal = *vel - up * vel.dot(&up);
+    (horizontal.length() > 1e-9 * vel.length()).then(|| horizontal.unit())
+}
+
+/// A burn decomposed along the orbital frame of the vessel \[**m/s**\]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DeltaV {
+    /// Component along the velocity
+    pub prograde: f64,
+    /// Component along the orbit normal, which points to the ecliptic north for prograde orbits
+    pub normal: f64,
+    /// Component perpendicular to the velocity in the orbital plane, pointing away from the central body
+    pub radial: f64,
+}
+
+impl DeltaV {
+    /// Build a new `DeltaV` from its components
+    pub const fn new(prograde: f64, normal: f64, radial: f64) -> Self {
+        Self { prograde, normal, radial }
+    }
+    /// Returns the unit prograde, normal and radial vectors of the orbit with position `pos` and velocity
+    /// `vel` relative to the central body
+    pub fn axes(pos: &Vector3, vel: &Vector3) -> (Vector3, Vector3, Vector3) {
+        let prograde = vel.unit();
+        // Orbiter's frame is left-handed, so the angular momentum is v × r
+        let normal = vel.cross(pos).unit();
+        let radial = (*pos - prograde * pos.dot(&prograde)).unit();
+        (prograde, normal, radial)
+    }
+    /// Decomposes the burn vector `dv`, in the frame of `pos` and `vel`
+    pub fn from_vector(dv: &Vector3, pos: &Vector3, vel: &Vector3) -> Self {
+        let (prograde, normal, radial) = Self::axes(pos, vel);
+        Self::new(dv.dot(&prograde), dv.dot(&normal), dv.dot(&radial))
+    }
+    /// Returns the burn vector in the frame of `pos` and `vel`
+    pub fn to_vector(&self, pos: &Vector3, vel: &Vector3) -> Vector3 {
+        let (prograde, normal, radial) = Self::axes(pos, vel);
+        prograde * self.prograde + normal * self.normal + radial * self.radial
+    }
+    /// Returns the magnitude of the burn
+    pub fn length(&self) -> f64 {
+        (self.prograde.powi(2) + self.normal.powi(2) + self.radial.powi(2)).sqrt()
+    }
+}
+
+/// Thrust and specific impulse of an engine, or of a cluster of engines firing together
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Engine {
+    /// Thrust at full throttle
+    pub thrust: Force,
+    /// Specific impulse
+    pub isp: SpecificImpulse,
+}
+
+impl Engine {
+    /// Define an engine with the same values as passed to `CreateThruster`
+    pub const fn new(thrust: Force, isp: SpecificImpulse) -> Self {
+        Self { thrust, isp }
+    }
+    /// Reads the vacuum thrust and ISP of the thruster `th` from the vessel
+    pub fn from_thruster(vessel: &(impl VesselApi + ?Sized), th: ThrusterHandle) -> Self {
+        Self::new(vessel.thruster_max_thrust(th), vessel.thruster_isp(th))
+    }
+    /// Combines engines firing in the same direction at full throttle
+    pub fn cluster(engines: &[Engine]) -> Self {
+        let thrust: Force = engines.iter().map(|e| e.thrust).sum();
+        let mass_flow: f64 = engines
+            .iter()
+            .map(|e| e.thrust.in_newtons() / e.isp.in_meters_per_second())
+            .sum();
+        Self {
+            thrust,
+            isp: SpecificImpulse::meters_per_second(thrust.in_newtons() / mass_flow),
+        }
+    }
+    /// Returns the propellant mass consumed to change the velocity by `delta_v` \[**m/s**\] from the
+    /// initial `mass`, from the rocket equation
+    pub fn propellant_mass(&self, delta_v: f64, mass: Mass) -> Mass {
+        mass * (1.0 - (-delta_v.abs() / self.isp.in_meters_per_second()).exp())
+    }
+    /// Returns the duration of a burn at full throttle changing the velocity by `delta_v` \[**m/s**\] from
+    /// the initial `mass`
+    pub fn burn_time(&self, delta_v: f64, mass: Mass) -> Time {
+        self.propellant_mass(delta_v, mass) * self.isp / self.thrust
+    }
+    /// Returns how long before the maneuver node the burn has to start, so that half of `delta_v` \[**m/s**\]
+    /// is spent on either side of it
+    pub fn lead_time(&self, delta_v: f64, mass: Mass) -> Time {
+        self.burn_time(delta_v / 2.0, mass)
+    }
+    /// Returns the delta-v \[**m/s**\] from burning `propellant` out of the initial `mass`
+    pub fn delta_v(&self, propellant: Mass, mass: Mass) -> f64 {
+        self.isp.in_meters_per_second() * (mass / (mass - propellant)).ln()
+    }
+}
+
+/// Conversion of burns between the orbital frame and the local vessel frame
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselManeuver: VesselApi {
+    /// Returns the burn `dv` in local vessel coordinates, e.g. to point the vessel or set thruster levels
+    fn delta_v_to_local(&self, dv: &DeltaV) -> Vec3<Local> {
+        let (pos, vel) = self.orbit_state();
+        self.global_dir_to_local(&Vec3::from_vector(dv.to_vector(&pos, &vel)))
+    }
+    /// Decomposes the burn `dv`, given in local vessel coordinates, along the orbital frame
+    fn local_to_delta_v(&self, dv: &Vec3<Local>) -> DeltaV {
+        let (pos, vel) = self.orbit_state();
+        DeltaV::from_vector(&self.local_dir_to_global(dv).vector(), &pos, &vel)
+    }
+    /// Returns the position and velocity of the vessel relative to its reference body
+    fn orbit_state(&self) -> (Vector3, Vector3) {
+        let mut status = VesselStatus::default();
+        self.GetStatus(&mut status);
+        (status.rpos, status.rvel)
+    }
+}
+impl<V: VesselApi + ?Sized> VesselManeuver for V {}
@@ -0,0 +1,316 @@
+//! Mass properties of a vessel built from simple components
+//!
+//! A [MassModel] describes the vessel as a set of [Component]s, each with a [Shape] placed at an offset from
+//! the origin of the vessel frame. Its mass is either fixed or the current mass of a propellant resource,
+//! as returned by `GetPropellantMass`. From these, [MassProperties] gives the total mass, the centre of
+//! mass, the inertia tensor and its principal axes, and the mass-normalised principal moments of inertia
+//! expected by `SetPMI`.
+//!
+//! [VesselMassProperties::apply_mass_properties] sets the empty mass and PMI of the vessel from the model,
+//! and can be called on every step to follow the propellant as it drains:
+//!
+//! ```
+//! use orbiter_rs::mass_properties::{Component, MassModel, Shape, VesselMassProperties};
+//! use orbiter_rs::mock::{MockCall, MockVessel};
+//! use orbiter_rs::V;
+//!
+//! let mock = MockVessel::new("Lander");
+//! let vessel = mock.sdk_vessel();
+//! let ph = vessel.CreatePropellantResource(100.0);
+//!
+//! let mut model = MassModel::new();
+//! model.push(Component::new(500.0, Shape::Cylinder { radius: 1.0, length: 2.0 }, V!(0.0, 0.0, 0.0)));
+//! model.push(Component::tank(ph, Shape::Sphere { radius: 0.5 }, V!(0.0, 0.0, 1.5)));
+//!
+//! let full = vessel.apply_mass_properties(&model);
+//! assert_eq!(full.mass, 600.0);
+//! assert!((full.center_of_mass.z() - 0.25).abs() < 1e-12);
+//! assert_eq!(
+//!     mock.calls()[1..],
+//!     [MockCall::GetPropellantMass(ph), MockCall::SetEmptyMass(500.0), MockCall::SetPMI(full.pmi())]
+//! );
+//!
+//! // The centre of mass moves back as the tank drains
+//! mock.state_mut().propellants[0].mass = 20.0;
+//! let drained = vessel.mass_properties(&model);
+//! assert!((drained.center_of_mass.z() - 30.0 / 520.0).abs() < 1e-12);
+//! ```
+use crate::{Matrix3, PropellantHandle, Vector3, VesselApi};
+
+/// Maximum number of Jacobi sweeps when diagonalising the inertia tensor
+const MAX_JACOBI_SWEEPS: usize = 50;
+
+/// Geometry of a component, assumed to be of uniform density
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// A point mass
+    Point,
+    /// A solid sphere
+    Sphere {
+        /// Radius \[**m**\]
+        radius: f64,
+    },
+    /// A solid cylinder along the `z` axis of the component
+    Cylinder {
+        /// Radius \[**m**\]
+        radius: f64,
+        /// Length along the axis \[**m**\]
+        length: f64,
+    },
+    /// A solid rectangular box
+    Cuboid {
+        /// Edge lengths along the `x`, `y` and `z` axes of the component \[**m**\]
+        size: Vector3,
+    },
+}
+
+impl Shape {
+    /// Returns the principal moments of inertia about the centre of the shape, along the axes of the
+    /// component, for a unit mass \[**m²**\]
+    pub fn normalised_inertia(&self) -> Vector3 {
+        match *self {
+            Shape::Point => Vector3::default(),
+            Shape::Sphere { radius } => {
+                let i = 0.4 * radius * radius;
+                Vector3::new(i, i, i)
+            }
+            Shape::Cylinder { radius, length } => {
+                let i = (3.0 * radius * radius + length * length) / 12.0;
+                Vector3::new(i, i, 0.5 * radius * radius)
+            }
+            Shape::Cuboid { size } => {
+                let [x2, y2, z2] = size.component_mul(&size).0;
+                Vector3::new(y2 + z2, x2 + z2, x2 + y2) / 12.0
+            }
+        }
+    }
+}
+
+/// Where the mass of a component comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComponentMass {
+    /// A constant mass, part of the empty mass of the vessel \[**kg**\]
+    Fixed(f64),
+    /// The current mass of a propellant resource, which fills the shape of the component
+    Propellant(PropellantHandle),
+}
+
+/// A part of the vessel contributing to its mass properties
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Component {
+    /// Mass of the component
+    pub mass: ComponentMass,
+    /// Geometry of the component
+    pub shape: Shape,
+    /// Position of the centre of the component in vessel coordinates \[**m**\]
+    pub offset: Vector3,
+    /// Rotation from the axes of the component to the vessel frame
+    pub rotation: Matrix3,
+}
+
+impl Component {
+    /// Build a new `Component` of fixed `mass` \[**kg**\], aligned with the vessel axes
+    pub fn new(mass: f64, shape: Shape, offset: Vector3) -> Self {
+        Self {
+            mass: ComponentMass::Fixed(mass),
+            shape,
+            offset,
+            rotation: Matrix3::IDENTITY,
+        }
+    }
+    /// Build a point mass of `mass` \[**kg**\]
+    pub fn point(mass: f64, offset: Vector3) -> Self {
+        Self::new(mass, Shape::Point, offset)
+    }
+    /// Build a tank holding the propellant resource `ph`, aligned with the vessel axes
+    ///
+    /// The structure of the tank itself should be added as a separate fixed-mass component.
+    pub fn tank(ph: PropellantHandle, shape: Shape, offset: Vector3) -> Self {
+        Self {
+            mass: ComponentMass::Propellant(ph),
+            shape,
+            offset,
+            rotation: Matrix3::IDENTITY,
+        }
+    }
+    /// Returns the same component with its axes rotated into the vessel frame by `rotation`
+    pub fn with_rotation(mut self, rotation: Matrix3) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+/// Mass, centre of mass and inertia of a vessel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    /// Total mass \[**kg**\]
+    pub mass: f64,
+    /// Centre of mass in vessel coordinates \[**m**\]
+    pub center_of_mass: Vector3,
+    /// Inertia tensor about the centre of mass, along the vessel axes \[**kg·m²**\]
+    pub inertia: Matrix3,
+}
+
+impl MassProperties {
+    /// Returns the mass-normalised moments of inertia about the vessel axes, as expected by `SetPMI` \[**m²**\]
+    ///
+    /// Orbiter assumes that the vessel axes are the principal axes of inertia, so the products of inertia
+    /// are ignored. [principal_axes](Self::principal_axes) tells how far off this assumption is.
+    pub fn pmi(&self) -> Vector3 {
+        if self.mass <= 0.0 {
+            return Vector3::default();
+        }
+        let m = &self.inertia.0;
+        Vector3::new(m[0][0], m[1][1], m[2][2]) / self.mass
+    }
+    /// Returns the principal moments of inertia \[**kg·m²**\] and the matrix whose columns are the
+    /// corresponding principal axes in vessel coordinates
+    ///
+    /// The axes are ordered and oriented to match the vessel axes they are closest to, so the moments can
+    /// be compared to the diagonal of the inertia tensor.
+    pub fn principal_axes(&self) -> (Vector3, Matrix3) {
+        let (moments, axes) = symmetric_eigen(&self.inertia);
+        // Pick the assignment of eigenvectors to vessel axes with the best alignment
+        let permutations = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        let alignment = |p: &[usize; 3]| (0..3).map(|i| axes.0[i][p[i]].abs()).sum::<f64>();
+        let best = permutations
+            .iter()
+            .max_by(|a, b| alignment(a).total_cmp(&alignment(b)))
+            .unwrap_or(&permutations[0]);
+        let cols: [Vector3; 3] = std::array::from_fn(|i| {
+            let axis = axes.col(best[i]);
+            if axis.0[i] < 0.0 {
+                -axis
+            } else {
+                axis
+            }
+        });
+        (
+            Vector3::new(moments[best[0]], moments[best[1]], moments[best[2]]),
+            Matrix3::from_cols(&cols[0], &cols[1], &cols[2]),
+        )
+    }
+}
+
+/// A vessel described as a set of components
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MassModel {
+    components: Vec<Component>,
+}
+
+impl MassModel {
+    /// Build an empty `MassModel`
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds a component to the model
+    pub fn push(&mut self, component: Component) {
+        self.components.push(component);
+    }
+    /// Returns the components of the model
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+    /// Returns the total mass of the fixed-mass components, to be passed to `SetEmptyMass` \[**kg**\]
+    pub fn empty_mass(&self) -> f64 {
+        self.components
+            .iter()
+            .map(|component| match component.mass {
+                ComponentMass::Fixed(mass) => mass,
+                ComponentMass::Propellant(_) => 0.0,
+            })
+            .sum()
+    }
+    /// Returns the mass properties with the propellant masses given by `propellant_mass` \[**kg**\]
+    pub fn properties(&self, propellant_mass: impl Fn(PropellantHandle) -> f64) -> MassProperties {
+        let masses: Vec<f64> = self
+            .components
+            .iter()
+            .map(|component| match component.mass {
+                ComponentMass::Fixed(mass) => mass,
+                ComponentMass::Propellant(ph) => propellant_mass(ph),
+            })
+            .collect();
+        let mass: f64 = masses.iter().sum();
+        let mut center_of_mass = Vector3::default();
+        if mass > 0.0 {
+            for (component, m) in self.components.iter().zip(&masses) {
+                center_of_mass += component.offset * (*m / mass);
+            }
+        }
+        let mut inertia = Matrix3::new([[0.0; 3]; 3]);
+        for (component, &m) in self.components.iter().zip(&masses) {
+            // Rotate the inertia of the shape into the vessel axes, then move it to the centre of mass
+            let [ix, iy, iz] = (component.shape.normalised_inertia() * m).0;
+            let local = Matrix3::new([[ix, 0.0, 0.0], [0.0, iy, 0.0], [0.0, 0.0, iz]]);
+            let r = component.rotation;
+            let d = (component.offset - center_of_mass).0;
+            let d2 = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+            let parallel = Matrix3::new(std::array::from_fn(|i| {
+                std::array::from_fn(|j| m * (if i == j { d2 } else { 0.0 } - d[i] * d[j]))
+            }));
+            inertia = inertia + r * local * r.transpose() + parallel;
+        }
+        MassProperties {
+            mass,
+            center_of_mass,
+            inertia,
+        }
+    }
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a symmetric matrix by the cyclic Jacobi method
+fn symmetric_eigen(m: &Matrix3) -> ([f64; 3], Matrix3) {
+    let mut a = m.0;
+    let mut v = Matrix3::IDENTITY.0;
+    let scale: f64 = a.iter().flatten().map(|x| x * x).sum();
+    for _ in 0..MAX_JACOBI_SWEEPS {
+        let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+        if off <= f64::EPSILON * f64::EPSILON * scale {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q] == 0.0 {
+                continue;
+            }
+            // Rotation in the (p, q) plane zeroing a[p][q]
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+            for row in a.iter_mut().chain(v.iter_mut()) {
+                let (kp, kq) = (row[p], row[q]);
+                row[p] = c * kp - s * kq;
+                row[q] = s * kp + c * kq;
+            }
+            let (rp, rq) = (a[p], a[q]);
+            for k in 0..3 {
+                a[p][k] = c * rp[k] - s * rq[k];
+                a[q][k] = s * rp[k] + c * rq[k];
+            }
+        }
+    }
+    ([a[0][0], a[1][1], a[2][2]], Matrix3::new(v))
+}
+
+/// Mass properties of the vessel from a [MassModel]
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselMassProperties: VesselApi {
+    /// Returns the mass properties of `model` with the current propellant masses of the vessel
+    fn mass_properties(&self, model: &MassModel) -> MassProperties {
+        model.properties(|ph| self.GetPropellantMass(ph))
+    }
+    /// Sets the empty mass and the PMI of the vessel from `model` with the current propellant masses
+    ///
+    /// Returns the mass properties that were applied. The PMI is left unchanged while the vessel has no mass.
+    fn apply_mass_properties(&self, model: &MassModel) -> MassProperties {
+        let properties = self.mass_properties(model);
+        self.SetEmptyMass(model.empty_mass());
+        if properties.mass > 0.0 {
+            self.SetPMI(&properties.pmi());
+        }
+        properties
+    }
+}
+impl<V: VesselApi + ?Sized> VesselMassProperties for V {}
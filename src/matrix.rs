@@ -0,0 +1,188 @@
+use crate::{Vector3, V};
+
+/// A 3x3 matrix with the same memory layout as `MATRIX3` in the Orbiter SDK
+///
+/// The elements are stored row by row, i.e. `self.0[1][2]` is `m23`. Rotation matrices returned by Orbiter
+/// transform vectors from the local vessel frame to the global frame (`global = R * local`).
+///
+/// ```
+/// use orbiter_rs::{Matrix3, V};
+///
+/// let rot = Matrix3::rotation_z(std::f64::consts::FRAC_PI_2);
+/// let v = rot * V!(1.0, 0.0, 0.0);
+/// assert!((&v - V!(0.0, 1.0, 0.0)).length() < 1e-12);
+/// assert!((rot.tmul(&v) - V!(1.0, 0.0, 0.0)).length() < 1e-12);
+///
+/// let m = Matrix3::from_euler(0.1, 0.2, 0.3) * 2.0;
+/// let prod = m * m.inverse().unwrap();
+/// assert!((0..3).all(|i| (prod.row(i) - Matrix3::IDENTITY.row(i)).length() < 1e-12));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Matrix3(pub [[f64; 3]; 3]);
+
+impl Matrix3 {
+    /// The identity matrix
+    pub const IDENTITY: Self = Self([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+
+    /// Build a new `Matrix3` from its rows
+    pub const fn new(rows: [[f64; 3]; 3]) -> Self {
+        Self(rows)
+    }
+    /// Build a matrix with the given row vectors
+    pub fn from_rows(r1: &Vector3, r2: &Vector3, r3: &Vector3) -> Self {
+        Self([r1.0, r2.0, r3.0])
+    }
+    /// Build a matrix with the given column vectors
+    pub fn from_cols(c1: &Vector3, c2: &Vector3, c3: &Vector3) -> Self {
+        Self::from_rows(c1, c2, c3).transpose()
+    }
+    /// Rotation by `angle` radians about the `x` axis
+    pub fn rotation_x(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self([[1.0, 0.0, 0.0], [0.0, c, -s], [0.0, s, c]])
+    }
+    /// Rotation by `angle` radians about the `y` axis
+    pub fn rotation_y(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self([[c, 0.0, s], [0.0, 1.0, 0.0], [-s, 0.0, c]])
+    }
+    /// Rotation by `angle` radians about the `z` axis
+    pub fn rotation_z(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self([[c, -s, 0.0], [s, c, 0.0], [0.0, 0.0, 1.0]])
+    }
+    /// Rotation by `angle` radians about `axis` (Rodrigues' formula)
+    ///
+    /// `axis` does not need to be normalised.
+    pub fn from_axis_angle(axis: &Vector3, angle: f64) -> Self {
+        let k = axis.unit();
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        let (x, y, z) = (k.x(), k.y(), k.z());
+        Self([
+            [c + t * x * x, t * x * y - s * z, t * x * z + s * y],
+            [t * x * y + s * z, c + t * y * y, t * y * z - s * x],
+            [t * x * z - s * y, t * y * z + s * x, c + t * z * z],
+        ])
+    }
+    /// Rotation matrix for the Euler angles used by Orbiter, e.g. in [VesselStatus::arot](crate::VesselStatus::arot)
+    ///
+    /// Orbiter's elementary rotations are defined for its left-handed frames and are the transposes of
+    /// [rotation_x](Self::rotation_x) etc., so the result is `Rx(-alpha) * Ry(-beta) * Rz(-gamma)`.
+    pub fn from_euler(alpha: f64, beta: f64, gamma: f64) -> Self {
+        Self::rotation_x(-alpha) * Self::rotation_y(-beta) * Self::rotation_z(-gamma)
+    }
+    /// Returns the Orbiter Euler angles `(alpha, beta, gamma)` of a rotation matrix
+    ///
+    /// This is the inverse of [from_euler](Self::from_euler), with `beta` in `[-pi/2, pi/2]`.
+    pub fn euler(&self) -> Vector3 {
+        let m = &self.0;
+        V!(m[1][2].atan2(m[2][2]), -m[0][2].clamp(-1.0, 1.0).asin(), m[0][1].atan2(m[0][0]))
+    }
+    /// Returns row `i` as a vector
+    pub fn row(&self, i: usize) -> Vector3 {
+        Vector3(self.0[i])
+    }
+    /// Returns column `j` as a vector
+    pub fn col(&self, j: usize) -> Vector3 {
+        V!(self.0[0][j], self.0[1][j], self.0[2][j])
+    }
+    /// Returns the transposed matrix
+    pub fn transpose(&self) -> Self {
+        let m = &self.0;
+        Self([
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ])
+    }
+    /// Computes the determinant of the matrix
+    pub fn determinant(&self) -> f64 {
+        self.row(0).dot(&self.row(1).cross(&self.row(2)))
+    }
+    /// Computes the inverse of the matrix, or `None` if it is singular
+    ///
+    /// For rotation matrices, [transpose](Self::transpose) gives the same result and is cheaper.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (r0, r1, r2) = (self.row(0), self.row(1), self.row(2));
+        // The columns of the inverse are the cross products of the rows, scaled by 1/det
+        let cofactors = Self::from_cols(&r1.cross(&r2), &r2.cross(&r0), &r0.cross(&r1));
+        Some(cofactors * (1.0 / det))
+    }
+    /// Multiplies the transposed matrix with `v`
+    ///
+    /// For a rotation matrix, this transforms a vector from the global frame back to the local vessel frame.
+    pub fn tmul(&self, v: &Vector3) -> Vector3 {
+        V!(self.col(0).dot(v), self.col(1).dot(v), self.col(2).dot(v))
+    }
+}
+
+/// Implement matrix multiplication for `Matrix3`
+impl std::ops::Mul for Matrix3 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| std::array::from_fn(|j| self.row(i).dot(&rhs.col(j)))))
+    }
+}
+/// Implement matrix multiplication for `Matrix3`
+impl std::ops::Mul<&Matrix3> for Matrix3 {
+    type Output = Self;
+    fn mul(self, rhs: &Matrix3) -> Self::Output {
+        self * *rhs
+    }
+}
+/// Implement matrix-vector multiplication for `Matrix3`
+impl std::ops::Mul<&Vector3> for &Matrix3 {
+    type Output = Vector3;
+    fn mul(self, rhs: &Vector3) -> Self::Output {
+        V!(
+            self.row(0).dot(rhs),
+            self.row(1).dot(rhs),
+            self.row(2).dot(rhs)
+        )
+    }
+}
+/// Implement matrix-vector multiplication for `Matrix3`
+impl std::ops::Mul<&Vector3> for Matrix3 {
+    type Output = Vector3;
+    fn mul(self, rhs: &Vector3) -> Self::Output {
+        V!(self.row(0).dot(rhs), self.row(1).dot(rhs), self.row(2).dot(rhs))
+    }
+}
+/// Implement matrix-vector multiplication for `Matrix3`
+impl std::ops::Mul<Vector3> for Matrix3 {
+    type Output = Vector3;
+    fn mul(self, rhs: Vector3) -> Self::Output {
+        self * &rhs
+    }
+}
+/// Scalar multiplication for `Matrix3`
+impl std::ops::Mul<f64> for Matrix3 {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self(self.0.map(|row| row.map(|value| value * rhs)))
+    }
+}
+/// Implement addition operator for `Matrix3`
+impl std::ops::Add for Matrix3 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.0[i][j] + rhs.0[i][j])
+        }))
+    }
+}
+/// Implement subtraction operator for `Matrix3`
+impl std::ops::Sub for Matrix3 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.0[i][j] - rhs.0[i][j])
+        }))
+    }
+}
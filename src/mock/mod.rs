@@ -0,0 +1,436 @@
+//! In-memory stand-in for the Orbiter SDK
+//!
+//! When the `mock` feature is enabled or the `sdk` feature is disabled, the C++ bridge to Orbiter is replaced by a pure-Rust
+//! implementation of [`VesselContext`](crate::VesselContext) with the same methods. Every call
+//! made by the addon is recorded as a [`MockCall`], and the values returned by the getters can be
+//! set up through [`MockState`]. This allows `OrbiterVessel` implementations to be unit-tested on
+//! any platform without an Orbiter installation:
+//!
+//! ```
+//! use orbiter_rs::mock::{MockCall, MockVessel};
+//! use orbiter_rs::{FileHandle, OrbiterVessel, SDKVessel, V};
+//!
+//! struct Probe {
+//!     ctx: SDKVessel,
+//! }
+//! impl OrbiterVessel for Probe {
+//!     fn set_class_caps(&mut self, _cfg: &FileHandle) {
+//!         self.ctx.SetSize(1.0);
+//!         self.ctx.AddMeshWithOffset("Probe".into(), &V!(0.0, 0.0, 1.0));
+//!     }
+//! }
+//!
+//! let mock = MockVessel::new("Probe-1");
+//! let mut probe = Probe { ctx: mock.sdk_vessel() };
+//! probe.set_class_caps(&mock.config_file(""));
+//! assert_eq!(
+//!     *mock.calls(),
+//!     [
+//!         MockCall::SetSize(1.0),
+//!         MockCall::AddMeshWithOffset("Probe".into(), V!(0.0, 0.0, 1.0)),
+//!     ]
+//! );
+//! ```
+//!
+//! Functions that are not bound to a vessel in the SDK (e.g. [`oapi_create_vessel`](crate::oapi_create_vessel),
+//! [`debug_string!`](crate::debug_string) or the logger) are recorded per-thread and can be
+//! inspected with [`created_vessels`], [`debug_string`] and [`log_lines`].
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::VecDeque;
+use std::os::raw::c_char;
+use std::rc::Rc;
+
+use crate::{
+    Elements, FileHandle, Key, KeyStates, Matrix3, OrbitParam, OrbiterVessel, PropellantHandle,
+    SDKVessel, ThrusterGroupType, ThrusterHandle, Vector3, VesselStatus, OBJHANDLE,
+};
+use crate::surface::EquPos;
+use crate::trace::{self, RecordingVessel, TraceEvent, VesselReturn};
+
+#[doc(hidden)]
+pub mod sdk;
+
+/// A call made by the addon to [`VesselContext`](crate::VesselContext)
+pub use crate::trace::VesselCall as MockCall;
+
+/// A propellant resource created with `CreatePropellantResource`
+#[derive(Debug, Default)]
+pub struct MockPropellant {
+    /// Capacity of the resource \[**kg**\]
+    pub max_mass: f64,
+    /// Current propellant mass \[**kg**\]
+    pub mass: f64,
+}
+
+/// A thruster created with `CreateThruster`
+#[derive(Debug, Default)]
+pub struct MockThruster {
+    /// Thrust attack point in vessel coordinates \[**m**\]
+    pub pos: Vector3,
+    /// Thrust direction in vessel coordinates
+    pub dir: Vector3,
+    /// Vacuum thrust rating \[**N**\]
+    pub max_thrust: f64,
+    /// Propellant resource feeding the thruster
+    pub propellant: PropellantHandle,
+    /// Vacuum fuel-specific impulse \[**m/s**\]
+    pub isp: f64,
+    /// Thrust level set with `SetThrusterLevel`
+    pub level: f64,
+    /// Thrust level set with `SetThrusterLevel_SingleStep` for the current time step only
+    pub single_step_level: Option<f64>,
+}
+impl MockThruster {
+    /// Returns the thrust level in effect for the current time step
+    pub fn effective_level(&self) -> f64 {
+        self.single_step_level.unwrap_or(self.level)
+    }
+}
+
+/// A thruster group created with `CreateThrusterGroup`
+#[derive(Debug)]
+pub struct MockThrusterGroup {
+    /// Thrusters in the group
+    pub thrusters: Vec<ThrusterHandle>,
+    /// Group type
+    pub group_type: ThrusterGroupType,
+}
+
+/// A mesh added with `AddMesh` or `AddMeshWithOffset`
+#[derive(Debug, PartialEq)]
+pub struct MockMesh {
+    /// Name of the mesh file
+    pub name: String,
+    /// Offset of the mesh origin against the vessel origin
+    pub offset: Vector3,
+}
+
+/// State of a mocked vessel
+///
+/// The first group of fields is filled in by the addon through the `VesselContext` setters.
+/// The second group holds the values returned by the getters and can be changed freely by the test.
+#[derive(Debug)]
+pub struct MockState {
+    /// Every call made on the vessel, in order
+    pub calls: Vec<MockCall>,
+
+    /// Mean radius set with `SetSize` \[**m**\]
+    pub size: f64,
+    /// Mass-normalised principal moments of inertia set with `SetPMI` \[**m²**\]
+    pub pmi: Vector3,
+    /// Empty mass set with `SetEmptyMass` \[**kg**\]
+    pub empty_mass: f64,
+    /// Cockpit camera offset set with `SetCameraOffset`
+    pub camera_offset: Vector3,
+    /// Ground contact points set with `SetTouchdownPoints`
+    pub touchdown_points: Option<[Vector3; 3]>,
+    /// Current mesh definitions
+    pub meshes: Vec<MockMesh>,
+    /// Propellant resources. The handle of a resource is its index plus one.
+    pub propellants: Vec<MockPropellant>,
+    /// Thrusters. The handle of a thruster is its index plus one.
+    pub thrusters: Vec<MockThruster>,
+    /// Thruster groups. The handle of a group is its index plus one.
+    pub thruster_groups: Vec<MockThrusterGroup>,
+
+    /// Value returned by `GetStatus`
+    pub status: VesselStatus,
+    /// Position of the vessel in the global frame, used by `Local2Global` and `Global2Local` \[**m**\]
+    pub global_pos: Vector3,
+    /// Value returned by `GetRotationMatrix`, also used by `Local2Global` and `Global2Local`
+    pub rotation: Matrix3,
+    /// Value returned by `GetAngularVel` \[**rad/s**\]
+    pub angular_vel: Vector3,
+    /// Values returned by `GetAirspeedVector`, indexed by [ReferenceFrame](crate::ReferenceFrame) \[**m/s**\]
+    pub airspeed: [Vector3; 4],
+    /// Value returned by `GetAltitude` \[**m**\]
+    pub altitude: f64,
+    /// Value returned by `GetSurfaceElevation` \[**m**\]
+    pub surface_elevation: f64,
+    /// Value returned by `GetAtmTemperature` \[**K**\]
+    pub atm_temperature: f64,
+    /// Value returned by `GetAtmDensity` \[**kg/m³**\]
+    pub atm_density: f64,
+    /// Value returned by `GetAtmPressure` \[**Pa**\]
+    pub atm_pressure: f64,
+    /// Value returned by `GroundContact`
+    pub ground_contact: bool,
+    /// Value returned by `GetSurfaceRef`
+    pub surface_ref: OBJHANDLE,
+    /// Value returned by `GetRelativeVel` \[**m/s**\]
+    pub relative_vel: Vector3,
+    /// Value returned by `GetEquPos`
+    pub equ_pos: EquPos,
+    /// Value returned by `GetRotDrag`
+    pub rot_drag: Vector3,
+    /// Elements returned by `GetElements`, replaced by `SetElements`
+    pub elements: Elements,
+    /// Secondary orbit parameters returned by `GetElements` and `SetElements`
+    pub orbit_param: OrbitParam,
+
+    /// Values to be returned by the next getter calls instead of the ones above, used by
+    /// [replay](crate::trace::replay). A value is only used if it belongs to the getter that is called.
+    pub returns: VecDeque<VesselReturn>,
+}
+impl Default for MockState {
+    fn default() -> Self {
+        Self {
+            calls: Vec::new(),
+            size: 0.0,
+            pmi: Vector3::default(),
+            empty_mass: 0.0,
+            camera_offset: Vector3::default(),
+            touchdown_points: None,
+            meshes: Vec::new(),
+            propellants: Vec::new(),
+            thrusters: Vec::new(),
+            thruster_groups: Vec::new(),
+            status: VesselStatus::default(),
+            global_pos: Vector3::default(),
+            rotation: Matrix3::IDENTITY,
+            angular_vel: Vector3::default(),
+            airspeed: Default::default(),
+            altitude: 0.0,
+            surface_elevation: 0.0,
+            atm_temperature: 0.0,
+            atm_density: 0.0,
+            atm_pressure: 0.0,
+            ground_contact: false,
+            surface_ref: OBJHANDLE::default(),
+            relative_vel: Vector3::default(),
+            equ_pos: EquPos::default(),
+            rot_drag: Vector3::default(),
+            elements: Elements::default(),
+            orbit_param: OrbitParam::default(),
+            returns: VecDeque::new(),
+        }
+    }
+}
+impl MockState {
+    /// Returns the thruster referred to by `th`
+    pub fn thruster(&self, th: ThrusterHandle) -> Option<&MockThruster> {
+        th.0.checked_sub(1).and_then(|idx| self.thrusters.get(idx))
+    }
+    /// Returns the thruster referred to by `th` for modification
+    pub fn thruster_mut(&mut self, th: ThrusterHandle) -> Option<&mut MockThruster> {
+        th.0.checked_sub(1).and_then(move |idx| self.thrusters.get_mut(idx))
+    }
+    /// Returns the propellant resource referred to by `ph`
+    pub fn propellant(&self, ph: PropellantHandle) -> Option<&MockPropellant> {
+        ph.0.checked_sub(1).and_then(|idx| self.propellants.get(idx))
+    }
+    /// Returns the propellant resource referred to by `ph` for modification
+    pub fn propellant_mut(&mut self, ph: PropellantHandle) -> Option<&mut MockPropellant> {
+        ph.0.checked_sub(1).and_then(move |idx| self.propellants.get_mut(idx))
+    }
+    /// Sets the level of all thrusters in the default group `group_type`
+    ///
+    /// This simulates user input on the keyboard or joystick, e.g. for the attitude thruster groups.
+    pub fn set_thruster_group_level(&mut self, group_type: ThrusterGroupType, level: f64) {
+        let handles: Vec<ThrusterHandle> = self
+            .thruster_groups
+            .iter()
+            .filter(|group| group.group_type == group_type)
+            .flat_map(|group| group.thrusters.iter().copied())
+            .collect();
+        for th in handles {
+            if let Some(thruster) = self.thruster_mut(th) {
+                thruster.level = level;
+            }
+        }
+    }
+    /// Mean thrust level of the thrusters in a group
+    pub(crate) fn group_level(&self, group: &MockThrusterGroup) -> f64 {
+        if group.thrusters.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = group
+            .thrusters
+            .iter()
+            .filter_map(|&th| self.thruster(th))
+            .map(MockThruster::effective_level)
+            .sum();
+        total / group.thrusters.len() as f64
+    }
+}
+
+/// Handle to a mocked vessel
+///
+/// Cloning the handle does not clone the vessel; all clones refer to the same [MockState].
+#[derive(Debug, Clone)]
+pub struct MockVessel {
+    name: String,
+    state: Rc<RefCell<MockState>>,
+}
+impl MockVessel {
+    /// Create a new mocked vessel called `name`
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            state: Rc::new(RefCell::new(MockState::default())),
+        }
+    }
+    /// Returns an [SDKVessel] bound to this mock, to be passed to the addon
+    ///
+    /// The underlying context is leaked to satisfy the `'static` lifetime, just like the one
+    /// handed out by Orbiter lives for the whole simulation session.
+    pub fn sdk_vessel(&self) -> SDKVessel {
+        let ctx = sdk::VesselContext {
+            name: self.name.clone(),
+            state: Rc::clone(&self.state),
+        };
+        RecordingVessel::new(std::pin::Pin::new(Box::leak(Box::new(ctx))))
+    }
+    /// Returns the vessel's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Immutable access to the state of the vessel
+    pub fn state(&self) -> Ref<'_, MockState> {
+        self.state.borrow()
+    }
+    /// Mutable access to the state of the vessel
+    pub fn state_mut(&self) -> RefMut<'_, MockState> {
+        self.state.borrow_mut()
+    }
+    /// Returns all calls made on the vessel so far
+    pub fn calls(&self) -> Ref<'_, [MockCall]> {
+        Ref::map(self.state.borrow(), |state| state.calls.as_slice())
+    }
+    /// Removes and returns all calls made on the vessel so far
+    pub fn take_calls(&self) -> Vec<MockCall> {
+        std::mem::take(&mut self.state.borrow_mut().calls)
+    }
+    /// Creates an in-memory configuration file to be passed to [OrbiterVessel::set_class_caps]
+    ///
+    /// `contents` holds `ITEM = VALUE` lines as found in the vessel's `.cfg` file
+    pub fn config_file(&self, contents: &str) -> FileHandle {
+        MockFile::new(contents).handle()
+    }
+    /// Feeds scenario lines to the addon like Orbiter does when loading a scenario
+    ///
+    /// Lines that are not consumed by [OrbiterVessel::on_load_param] are recorded as
+    /// [MockCall::ParseScenarioLineEx].
+    pub fn load_scenario(&self, vessel: &mut dyn OrbiterVessel, lines: &[&str]) {
+        for line in lines {
+            trace::record(|| TraceEvent::LoadParam(line.to_string()));
+            if !vessel.on_load_param(line) {
+                trace::record(|| TraceEvent::Call(MockCall::ParseScenarioLineEx(line.to_string())));
+                self.state
+                    .borrow_mut()
+                    .calls
+                    .push(MockCall::ParseScenarioLineEx(line.to_string()));
+            }
+        }
+    }
+    /// Calls [OrbiterVessel::on_save_state] and returns the lines written to the scenario
+    pub fn save_scenario(&self, vessel: &mut dyn OrbiterVessel) -> Vec<String> {
+        trace::record(|| TraceEvent::SaveState);
+        let scn = MockFile::new("");
+        vessel.on_save_state(&scn.handle());
+        scn.lines()
+    }
+    /// Calls [OrbiterVessel::consume_buffered_key] like Orbiter does on a key press or release
+    pub fn press_key(
+        &self,
+        vessel: &mut dyn OrbiterVessel,
+        key: Key,
+        down: bool,
+        keyboard: &mut KeyboardState,
+    ) -> i32 {
+        let kstate = keyboard.states();
+        trace::record_key(key, down, &kstate);
+        vessel.consume_buffered_key(key, down, kstate)
+    }
+}
+
+/// State of the keyboard passed to [OrbiterVessel::consume_buffered_key]
+#[derive(Debug, Clone)]
+pub struct KeyboardState([c_char; 256]);
+impl Default for KeyboardState {
+    fn default() -> Self {
+        Self([0; 256])
+    }
+}
+impl KeyboardState {
+    /// Creates a keyboard state with no keys held down
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Marks `key` as held down
+    pub fn press(&mut self, key: Key) -> &mut Self {
+        self.0[key.as_u8() as usize] = 0x80u8 as c_char;
+        self
+    }
+    /// Marks `key` as released
+    pub fn release(&mut self, key: Key) -> &mut Self {
+        self.0[key.as_u8() as usize] = 0;
+        self
+    }
+    /// Returns the [KeyStates] view of the keyboard
+    pub fn states(&mut self) -> KeyStates<'_> {
+        KeyStates::from_slice(&mut self.0)
+    }
+}
+
+/// An in-memory configuration or scenario file
+#[derive(Debug, Clone, Copy)]
+pub struct MockFile(FileHandle);
+impl MockFile {
+    /// Creates a new file with the given contents
+    pub fn new(contents: &str) -> Self {
+        Self(sdk::create_file("", contents))
+    }
+    /// Returns the file opened by the addon with [FileHandle::open] under `name`, if any
+    pub fn opened(name: &str) -> Option<Self> {
+        sdk::find_file(name).map(Self)
+    }
+    /// Returns a [FileHandle] referring to the file
+    pub fn handle(&self) -> FileHandle {
+        self.0
+    }
+    /// Returns the current contents of the file
+    pub fn lines(&self) -> Vec<String> {
+        sdk::file_lines(self.0)
+    }
+}
+
+/// A vessel created with [`oapi_create_vessel`](crate::oapi_create_vessel)
+#[derive(Debug)]
+pub struct MockCreatedVessel {
+    /// Name of the new vessel
+    pub name: String,
+    /// Class name of the new vessel
+    pub classname: String,
+    /// Initial status of the new vessel
+    pub status: VesselStatus,
+}
+
+/// Returns the vessels created on the current thread so far
+pub fn created_vessels() -> Vec<MockCreatedVessel> {
+    sdk::with_sim(|sim| sim.created_vessels.iter().map(|v| MockCreatedVessel {
+        name: v.name.clone(),
+        classname: v.classname.clone(),
+        status: v.status,
+    }).collect())
+}
+/// Returns the last string shown with [`debug_string!`](crate::debug_string) on the current thread
+pub fn debug_string() -> String {
+    sdk::with_sim(|sim| sim.debug_string.clone())
+}
+/// Returns the lines written to the Orbiter log on the current thread so far
+pub fn log_lines() -> Vec<String> {
+    sdk::with_sim(|sim| sim.log.clone())
+}
+/// Sets the matrix returned by [`oapi_get_planet_obliquity_matrix`](crate::oapi_get_planet_obliquity_matrix) for `hplanet` on the current thread
+pub fn set_planet_obliquity_matrix(hplanet: OBJHANDLE, mat: Matrix3) {
+    sdk::with_sim(|sim| {
+        sim.obliquity.retain(|(h, _)| *h != hplanet);
+        sim.obliquity.push((hplanet, mat));
+    });
+}
+/// Clears created vessels, debug string, log, files and planet obliquities on the current thread
+pub fn reset() {
+    sdk::with_sim(|sim| *sim = Default::default());
+}
+
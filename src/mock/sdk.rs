@@ -0,0 +1,714 @@
+//! Stand-ins for the items generated by the cxx bridge in `ffi.rs`
+//!
+//! The names and signatures mirror the bridge so that the rest of the crate and the addons
+//! compile unchanged against either backend.
+#![allow(non_snake_case, non_camel_case_types)]
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::rc::Rc;
+
+use super::{MockCall, MockMesh, MockPropellant, MockState, MockThruster, MockThrusterGroup};
+use crate::surface::EquPos;
+use crate::trace::VesselReturn;
+use crate::{
+    FileHandle, Matrix3, Vector3, VesselStatus, ELEMENTS, FILEHANDLE, MATRIX3, OBJHANDLE, ORBITPARAM,
+    PROPELLANT_HANDLE, THGROUP_HANDLE, THRUSTER_HANDLE, VECTOR3, VESSELSTATUS,
+};
+
+/// Reference Frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum REFFRAME {
+    /// Global (ecliptic) frame
+    Global,
+    /// local object frame
+    Local,
+    /// local reference object frame
+    RefLocal,
+    /// local horizon frame
+    Horizon,
+}
+/// Thruster Group Type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum THGROUP_TYPE {
+    /// Main Thrusters
+    Main,
+    /// Retro Thrusters
+    Retro,
+    /// Hover Thrusters
+    Hover,
+    /// Rotation: Pitch Up
+    AttPitchup,
+    /// Rotation: Pitch Down
+    AttPitchdown,
+    /// Rotation: Yaw Left
+    AttYawleft,
+    /// Rotation: Yaw Right
+    AttYawright,
+    /// Rotation: Bank Left
+    AttBankleft,
+    /// Rotation: Bank Right
+    AttBankright,
+    /// Translation: Move Right
+    AttRight,
+    /// Translation: Move Left
+    AttLeft,
+    /// Translation: Move Up
+    AttUp,
+    /// Translation: Move Down
+    AttDown,
+    /// Translation: Move Forward
+    AttForward,
+    /// Translation: Move Back
+    AttBack,
+    /// User-Defined Thruster Group
+    User = 0x40,
+}
+/// Altitude Mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum AltitudeMode {
+    /// Altitude over mean radius
+    MeanRadius,
+    /// Altitude over ground
+    Ground,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum FileAccessMode {
+    /// Read
+    In,
+    /// Write
+    Out,
+    /// Write (append)
+    Append,
+    /// Read (zero on fail)
+    In_ZeroOnFail,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum PathRoot {
+    Root,
+    Config,
+    Scenarios,
+    Textures,
+    Textures2,
+    Meshes,
+    Modules,
+}
+
+pub type c_void = std::ffi::c_void;
+pub type BoxDynVessel = Box<dyn crate::OrbiterVessel>;
+
+/// In-memory replacement for the `VESSELx` abstract classes in Orbiter SDK
+pub struct VesselContext {
+    pub(super) name: String,
+    pub(super) state: Rc<RefCell<MockState>>,
+}
+impl std::fmt::Debug for VesselContext {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("VesselContext")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// Pops the next value queued in [MockState::returns] if it matches `$pat`
+macro_rules! replayed {
+    ($state:expr, $pat:pat => $value:expr) => {
+        match $state.returns.front() {
+            Some($pat) => {
+                let value = $value;
+                $state.returns.pop_front();
+                Some(value)
+            }
+            _ => None,
+        }
+    };
+}
+
+impl VesselContext {
+    fn record(&self, call: MockCall) -> std::cell::RefMut<'_, MockState> {
+        let mut state = self.state.borrow_mut();
+        state.calls.push(call);
+        state
+    }
+
+    pub fn Local2Rel(&self, local: &VECTOR3, rel: &mut VECTOR3) {
+        let mut state = self.record(MockCall::Local2Rel(*local));
+        *rel = replayed!(state, VesselReturn::Local2Rel(v) => *v)
+            .unwrap_or_else(|| state.rotation * local + state.status.rpos);
+    }
+    pub fn Global2Local(&self, global: &VECTOR3, local: &mut VECTOR3) {
+        let mut state = self.record(MockCall::Global2Local(*global));
+        *local = replayed!(state, VesselReturn::Global2Local(v) => *v)
+            .unwrap_or_else(|| state.rotation.tmul(&(*global - state.global_pos)));
+    }
+    pub fn Local2Global(&self, local: &VECTOR3, global: &mut VECTOR3) {
+        let mut state = self.record(MockCall::Local2Global(*local));
+        *global = replayed!(state, VesselReturn::Local2Global(v) => *v)
+            .unwrap_or_else(|| state.rotation * local + state.global_pos);
+    }
+
+    pub fn SetSize(&self, size: f64) {
+        self.record(MockCall::SetSize(size)).size = size;
+    }
+    pub fn SetPMI(&self, pmi: &VECTOR3) {
+        self.record(MockCall::SetPMI(*pmi)).pmi = *pmi;
+    }
+    pub fn SetEmptyMass(&self, empty_mass: f64) {
+        self.record(MockCall::SetEmptyMass(empty_mass)).empty_mass = empty_mass;
+    }
+    pub fn SetCameraOffset(&self, camera_offset: &VECTOR3) {
+        self.record(MockCall::SetCameraOffset(*camera_offset))
+            .camera_offset = *camera_offset;
+    }
+    pub fn SetTouchdownPoints(&self, pt1: &VECTOR3, pt2: &VECTOR3, pt3: &VECTOR3) {
+        let mut state = self.record(MockCall::SetTouchdownPoints(
+            *pt1,
+            *pt2,
+            *pt3,
+        ));
+        state.touchdown_points = Some([*pt1, *pt2, *pt3]);
+    }
+    pub fn SetThrusterDir(&self, th: THRUSTER_HANDLE, dir: &VECTOR3) {
+        let mut state = self.record(MockCall::SetThrusterDir(th, *dir));
+        if let Some(thruster) = state.thruster_mut(th) {
+            thruster.dir = *dir;
+        }
+    }
+    pub fn SetThrusterLevel(&self, th: THRUSTER_HANDLE, level: f64) {
+        let mut state = self.record(MockCall::SetThrusterLevel(th, level));
+        if let Some(thruster) = state.thruster_mut(th) {
+            thruster.level = level;
+        }
+    }
+    pub fn SetThrusterLevel_SingleStep(&self, th: THRUSTER_HANDLE, level: f64) {
+        let mut state = self.record(MockCall::SetThrusterLevelSingleStep(th, level));
+        if let Some(thruster) = state.thruster_mut(th) {
+            thruster.single_step_level = Some(level);
+        }
+    }
+    pub fn SetThrusterGroupLevelByType(&self, thgroup_type: THGROUP_TYPE, level: f64) {
+        let mut state = self.record(MockCall::SetThrusterGroupLevelByType(thgroup_type, level));
+        let thrusters: Vec<THRUSTER_HANDLE> = state
+            .thruster_groups
+            .iter()
+            .filter(|group| group.group_type == thgroup_type)
+            .flat_map(|group| group.thrusters.iter().copied())
+            .collect();
+        for th in thrusters {
+            if let Some(thruster) = state.thruster_mut(th) {
+                thruster.level = level;
+            }
+        }
+    }
+
+    pub fn AddMesh(&self, mesh_name: String) {
+        let mut state = self.record(MockCall::AddMesh(mesh_name.clone()));
+        state.meshes.push(MockMesh {
+            name: mesh_name,
+            offset: Vector3::default(),
+        });
+    }
+    pub fn AddMeshWithOffset(&self, mesh_name: String, ofs: &VECTOR3) {
+        let mut state = self.record(MockCall::AddMeshWithOffset(mesh_name.clone(), *ofs));
+        state.meshes.push(MockMesh {
+            name: mesh_name,
+            offset: *ofs,
+        });
+    }
+    pub fn AddExhaust(&self, th: THRUSTER_HANDLE, lscale: f64, wscale: f64) -> usize {
+        let state = self.record(MockCall::AddExhaust(th, lscale, wscale));
+        state
+            .calls
+            .iter()
+            .filter(|call| matches!(call, MockCall::AddExhaust(..)))
+            .count()
+            - 1
+    }
+
+    pub fn CreatePropellantResource(&self, mass: f64) -> PROPELLANT_HANDLE {
+        let mut state = self.record(MockCall::CreatePropellantResource(mass));
+        state.propellants.push(MockPropellant {
+            max_mass: mass,
+            mass,
+        });
+        PROPELLANT_HANDLE(state.propellants.len())
+    }
+    pub fn CreateThruster(
+        &self,
+        pos: &VECTOR3,
+        dir: &VECTOR3,
+        maxth0: f64,
+        ph: PROPELLANT_HANDLE,
+        isp: f64,
+    ) -> THRUSTER_HANDLE {
+        let mut state = self.record(MockCall::CreateThruster {
+            pos: *pos,
+            dir: *dir,
+            maxth0,
+            ph,
+            isp,
+        });
+        state.thrusters.push(MockThruster {
+            pos: *pos,
+            dir: *dir,
+            max_thrust: maxth0,
+            propellant: ph,
+            isp,
+            level: 0.0,
+            single_step_level: None,
+        });
+        THRUSTER_HANDLE(state.thrusters.len())
+    }
+    pub fn CreateThrusterGroup(
+        &self,
+        thrusters: &[THRUSTER_HANDLE],
+        thgroup_type: THGROUP_TYPE,
+    ) -> THGROUP_HANDLE {
+        let mut state = self.record(MockCall::CreateThrusterGroup(thrusters.to_vec(), thgroup_type));
+        state.thruster_groups.push(MockThrusterGroup {
+            thrusters: thrusters.to_vec(),
+            group_type: thgroup_type,
+        });
+        THGROUP_HANDLE(state.thruster_groups.len())
+    }
+
+    pub fn ClearMeshes(&self) {
+        self.record(MockCall::ClearMeshes).meshes.clear();
+    }
+
+    pub fn GetName(&self) -> &str {
+        self.record(MockCall::GetName);
+        &self.name
+    }
+    pub fn GetStatus(&self, status: &mut VESSELSTATUS) {
+        let mut state = self.record(MockCall::GetStatus);
+        *status = replayed!(state, VesselReturn::GetStatus(s) => **s)
+            .unwrap_or_else(|| state.status);
+    }
+    pub fn GetPropellantMass(&self, ph: PROPELLANT_HANDLE) -> f64 {
+        let mut state = self.record(MockCall::GetPropellantMass(ph));
+        replayed!(state, VesselReturn::GetPropellantMass(mass) => *mass)
+            .unwrap_or_else(|| state.propellant(ph).map_or(0.0, |p| p.mass))
+    }
+    pub fn GetAngularVel(&self, a_vel: &mut VECTOR3) {
+        let mut state = self.record(MockCall::GetAngularVel);
+        *a_vel = replayed!(state, VesselReturn::GetAngularVel(v) => *v)
+            .unwrap_or_else(|| state.angular_vel);
+    }
+    pub fn GetAirspeedVector(&self, ref_frame: REFFRAME, airspeed: &mut VECTOR3) -> bool {
+        let mut state = self.record(MockCall::GetAirspeedVector(ref_frame));
+        let (valid, value) = replayed!(state, VesselReturn::GetAirspeedVector(valid, v) => (*valid, *v))
+            .unwrap_or_else(|| (true, state.airspeed[ref_frame as usize]));
+        *airspeed = value;
+        valid
+    }
+    pub fn GetThrustVector(&self, thrust_vec: &mut VECTOR3) -> bool {
+        let mut state = self.record(MockCall::GetThrustVector);
+        if let Some((thrusting, v)) = replayed!(state, VesselReturn::GetThrustVector(thrusting, v) => (*thrusting, *v)) {
+            *thrust_vec = v;
+            return thrusting;
+        }
+        let mut total = Vector3::default();
+        for thruster in state.thrusters.iter() {
+            total += thruster.dir * (thruster.effective_level() * thruster.max_thrust);
+        }
+        let thrusting = total.length() > 0.0;
+        *thrust_vec = total;
+        thrusting
+    }
+    pub fn GetTotalPropellantMass(&self) -> f64 {
+        let mut state = self.record(MockCall::GetTotalPropellantMass);
+        replayed!(state, VesselReturn::GetTotalPropellantMass(mass) => *mass)
+            .unwrap_or_else(|| state.propellants.iter().map(|p| p.mass).sum())
+    }
+    pub fn GetEmptyMass(&self) -> f64 {
+        let mut state = self.record(MockCall::GetEmptyMass);
+        replayed!(state, VesselReturn::GetEmptyMass(mass) => *mass).unwrap_or(state.empty_mass)
+    }
+    pub fn GetThrusterGroupLevelByType(&self, thgroup_type: THGROUP_TYPE) -> f64 {
+        let mut state = self.record(MockCall::GetThrusterGroupLevelByType(thgroup_type));
+        if let Some(level) = replayed!(state, VesselReturn::GetThrusterGroupLevelByType(level) => *level) {
+            return level;
+        }
+        state
+            .thruster_groups
+            .iter()
+            .find(|group| group.group_type == thgroup_type)
+            .map_or(0.0, |group| state.group_level(group))
+    }
+    pub fn GetThrusterGroupLevel(&self, th: THGROUP_HANDLE) -> f64 {
+        let mut state = self.record(MockCall::GetThrusterGroupLevel(th));
+        if let Some(level) = replayed!(state, VesselReturn::GetThrusterGroupLevel(level) => *level) {
+            return level;
+        }
+        th.0.checked_sub(1)
+            .and_then(|idx| state.thruster_groups.get(idx))
+            .map_or(0.0, |group| state.group_level(group))
+    }
+    pub fn GetThrusterMax0(&self, th: THRUSTER_HANDLE) -> f64 {
+        let mut state = self.record(MockCall::GetThrusterMax0(th));
+        replayed!(state, VesselReturn::GetThrusterMax0(maxth) => *maxth)
+            .unwrap_or_else(|| state.thruster(th).map_or(0.0, |thruster| thruster.max_thrust))
+    }
+    pub fn GetThrusterIsp0(&self, th: THRUSTER_HANDLE) -> f64 {
+        let mut state = self.record(MockCall::GetThrusterIsp0(th));
+        replayed!(state, VesselReturn::GetThrusterIsp0(isp) => *isp)
+            .unwrap_or_else(|| state.thruster(th).map_or(0.0, |thruster| thruster.isp))
+    }
+    pub fn GroundContact(&self) -> bool {
+        let mut state = self.record(MockCall::GroundContact);
+        replayed!(state, VesselReturn::GroundContact(contact) => *contact).unwrap_or(state.ground_contact)
+    }
+    pub fn GetSurfaceRef(&self) -> OBJHANDLE {
+        let mut state = self.record(MockCall::GetSurfaceRef);
+        replayed!(state, VesselReturn::GetSurfaceRef(href) => *href).unwrap_or(state.surface_ref)
+    }
+    pub fn GetSurfaceElevation(&self) -> f64 {
+        let mut state = self.record(MockCall::GetSurfaceElevation);
+        replayed!(state, VesselReturn::GetSurfaceElevation(elev) => *elev)
+            .unwrap_or(state.surface_elevation)
+    }
+    pub fn GetAltitude(&self) -> f64 {
+        let mut state = self.record(MockCall::GetAltitude);
+        replayed!(state, VesselReturn::GetAltitude(alt) => *alt).unwrap_or(state.altitude)
+    }
+    pub fn GetAtmTemperature(&self) -> f64 {
+        let mut state = self.record(MockCall::GetAtmTemperature);
+        replayed!(state, VesselReturn::GetAtmTemperature(temp) => *temp).unwrap_or(state.atm_temperature)
+    }
+    pub fn GetAtmDensity(&self) -> f64 {
+        let mut state = self.record(MockCall::GetAtmDensity);
+        replayed!(state, VesselReturn::GetAtmDensity(density) => *density).unwrap_or(state.atm_density)
+    }
+    pub fn GetAtmPressure(&self) -> f64 {
+        let mut state = self.record(MockCall::GetAtmPressure);
+        replayed!(state, VesselReturn::GetAtmPressure(pressure) => *pressure).unwrap_or(state.atm_pressure)
+    }
+    pub fn GetRelativeVel(&self, href: OBJHANDLE, rel_vel: &mut VECTOR3) {
+        let mut state = self.record(MockCall::GetRelativeVel(href));
+        *rel_vel = replayed!(state, VesselReturn::GetRelativeVel(v) => *v)
+            .unwrap_or_else(|| state.relative_vel);
+    }
+    pub fn GetEquPos(&self, longitude: &mut f64, latitude: &mut f64, radius: &mut f64) {
+        let mut state = self.record(MockCall::GetEquPos);
+        let pos = replayed!(state, VesselReturn::GetEquPos(lng, lat, rad) => EquPos::new(*lng, *lat, *rad))
+            .unwrap_or(state.equ_pos);
+        *longitude = pos.longitude;
+        *latitude = pos.latitude;
+        *radius = pos.radius;
+    }
+    pub fn GetHorizonAirspeedVector(&self, airspeed: &mut VECTOR3) -> bool {
+        let mut state = self.record(MockCall::GetHorizonAirspeedVector);
+        let (valid, value) = replayed!(state, VesselReturn::GetHorizonAirspeedVector(valid, v) => (*valid, *v))
+            .unwrap_or_else(|| (true, state.airspeed[REFFRAME::Horizon as usize]));
+        *airspeed = value;
+        valid
+    }
+    pub fn GetRotationMatrix(&self, r: &mut MATRIX3) {
+        let mut state = self.record(MockCall::GetRotationMatrix);
+        *r = replayed!(state, VesselReturn::GetRotationMatrix(m) => *m).unwrap_or(state.rotation);
+    }
+    pub fn GetRotDrag(&self, rd: &mut VECTOR3) {
+        let mut state = self.record(MockCall::GetRotDrag);
+        *rd = replayed!(state, VesselReturn::GetRotDrag(v) => *v)
+            .unwrap_or_else(|| state.rot_drag);
+    }
+    pub fn GetElements(
+        &self,
+        href: OBJHANDLE,
+        el: &mut ELEMENTS,
+        prm: &mut ORBITPARAM,
+        mjd_ref: f64,
+        frame: i32,
+    ) -> bool {
+        let mut state = self.record(MockCall::GetElements(href, mjd_ref, frame));
+        let (valid, elements, orbit_param) =
+            replayed!(state, VesselReturn::GetElements(valid, e, p) => (*valid, *e, *p))
+                .unwrap_or((true, state.elements, state.orbit_param));
+        *el = elements;
+        *prm = orbit_param;
+        valid
+    }
+    pub fn SetElements(
+        &self,
+        href: OBJHANDLE,
+        el: &ELEMENTS,
+        prm: &mut ORBITPARAM,
+        mjd_ref: f64,
+        frame: i32,
+    ) -> bool {
+        let mut state = self.record(MockCall::SetElements(href, *el, mjd_ref, frame));
+        if let Some((valid, orbit_param)) = replayed!(state, VesselReturn::SetElements(valid, p) => (*valid, *p)) {
+            *prm = orbit_param;
+            return valid;
+        }
+        state.elements = *el;
+        *prm = state.orbit_param;
+        true
+    }
+
+    /// # Safety
+    /// `line` must point to a valid null-terminated string
+    pub unsafe fn ParseScenarioLineEx(&self, line: *mut c_char, _status: *mut c_void) {
+        let line = CStr::from_ptr(line).to_string_lossy().to_string();
+        self.record(MockCall::ParseScenarioLineEx(line));
+    }
+}
+
+pub(super) struct MockCreatedVesselData {
+    pub(super) name: String,
+    pub(super) classname: String,
+    pub(super) status: VesselStatus,
+}
+#[derive(Default)]
+struct MockFileData {
+    name: String,
+    lines: Vec<String>,
+    read_pos: usize,
+    current_line: CString,
+}
+#[derive(Default)]
+pub(super) struct MockSim {
+    pub(super) created_vessels: Vec<MockCreatedVesselData>,
+    pub(super) debug_string: String,
+    pub(super) log: Vec<String>,
+    pub(super) obliquity: Vec<(OBJHANDLE, Matrix3)>,
+    files: Vec<MockFileData>,
+}
+impl MockSim {
+    fn file(&mut self, f: FILEHANDLE) -> Option<&mut MockFileData> {
+        f.0.checked_sub(1).and_then(move |idx| self.files.get_mut(idx))
+    }
+}
+
+thread_local! {
+    static SIM: RefCell<MockSim> = RefCell::new(MockSim::default());
+}
+pub(super) fn with_sim<T>(f: impl FnOnce(&mut MockSim) -> T) -> T {
+    SIM.with(|sim| f(&mut sim.borrow_mut()))
+}
+
+pub(super) fn create_file(name: &str, contents: &str) -> FileHandle {
+    with_sim(|sim| {
+        sim.files.push(MockFileData {
+            name: name.to_owned(),
+            lines: contents.lines().map(str::to_owned).collect(),
+            ..Default::default()
+        });
+        FileHandle(sim.files.len())
+    })
+}
+pub(super) fn find_file(name: &str) -> Option<FileHandle> {
+    with_sim(|sim| {
+        sim.files
+            .iter()
+            .position(|file| !name.is_empty() && file.name == name)
+            .map(|idx| FileHandle(idx + 1))
+    })
+}
+pub(super) fn file_lines(f: FileHandle) -> Vec<String> {
+    with_sim(|sim| sim.file(f).map(|file| file.lines.clone()).unwrap_or_default())
+}
+
+/// Create new vessel using Orbiter SDK
+pub fn oapi_create_vessel(name: String, classname: String, status: &VESSELSTATUS) -> OBJHANDLE {
+    with_sim(|sim| {
+        sim.created_vessels.push(MockCreatedVesselData {
+            name,
+            classname,
+            status: *status,
+        });
+        OBJHANDLE(sim.created_vessels.len())
+    })
+}
+/// Returns the rotation matrix from the planet's local frame to the global (ecliptic) frame
+///
+/// Planets without a matrix set with [set_planet_obliquity_matrix](super::set_planet_obliquity_matrix) have no obliquity.
+pub fn oapi_get_planet_obliquity_matrix(hplanet: OBJHANDLE, mat: &mut MATRIX3) {
+    *mat = with_sim(|sim| {
+        sim.obliquity
+            .iter()
+            .find(|(h, _)| *h == hplanet)
+            .map_or(Matrix3::IDENTITY, |(_, m)| *m)
+    });
+}
+/// Print message to lower-left corner of screen. For debugging purposes only!
+pub fn ODebug(s: String) {
+    with_sim(|sim| sim.debug_string = s.chars().take(254).collect());
+}
+
+unsafe fn to_str(s: *const c_char) -> String {
+    CStr::from_ptr(s).to_string_lossy().to_string()
+}
+fn read_item(f: FILEHANDLE, item: *mut c_char) -> Option<String> {
+    let item = unsafe { to_str(item) };
+    with_sim(|sim| {
+        let file = sim.file(f)?;
+        file.lines.iter().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            if key.trim().eq_ignore_ascii_case(item.trim()) {
+                Some(value.trim().to_owned())
+            } else {
+                None
+            }
+        })
+    })
+}
+fn write_line(f: FILEHANDLE, line: String) {
+    with_sim(|sim| {
+        if let Some(file) = sim.file(f) {
+            file.lines.push(line);
+        }
+    })
+}
+
+pub unsafe fn oapiReadItem_string(f: FILEHANDLE, item: *mut c_char, val: *mut c_char) -> bool {
+    match read_item(f, item) {
+        Some(value) => {
+            let bytes = value.as_bytes();
+            let len = bytes.len().min(255);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr() as *const c_char, val, len);
+            *val.add(len) = 0;
+            true
+        }
+        None => false,
+    }
+}
+pub unsafe fn oapiReadItem_float(f: FILEHANDLE, item: *mut c_char, val: &mut f64) -> bool {
+    match read_item(f, item).and_then(|v| v.parse().ok()) {
+        Some(value) => {
+            *val = value;
+            true
+        }
+        None => false,
+    }
+}
+pub unsafe fn oapiReadItem_int(f: FILEHANDLE, item: *mut c_char, val: &mut i32) -> bool {
+    match read_item(f, item).and_then(|v| v.parse().ok()) {
+        Some(value) => {
+            *val = value;
+            true
+        }
+        None => false,
+    }
+}
+pub unsafe fn oapiReadItem_bool(f: FILEHANDLE, item: *mut c_char, val: &mut bool) -> bool {
+    match read_item(f, item).map(|v| v.to_ascii_uppercase()) {
+        Some(value) if value == "TRUE" => {
+            *val = true;
+            true
+        }
+        Some(value) if value == "FALSE" => {
+            *val = false;
+            true
+        }
+        _ => false,
+    }
+}
+pub unsafe fn oapiReadItem_vec(f: FILEHANDLE, item: *mut c_char, val: &mut VECTOR3) -> bool {
+    let parsed = read_item(f, item).and_then(|v| {
+        let parts: Vec<f64> = v.split_whitespace().filter_map(|p| p.parse().ok()).collect();
+        match parts[..] {
+            [x, y, z] => Some(Vector3::new(x, y, z)),
+            _ => None,
+        }
+    });
+    match parsed {
+        Some(value) => {
+            *val = value;
+            true
+        }
+        None => false,
+    }
+}
+
+fn format_vec(v: &VECTOR3) -> String {
+    format!("{} {} {}", v.x(), v.y(), v.z())
+}
+pub unsafe fn oapiWriteItem_string(f: FILEHANDLE, item: *mut c_char, val: *mut c_char) {
+    write_line(f, format!("{} = {}", to_str(item), to_str(val)));
+}
+pub unsafe fn oapiWriteItem_float(f: FILEHANDLE, item: *mut c_char, val: f64) {
+    write_line(f, format!("{} = {}", to_str(item), val));
+}
+pub unsafe fn oapiWriteItem_int(f: FILEHANDLE, item: *mut c_char, val: i32) {
+    write_line(f, format!("{} = {}", to_str(item), val));
+}
+pub unsafe fn oapiWriteItem_bool(f: FILEHANDLE, item: *mut c_char, val: bool) {
+    let val = if val { "TRUE" } else { "FALSE" };
+    write_line(f, format!("{} = {}", to_str(item), val));
+}
+pub unsafe fn oapiWriteItem_vec(f: FILEHANDLE, item: *mut c_char, val: &VECTOR3) {
+    write_line(f, format!("{} = {}", to_str(item), format_vec(val)));
+}
+
+pub unsafe fn oapiWriteScenario_string(scn: FILEHANDLE, item: *mut c_char, val: *mut c_char) {
+    write_line(scn, format!("  {} {}", to_str(item), to_str(val)));
+}
+pub unsafe fn oapiWriteScenario_float(scn: FILEHANDLE, item: *mut c_char, val: f64) {
+    write_line(scn, format!("  {} {}", to_str(item), val));
+}
+pub unsafe fn oapiWriteScenario_int(scn: FILEHANDLE, item: *mut c_char, val: i32) {
+    write_line(scn, format!("  {} {}", to_str(item), val));
+}
+pub unsafe fn oapiWriteScenario_vec(scn: FILEHANDLE, item: *mut c_char, val: &VECTOR3) {
+    write_line(scn, format!("  {} {}", to_str(item), format_vec(val)));
+}
+
+pub unsafe fn oapiReadScenario_nextline(scn: FILEHANDLE, line: &mut *mut c_char) -> bool {
+    with_sim(|sim| {
+        let file = match sim.file(scn) {
+            Some(file) => file,
+            None => return false,
+        };
+        match file.lines.get(file.read_pos) {
+            Some(next) if next.trim() != "END" => {
+                file.current_line = CString::new(next.as_str()).unwrap_or_default();
+                file.read_pos += 1;
+                *line = file.current_line.as_ptr() as *mut c_char;
+                true
+            }
+            _ => false,
+        }
+    })
+}
+pub unsafe fn oapiWriteLine(f: FILEHANDLE, line: *mut c_char) {
+    write_line(f, to_str(line));
+}
+
+pub unsafe fn oapiOpenFile(fname: *const c_char, mode: FileAccessMode, _root: PathRoot) -> FILEHANDLE {
+    let name = to_str(fname);
+    match find_file(&name) {
+        Some(f) => {
+            with_sim(|sim| {
+                if let Some(file) = sim.file(f) {
+                    file.read_pos = 0;
+                    if mode == FileAccessMode::Out {
+                        file.lines.clear();
+                    }
+                }
+            });
+            f
+        }
+        None if mode == FileAccessMode::In => FileHandle(0),
+        None => create_file(&name, ""),
+    }
+}
+pub fn oapiCloseFile(f: FILEHANDLE, _mode: FileAccessMode) {
+    with_sim(|sim| {
+        if let Some(file) = sim.file(f) {
+            file.read_pos = 0;
+        }
+    })
+}
+
+/// Writes a line to the Orbiter log file (orbiter.log) in the main orbiter directory
+pub unsafe fn oapiWriteLog(line: *mut c_char) {
+    let line = to_str(line);
+    with_sim(|sim| sim.log.push(line));
+}
@@ -0,0 +1,297 @@
+//! Keplerian orbital elements
+//!
+//! [OrbitalElements] describes the two-body orbit of a vessel around a central body with gravitational
+//! parameter `gm` (see [Body::gm](crate::Body::gm)) and converts to and from the state vectors found in
+//! [VesselStatus::rpos](crate::VesselStatus::rpos) and [VesselStatus::rvel](crate::VesselStatus::rvel).
+//!
+//! Like the rest of the SDK, state vectors are expressed in Orbiter's left-handed ecliptic frame, where `y`
+//! points to the ecliptic north pole. The elements follow the usual right-handed conventions and match the
+//! ones returned by `GetElements` with [FRAME_ECL]:
+//!
+//! ```
+//! use orbiter_rs::orbit::OrbitalElements;
+//! use orbiter_rs::{Body, V};
+//!
+//! let gm = Body::EARTH.gm();
+//! let pos = V!(7.0e6, 0.0, 0.0);
+//! let vel = V!(0.0, 1000.0, 7800.0);
+//!
+//! let el = OrbitalElements::from_state(&pos, &vel, gm);
+//! assert!(el.e < 1.0 && el.i < std::f64::consts::FRAC_PI_2);
+//! assert!(el.periapsis() <= pos.length() && pos.length() <= el.apoapsis());
+//!
+//! let (pos2, vel2) = el.to_state(gm);
+//! assert!((pos2 - pos).length() < 1e-6 && (vel2 - vel).length() < 1e-9);
+//! ```
+//!
+//! The [VesselOrbit] extension trait reads and sets the elements of a vessel through the `GetElements` and
+//! `SetElements` bindings.
+use std::f64::consts::{PI, TAU};
+
+use crate::{Elements, Matrix3, OrbitParam, Vector3, VesselApi, FRAME_ECL, OBJHANDLE, V};
+
+/// Eccentricities and inclinations below this are treated as circular and equatorial orbits
+const EPS: f64 = 1e-10;
+
+/// Converts between Orbiter's left-handed frames and right-handed frames by swapping `y` and `z`
+pub(crate) fn swap_yz(v: &Vector3) -> Vector3 {
+    V!(v.x(), v.z(), v.y())
+}
+
+/// Osculating Keplerian elements of an elliptic or hyperbolic orbit
+///
+/// Angles are in radians. Angles that are undefined for circular or equatorial orbits are set to zero:
+/// the argument of periapsis of a circular orbit, which then measures the true anomaly from the ascending
+/// node, and the longitude of the ascending node of an equatorial orbit, which then measures from the `x`
+/// axis. Parabolic orbits (`e == 1`) have an infinite semi-major axis and cannot be represented.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    /// Semi-major axis, negative for hyperbolic orbits \[**m**\]
+    pub a: f64,
+    /// Eccentricity
+    pub e: f64,
+    /// Inclination against the ecliptic \[**rad**\]
+    pub i: f64,
+    /// Longitude of the ascending node (Ω) \[**rad**\]
+    pub lan: f64,
+    /// Argument of periapsis (ω) \[**rad**\]
+    pub arg_periapsis: f64,
+    /// True anomaly (ν) \[**rad**\]
+    pub true_anomaly: f64,
+}
+
+impl OrbitalElements {
+    /// Computes the elements from the position and velocity relative to the central body
+    ///
+    /// `pos` \[**m**\] and `vel` \[**m/s**\] are given in the ecliptic frame, `gm` is the gravitational
+    /// parameter of the central body \[**m³/s²**\].
+    pub fn from_state(pos: &Vector3, vel: &Vector3, gm: f64) -> Self {
+        let (r, v) = (swap_yz(pos), swap_yz(vel));
+        let r_len = r.length();
+        let h = r.cross(&v);
+        let h_len = h.length();
+        let h_unit = if h_len > 0.0 { h / h_len } else { V!(0.0, 0.0, 1.0) };
+        let e_vec = (r * (v.dot(&v) - gm / r_len) - v * r.dot(&v)) / gm;
+        let e = e_vec.length();
+        let a = 1.0 / (2.0 / r_len - v.dot(&v) / gm);
+        let i = h_unit.z().clamp(-1.0, 1.0).acos();
+
+        let node = V!(-h.y(), h.x(), 0.0);
+        let lan = if node.length() > EPS * h_len {
+            normalize(node.y().atan2(node.x()))
+        } else {
+            0.0
+        };
+        let node_dir = V!(lan.cos(), lan.sin(), 0.0);
+        // Angle from `from` to `to` in the direction of motion
+        let angle = |from: &Vector3, to: &Vector3| normalize(h_unit.dot(&from.cross(to)).atan2(from.dot(to)));
+        let (arg_periapsis, true_anomaly) = if e > EPS {
+            (angle(&node_dir, &e_vec), angle(&e_vec, &r))
+        } else {
+            (0.0, angle(&node_dir, &r))
+        };
+        Self { a, e, i, lan, arg_periapsis, true_anomaly }
+    }
+    /// Computes the position \[**m**\] and velocity \[**m/s**\] relative to the central body in the ecliptic frame
+    pub fn to_state(&self, gm: f64) -> (Vector3, Vector3) {
+        let p = self.semi_latus_rectum();
+        let (s, c) = self.true_anomaly.sin_cos();
+        let r = p / (1.0 + self.e * c);
+        let pos = V!(r * c, r * s, 0.0);
+        let vel = V!(-s, self.e + c, 0.0) * (gm / p).sqrt();
+        // Perifocal frame to the right-handed ecliptic frame
+        let rot = Matrix3::rotation_z(self.lan) * Matrix3::rotation_x(self.i) * Matrix3::rotation_z(self.arg_periapsis);
+        (swap_yz(&(rot * pos)), swap_yz(&(rot * vel)))
+    }
+    /// Converts the elements returned by `GetElements`
+    ///
+    /// The true anomaly is the one at the reference date `mjd_ref` passed to `GetElements`.
+    pub fn from_elements(el: &Elements) -> Self {
+        let mean_anomaly = if el.e < 1.0 { normalize(el.L - el.omegab) } else { el.L - el.omegab };
+        Self {
+            a: el.a,
+            e: el.e,
+            i: el.i,
+            lan: el.theta,
+            arg_periapsis: normalize(el.omegab - el.theta),
+            true_anomaly: true_from_mean_anomaly(mean_anomaly, el.e),
+        }
+    }
+    /// Converts into the elements expected by `SetElements`, with the current true anomaly at `mjd_ref`
+    pub fn to_elements(&self) -> Elements {
+        Elements {
+            a: self.a,
+            e: self.e,
+            i: self.i,
+            theta: self.lan,
+            omegab: self.longitude_of_periapsis(),
+            L: self.mean_longitude(),
+        }
+    }
+    /// Computes the secondary orbit parameters, as returned by `GetElements`
+    pub fn orbit_param(&self, gm: f64) -> OrbitParam {
+        OrbitParam {
+            SMi: self.semi_minor_axis(),
+            PeD: self.periapsis(),
+            ApD: self.apoapsis(),
+            MnA: self.mean_anomaly(),
+            TrA: self.true_anomaly,
+            MnL: self.mean_longitude(),
+            TrL: normalize(self.longitude_of_periapsis() + self.true_anomaly),
+            EcA: self.eccentric_anomaly(),
+            Lec: (self.a * self.e).abs(),
+            T: self.period(gm),
+            PeT: self.time_to_periapsis(gm),
+            ApT: self.time_to_apoapsis(gm),
+        }
+    }
+    /// Returns a copy of the elements with the true anomaly set from `mean_anomaly` \[**rad**\]
+    pub fn with_mean_anomaly(&self, mean_anomaly: f64) -> Self {
+        Self {
+            true_anomaly: true_from_mean_anomaly(mean_anomaly, self.e),
+            ..*self
+        }
+    }
+
+    /// Returns the semi-latus rectum `a (1 - e²)` \[**m**\]
+    pub fn semi_latus_rectum(&self) -> f64 {
+        self.a * (1.0 - self.e * self.e)
+    }
+    /// Returns the semi-minor axis \[**m**\]
+    pub fn semi_minor_axis(&self) -> f64 {
+        self.a.abs() * (1.0 - self.e * self.e).abs().sqrt()
+    }
+    /// Returns the periapsis distance from the centre of the central body \[**m**\]
+    pub fn periapsis(&self) -> f64 {
+        self.a * (1.0 - self.e)
+    }
+    /// Returns the apoapsis distance from the centre of the central body, infinite for hyperbolic orbits \[**m**\]
+    pub fn apoapsis(&self) -> f64 {
+        if self.e < 1.0 {
+            self.a * (1.0 + self.e)
+        } else {
+            f64::INFINITY
+        }
+    }
+    /// Returns the orbit period, infinite for hyperbolic orbits \[**s**\]
+    pub fn period(&self, gm: f64) -> f64 {
+        if self.e < 1.0 {
+            TAU / self.mean_motion(gm)
+        } else {
+            f64::INFINITY
+        }
+    }
+    /// Returns the mean motion \[**rad/s**\]
+    pub fn mean_motion(&self, gm: f64) -> f64 {
+        (gm / self.a.abs().powi(3)).sqrt()
+    }
+    /// Returns the longitude of periapsis `Ω + ω` \[**rad**\]
+    pub fn longitude_of_periapsis(&self) -> f64 {
+        normalize(self.lan + self.arg_periapsis)
+    }
+    /// Returns the mean longitude `Ω + ω + M` \[**rad**\]
+    pub fn mean_longitude(&self) -> f64 {
+        let l = self.longitude_of_periapsis() + self.mean_anomaly();
+        if self.e < 1.0 {
+            normalize(l)
+        } else {
+            l
+        }
+    }
+    /// Returns the eccentric anomaly, or the hyperbolic anomaly for hyperbolic orbits \[**rad**\]
+    pub fn eccentric_anomaly(&self) -> f64 {
+        let (e, nu) = (self.e, self.true_anomaly);
+        if e < 1.0 {
+            normalize(((1.0 - e * e).sqrt() * nu.sin()).atan2(e + nu.cos()))
+        } else {
+            2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * (nu / 2.0).tan()).atanh()
+        }
+    }
+    /// Returns the mean anomaly \[**rad**\]
+    ///
+    /// For hyperbolic orbits, the mean anomaly is negative before the periapsis passage.
+    pub fn mean_anomaly(&self) -> f64 {
+        let ecc = self.eccentric_anomaly();
+        if self.e < 1.0 {
+            normalize(ecc - self.e * ecc.sin())
+        } else {
+            self.e * ecc.sinh() - ecc
+        }
+    }
+    /// Returns the time to the next periapsis passage \[**s**\]
+    ///
+    /// For hyperbolic orbits, this is negative once the periapsis has been passed.
+    pub fn time_to_periapsis(&self, gm: f64) -> f64 {
+        let m = self.mean_anomaly();
+        if self.e < 1.0 {
+            (TAU - m) / self.mean_motion(gm)
+        } else {
+            -m / self.mean_motion(gm)
+        }
+    }
+    /// Returns the time to the next apoapsis passage, infinite for hyperbolic orbits \[**s**\]
+    pub fn time_to_apoapsis(&self, gm: f64) -> f64 {
+        if self.e < 1.0 {
+            normalize(PI - self.mean_anomaly()) / self.mean_motion(gm)
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+/// Solves Kepler's equation for the true anomaly \[**rad**\] at `mean_anomaly` \[**rad**\] on an orbit with eccentricity `e`
+pub fn true_from_mean_anomaly(mean_anomaly: f64, e: f64) -> f64 {
+    if e < 1.0 {
+        let m = normalize(mean_anomaly);
+        // Newton iteration on E - e sin(E) = M
+        let mut ecc = if e > 0.8 { PI } else { m };
+        for _ in 0..50 {
+            let delta = (ecc - e * ecc.sin() - m) / (1.0 - e * ecc.cos());
+            ecc -= delta;
+            if delta.abs() < 1e-14 {
+                break;
+            }
+        }
+        normalize(2.0 * ((1.0 + e).sqrt() * (ecc / 2.0).sin()).atan2((1.0 - e).sqrt() * (ecc / 2.0).cos()))
+    } else {
+        let m = mean_anomaly;
+        // Newton iteration on e sinh(F) - F = M
+        let mut ecc = m.signum() * (2.0 * m.abs() / e + 1.8).ln();
+        for _ in 0..50 {
+            let delta = (e * ecc.sinh() - ecc - m) / (e * ecc.cosh() - 1.0);
+            ecc -= delta;
+            if delta.abs() < 1e-14 * ecc.abs().max(1.0) {
+                break;
+            }
+        }
+        normalize(2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (ecc / 2.0).tanh()).atan())
+    }
+}
+
+/// Normalises an angle to `[0, 2π)`
+fn normalize(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(TAU);
+    // rem_euclid rounds tiny negative angles up to TAU
+    if angle >= TAU { 0.0 } else { angle }
+}
+
+/// Typed versions of the `GetElements` and `SetElements` methods of [VesselApi]
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselOrbit: VesselApi {
+    /// Returns the current osculating elements of the vessel's orbit around `href`, or `None` if they are not available
+    fn orbital_elements(&self, href: OBJHANDLE) -> Option<OrbitalElements> {
+        let mut el = Elements::default();
+        let mut prm = OrbitParam::default();
+        self.GetElements(href, &mut el, &mut prm, 0.0, FRAME_ECL)
+            .then(|| OrbitalElements::from_elements(&el))
+    }
+    /// Moves the vessel onto the orbit around `href` described by `elements` at the current simulation time
+    ///
+    /// Returns `false` if the elements could not be applied.
+    fn set_orbital_elements(&self, href: OBJHANDLE, elements: &OrbitalElements) -> bool {
+        let mut prm = OrbitParam::default();
+        self.SetElements(href, &elements.to_elements(), &mut prm, 0.0, FRAME_ECL)
+    }
+}
+impl<V: VesselApi + ?Sized> VesselOrbit for V {}
@@ -0,0 +1,112 @@
+//! Delta-v budget and burn times per propellant resource
+//!
+//! A [ThrusterLayout] records the propellant resource, thrust and ISP of each thruster as it is created with
+//! [ThrusterLayout::create_thruster]. For every resource, [VesselPropulsion::delta_v_budget] combines the
+//! thrusters drawing from it into an [Engine] and reports a [TankBudget]: the delta-v left in the resource
+//! from the rocket equation, starting from the current mass of the vessel, and how long it lasts at a given
+//! throttle. On a multi-stage vessel, this is the delta-v of each stage if it was burnt next, with the
+//! other stages carried along.
+//!
+//! ```
+//! use orbiter_rs::allocation::ThrusterLayout;
+//! use orbiter_rs::mock::MockVessel;
+//! use orbiter_rs::propulsion::VesselPropulsion;
+//! use orbiter_rs::units::Time;
+//! use orbiter_rs::V;
+//!
+//! let mock = MockVessel::new("Surveyor");
+//! let vessel = mock.sdk_vessel();
+//! vessel.SetEmptyMass(357.8);
+//! let ph_vernier = vessel.CreatePropellantResource(70.98);
+//! let ph_retro = vessel.CreatePropellantResource(560.64);
+//!
+//! let mut layout = ThrusterLayout::new();
+//! for x in [-0.86, 0.0, 0.86] {
+//!     layout.create_thruster(&vessel, &V!(x, 0.0, -0.5), &V!(0.0, 0.0, 1.0), 463.0, ph_vernier, 3200.0);
+//! }
+//! layout.create_thruster(&vessel, &V!(0.0, 0.0, -0.75), &V!(0.0, 0.0, 1.0), 39140.0, ph_retro, 2827.4);
+//!
+//! let budget = vessel.delta_v_budget(&layout);
+//! assert_eq!(budget.len(), 2);
+//! assert_eq!(budget[0].thrusters.len(), 3);
+//! assert!((budget[0].engine.thrust.in_newtons() - 1389.0).abs() < 1e-9);
+//! assert!((budget[0].delta_v - 3200.0 * (989.42f64 / 918.44).ln()).abs() < 1e-6);
+//! // The retro empties its case in 40.5 s at full thrust, twice as long at half thrust
+//! assert!((budget[1].burn_time(1.0) - Time::seconds(40.5)).in_seconds().abs() < 1e-3);
+//! assert!((budget[1].burn_time(0.5) - Time::seconds(81.0)).in_seconds().abs() < 1e-3);
+//! ```
+//!
+//! The thrust of an [Engine] is the sum of the thrusts of its thrusters, so burn times assume that all the
+//! thrusters drawing from the resource fire together. The delta-v only depends on their combined ISP.
+use crate::allocation::ThrusterLayout;
+use crate::maneuver::Engine;
+use crate::units::{Force, Mass, SpecificImpulse, Time, VesselUnits};
+use crate::{PropellantHandle, ThrusterHandle, VesselApi};
+
+/// Remaining delta-v and burn time of a propellant resource
+#[derive(Debug, Clone, PartialEq)]
+pub struct TankBudget {
+    /// The propellant resource
+    pub propellant: PropellantHandle,
+    /// Thrusters drawing from the resource, in the order they were added to the layout
+    pub thrusters: Vec<ThrusterHandle>,
+    /// The thrusters combined, firing together at full throttle
+    pub engine: Engine,
+    /// Current propellant mass of the resource
+    pub propellant_mass: Mass,
+    /// Current mass of the vessel, including all propellants
+    pub vessel_mass: Mass,
+    /// Velocity change from burning all the propellant of the resource \[**m/s**\]
+    pub delta_v: f64,
+}
+
+impl TankBudget {
+    /// Returns how long the propellant lasts with the thrusters at `level`, between 0 and 1
+    ///
+    /// The burn time is infinite at a level of 0.
+    pub fn burn_time(&self, level: f64) -> Time {
+        self.propellant_mass * self.engine.isp / (self.engine.thrust * level)
+    }
+    /// Returns the propellant mass left after a velocity change of `delta_v` \[**m/s**\], or `None` if the
+    /// resource does not hold enough
+    pub fn remaining_after(&self, delta_v: f64) -> Option<Mass> {
+        let used = self.engine.propellant_mass(delta_v, self.vessel_mass);
+        (used <= self.propellant_mass).then(|| self.propellant_mass - used)
+    }
+}
+
+/// Delta-v budget of the vessel's propellant resources
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselPropulsion: VesselApi {
+    /// Returns the budget of the propellant resource `ph`, or `None` if no thruster of `layout` draws from it
+    fn tank_budget(&self, layout: &ThrusterLayout, ph: PropellantHandle) -> Option<TankBudget> {
+        let engines: Vec<Engine> = layout
+            .thrusters_fed_by(ph)
+            .map(|th| Engine::new(Force::newtons(th.max_thrust), SpecificImpulse::meters_per_second(th.isp)))
+            .collect();
+        if engines.is_empty() {
+            return None;
+        }
+        let engine = Engine::cluster(&engines);
+        let propellant_mass = self.propellant_mass(ph);
+        let vessel_mass = self.empty_mass() + self.total_propellant_mass();
+        Some(TankBudget {
+            propellant: ph,
+            thrusters: layout.thrusters_fed_by(ph).map(|th| th.handle).collect(),
+            engine,
+            propellant_mass,
+            vessel_mass,
+            delta_v: engine.delta_v(propellant_mass, vessel_mass),
+        })
+    }
+    /// Returns the budget of every propellant resource feeding the thrusters of `layout`
+    fn delta_v_budget(&self, layout: &ThrusterLayout) -> Vec<TankBudget> {
+        layout
+            .propellants()
+            .into_iter()
+            .filter_map(|ph| self.tank_budget(layout, ph))
+            .collect()
+    }
+}
+impl<V: VesselApi + ?Sized> VesselPropulsion for V {}
@@ -0,0 +1,230 @@
+use crate::{Matrix3, Vector3, V};
+
+/// A quaternion `w + xi + yj + zk`, mostly used as a unit quaternion describing an attitude
+///
+/// A unit quaternion `q` describes the same rotation as the matrix [to_matrix](Self::to_matrix), i.e.
+/// `q.rotate(&v) == q.to_matrix() * v`. Attitudes follow the convention of
+/// [GetRotationMatrix](crate::VesselContext::GetRotationMatrix) and transform vectors from the local
+/// vessel frame to the global frame.
+///
+/// ```
+/// use orbiter_rs::{Matrix3, Quaternion, V};
+///
+/// let q = Quaternion::from_axis_angle(&V!(0.0, 0.0, 1.0), std::f64::consts::FRAC_PI_2);
+/// let v = q.rotate(&V!(1.0, 0.0, 0.0));
+/// assert!((&v - V!(0.0, 1.0, 0.0)).length() < 1e-12);
+///
+/// let arot = V!(0.1, -0.2, 0.3);
+/// let q = Quaternion::from_euler(arot.x(), arot.y(), arot.z());
+/// let r = Matrix3::from_euler(arot.x(), arot.y(), arot.z());
+/// assert!((q.rotate(&v) - r * &v).length() < 1e-12);
+/// assert!((q.euler() - arot).length() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    /// Scalar part
+    pub w: f64,
+    /// `i` component of the vector part
+    pub x: f64,
+    /// `j` component of the vector part
+    pub y: f64,
+    /// `k` component of the vector part
+    pub z: f64,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Quaternion {
+    /// The identity rotation
+    pub const IDENTITY: Self = Self::new(1.0, 0.0, 0.0, 0.0);
+
+    /// Build a new `Quaternion` from its components
+    pub const fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+    /// Build a quaternion from its scalar and vector parts
+    pub fn from_parts(w: f64, v: &Vector3) -> Self {
+        Self::new(w, v.x(), v.y(), v.z())
+    }
+    /// Rotation by `angle` radians about `axis`
+    ///
+    /// `axis` does not need to be normalised.
+    pub fn from_axis_angle(axis: &Vector3, angle: f64) -> Self {
+        let (s, c) = (angle / 2.0).sin_cos();
+        Self::from_parts(c, &(axis.unit() * s))
+    }
+    /// Rotation about `v` by `|v|` radians
+    pub fn from_rotation_vector(v: &Vector3) -> Self {
+        let angle = v.length();
+        if angle == 0.0 {
+            return Self::IDENTITY;
+        }
+        Self::from_axis_angle(v, angle)
+    }
+    /// Attitude for the Euler angles used by Orbiter, e.g. in [VesselStatus::arot](crate::VesselStatus::arot)
+    ///
+    /// This is the same rotation as [Matrix3::from_euler].
+    pub fn from_euler(alpha: f64, beta: f64, gamma: f64) -> Self {
+        let qx = Self::from_axis_angle(&V!(1.0, 0.0, 0.0), -alpha);
+        let qy = Self::from_axis_angle(&V!(0.0, 1.0, 0.0), -beta);
+        let qz = Self::from_axis_angle(&V!(0.0, 0.0, 1.0), -gamma);
+        qx * qy * qz
+    }
+    /// Converts a rotation matrix into a unit quaternion
+    pub fn from_matrix(m: &Matrix3) -> Self {
+        let m = &m.0;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        // Shepperd's method: pick the largest component to divide by for numerical stability
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self::new(s / 4.0, (m[2][1] - m[1][2]) / s, (m[0][2] - m[2][0]) / s, (m[1][0] - m[0][1]) / s)
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Self::new((m[2][1] - m[1][2]) / s, s / 4.0, (m[0][1] + m[1][0]) / s, (m[0][2] + m[2][0]) / s)
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Self::new((m[0][2] - m[2][0]) / s, (m[0][1] + m[1][0]) / s, s / 4.0, (m[1][2] + m[2][1]) / s)
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Self::new((m[1][0] - m[0][1]) / s, (m[0][2] + m[2][0]) / s, (m[1][2] + m[2][1]) / s, s / 4.0)
+        };
+        q.unit()
+    }
+
+    /// Returns the vector part of the quaternion
+    pub fn vector(&self) -> Vector3 {
+        V!(self.x, self.y, self.z)
+    }
+    /// Computes the dot product with `other`
+    pub fn dot(&self, other: &Quaternion) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+    /// Computes the length (norm) of the quaternion
+    pub fn length(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+    /// Computes the unit quaternion in the direction of this quaternion
+    pub fn unit(&self) -> Self {
+        *self * (1.0 / self.length())
+    }
+    /// Returns the conjugate `w - xi - yj - zk`
+    ///
+    /// For unit quaternions, this is the inverse rotation.
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.w, -self.x, -self.y, -self.z)
+    }
+    /// Computes the multiplicative inverse of the quaternion
+    pub fn inverse(&self) -> Self {
+        self.conjugate() * (1.0 / self.dot(self))
+    }
+    /// Rotates `v` by the unit quaternion, i.e. computes `q * v * q⁻¹`
+    pub fn rotate(&self, v: &Vector3) -> Vector3 {
+        let u = self.vector();
+        let t = u.cross(v) * 2.0;
+        u.cross(&t) + t * self.w + v
+    }
+    /// Converts the unit quaternion into a rotation matrix
+    pub fn to_matrix(&self) -> Matrix3 {
+        let Self { w, x, y, z } = *self;
+        Matrix3::new([
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+        ])
+    }
+    /// Returns the Orbiter Euler angles `(alpha, beta, gamma)` of the attitude
+    ///
+    /// See [Matrix3::euler].
+    pub fn euler(&self) -> Vector3 {
+        self.to_matrix().euler()
+    }
+    /// Spherical linear interpolation between `self` (`t = 0`) and `other` (`t = 1`)
+    ///
+    /// Both quaternions must be unit quaternions. The interpolation follows the shorter arc.
+    pub fn slerp(&self, other: &Quaternion, t: f64) -> Self {
+        let mut cos_theta = self.dot(other);
+        let mut other = *other;
+        if cos_theta < 0.0 {
+            other = -other;
+            cos_theta = -cos_theta;
+        }
+        if cos_theta > 0.9995 {
+            // Nearly parallel: fall back to normalised linear interpolation
+            return (*self * (1.0 - t) + other * t).unit();
+        }
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        *self * (((1.0 - t) * theta).sin() / sin_theta) + other * ((t * theta).sin() / sin_theta)
+    }
+    /// Time derivative of the attitude for the angular velocity `angular_vel` in the local vessel frame
+    ///
+    /// `angular_vel` is the value returned by [GetAngularVel](crate::VesselContext::GetAngularVel) \[**rad/s**\].
+    /// Orbiter's angular velocities follow its left-handed frames and turn in the opposite sense to
+    /// [from_axis_angle](Self::from_axis_angle), e.g. a positive rate about `x` pitches the nose up.
+    pub fn derivative(&self, angular_vel: &Vector3) -> Self {
+        *self * Self::from_parts(0.0, &-angular_vel) * 0.5
+    }
+    /// Propagates the attitude over `dt` seconds of rotation at the constant angular velocity `angular_vel`
+    ///
+    /// `angular_vel` is given in the local vessel frame as returned by
+    /// [GetAngularVel](crate::VesselContext::GetAngularVel) \[**rad/s**\].
+    pub fn integrate(&self, angular_vel: &Vector3, dt: f64) -> Self {
+        (*self * Self::from_rotation_vector(&(angular_vel * -dt))).unit()
+    }
+}
+
+impl From<Matrix3> for Quaternion {
+    fn from(m: Matrix3) -> Self {
+        Self::from_matrix(&m)
+    }
+}
+impl From<Quaternion> for Matrix3 {
+    fn from(q: Quaternion) -> Self {
+        q.to_matrix()
+    }
+}
+
+/// Hamilton product of two quaternions
+impl std::ops::Mul for Quaternion {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+/// Scalar multiplication for `Quaternion`
+impl std::ops::Mul<f64> for Quaternion {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self::new(self.w * rhs, self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+/// Implement addition operator for `Quaternion`
+impl std::ops::Add for Quaternion {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.w + rhs.w, self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+/// Implement subtraction operator for `Quaternion`
+impl std::ops::Sub for Quaternion {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.w - rhs.w, self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+/// Implement negation operator for `Quaternion`
+impl std::ops::Neg for Quaternion {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.w, -self.x, -self.y, -self.z)
+    }
+}
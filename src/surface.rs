@@ -0,0 +1,244 @@
+//! Planetocentric coordinates: body-fixed frames, longitude/latitude and the local horizon frame
+//!
+//! Positions relative to a body, such as [VesselStatus::rpos](crate::VesselStatus::rpos), are expressed in
+//! the non-rotating ecliptic frame. [BodyRotation] converts them to the body-fixed frame, which rotates
+//! with the body and has its `y` axis along the north pole. In that frame, [EquPos] gives the spherical
+//! longitude/latitude/radius returned by `GetEquPos`, and [Geodetic] the longitude/latitude/altitude above
+//! a reference ellipsoid. [horizon_matrix] rotates vectors into the local horizon frame used by
+//! [ReferenceFrame::Horizon](crate::ReferenceFrame::Horizon), where `x` points east, `y` up and `z` north.
+//!
+//! ```
+//! use orbiter_rs::surface::{BodyRotation, EquPos};
+//! use orbiter_rs::{Body, V};
+//!
+//! let earth = BodyRotation::EARTH;
+//! let mjd = 51982.6699;
+//! // Over the equator, 400 km up
+//! let pos = V!(0.0, 0.0, Body::EARTH.radius + 400e3);
+//!
+//! let equ = EquPos::from_body_fixed(&earth.to_body_fixed(&pos, mjd));
+//! assert!(equ.latitude.abs() < 0.41);
+//! assert!((equ.radius - pos.length()).abs() < 1e-6);
+//! assert!((earth.from_body_fixed(&equ.to_body_fixed(), mjd) - pos).length() < 1e-6);
+//! ```
+//!
+//! Like the rest of the SDK, all frames are left-handed. Longitudes are positive eastwards.
+use std::f64::consts::TAU;
+
+use crate::frame::{Horizon, Vec3};
+use crate::time::{MJD_J2000, SECONDS_PER_DAY};
+use crate::{Matrix3, Vector3, VesselApi, V};
+
+/// Rotation of a body about its axis, as defined in Orbiter's planet configuration files
+///
+/// Precession of the axis is not modelled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyRotation {
+    /// Sidereal rotation period \[**s**\]
+    pub sidereal_period: f64,
+    /// Rotation angle of the body at the J2000 epoch \[**rad**\]
+    pub rotation_offset: f64,
+    /// Tilt of the north pole from the ecliptic north pole \[**rad**\]
+    pub obliquity: f64,
+    /// Ecliptic longitude of the line where the equator crosses the ecliptic \[**rad**\]
+    ///
+    /// The north pole is tilted by [obliquity](Self::obliquity) towards longitude `lan + 90°`.
+    pub lan: f64,
+}
+
+impl BodyRotation {
+    /// Earth, whose rotation angle approximates the Greenwich mean sidereal time
+    pub const EARTH: BodyRotation = BodyRotation::new(86164.0905, 4.894961213, 0.4090928023, 0.0);
+
+    /// Define the rotation of a body
+    pub const fn new(sidereal_period: f64, rotation_offset: f64, obliquity: f64, lan: f64) -> Self {
+        Self { sidereal_period, rotation_offset, obliquity, lan }
+    }
+    /// Returns the angular velocity of the body \[**rad/s**\]
+    pub fn angular_velocity(&self) -> f64 {
+        TAU / self.sidereal_period
+    }
+    /// Returns the rotation angle of the body at `mjd`, in `[0, 2π)` \[**rad**\]
+    pub fn rotation_angle(&self, mjd: f64) -> f64 {
+        let t = (mjd - MJD_J2000) * SECONDS_PER_DAY;
+        (self.rotation_offset + self.angular_velocity() * t).rem_euclid(TAU)
+    }
+    /// Returns the orientation of the body's axis, from the non-rotating body frame to the ecliptic frame
+    pub fn obliquity_matrix(&self) -> Matrix3 {
+        // Built in the right-handed frame and converted by swapping y and z on both sides
+        let swap = Matrix3::new([[1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]]);
+        swap * Matrix3::rotation_z(self.lan) * Matrix3::rotation_x(-self.obliquity) * swap
+    }
+    /// Returns the rotation matrix from the body-fixed frame to the ecliptic frame at `mjd`
+    pub fn matrix(&self, mjd: f64) -> Matrix3 {
+        // Eastward rotation about y turns x towards z in the left-handed frame
+        self.obliquity_matrix() * Matrix3::rotation_y(-self.rotation_angle(mjd))
+    }
+    /// Converts a body-centred ecliptic vector into the body-fixed frame at `mjd`
+    pub fn to_body_fixed(&self, v: &Vector3, mjd: f64) -> Vector3 {
+        self.matrix(mjd).tmul(v)
+    }
+    /// Converts a body-fixed vector into the body-centred ecliptic frame at `mjd`
+    pub fn from_body_fixed(&self, v: &Vector3, mjd: f64) -> Vector3 {
+        self.matrix(mjd) * v
+    }
+    /// Returns the velocity relative to the rotating surface, in the body-fixed frame, of a vessel at the
+    /// body-centred ecliptic position `pos` \[**m**\] and velocity `vel` \[**m/s**\]
+    ///
+    /// This is the airspeed in a still atmosphere.
+    pub fn ground_velocity(&self, pos: &Vector3, vel: &Vector3, mjd: f64) -> Vector3 {
+        let m = self.matrix(mjd);
+        let pos = m.tmul(pos);
+        m.tmul(vel) - V!(-pos.z(), 0.0, pos.x()) * self.angular_velocity()
+    }
+}
+
+/// Spherical equatorial coordinates in a body-fixed frame, as returned by `GetEquPos`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EquPos {
+    /// Longitude, positive eastwards, in `(-π, π]` \[**rad**\]
+    pub longitude: f64,
+    /// Latitude, positive northwards \[**rad**\]
+    pub latitude: f64,
+    /// Distance from the centre of the body \[**m**\]
+    pub radius: f64,
+}
+
+impl EquPos {
+    /// Build a new `EquPos` from its components
+    pub const fn new(longitude: f64, latitude: f64, radius: f64) -> Self {
+        Self { longitude, latitude, radius }
+    }
+    /// Converts a body-fixed position
+    pub fn from_body_fixed(pos: &Vector3) -> Self {
+        let radius = pos.length();
+        if radius == 0.0 {
+            return Self::default();
+        }
+        Self {
+            longitude: pos.z().atan2(pos.x()),
+            latitude: (pos.y() / radius).clamp(-1.0, 1.0).asin(),
+            radius,
+        }
+    }
+    /// Converts into a body-fixed position
+    pub fn to_body_fixed(&self) -> Vector3 {
+        spherical(self.longitude, self.latitude) * self.radius
+    }
+    /// Returns the rotation matrix from the local horizon frame to the body-fixed frame at this position
+    pub fn horizon_matrix(&self) -> Matrix3 {
+        horizon_matrix(self.longitude, self.latitude)
+    }
+}
+
+/// Geodetic coordinates above a reference ellipsoid in a body-fixed frame
+///
+/// The ellipsoid is defined by its equatorial radius and flattening `f = (a - b) / a`; with a flattening
+/// of zero the coordinates are the spherical ones of [EquPos] with the altitude above the sphere.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Geodetic {
+    /// Longitude, positive eastwards, in `(-π, π]` \[**rad**\]
+    pub longitude: f64,
+    /// Geodetic latitude, positive northwards \[**rad**\]
+    pub latitude: f64,
+    /// Height above the ellipsoid along its normal \[**m**\]
+    pub altitude: f64,
+}
+
+impl Geodetic {
+    /// Build a new `Geodetic` from its components
+    pub const fn new(longitude: f64, latitude: f64, altitude: f64) -> Self {
+        Self { longitude, latitude, altitude }
+    }
+    /// Converts a body-fixed position, given the equatorial `radius` \[**m**\] and `flattening` of the ellipsoid
+    pub fn from_body_fixed(pos: &Vector3, radius: f64, flattening: f64) -> Self {
+        let e2 = flattening * (2.0 - flattening);
+        let p = pos.x().hypot(pos.z());
+        let longitude = if p == 0.0 { 0.0 } else { pos.z().atan2(pos.x()) };
+        // Fixed-point iteration on the latitude, which also converges at the poles
+        let mut latitude = pos.y().atan2(p * (1.0 - e2));
+        for _ in 0..20 {
+            let n = radius / (1.0 - e2 * latitude.sin().powi(2)).sqrt();
+            let next = (pos.y() + e2 * n * latitude.sin()).atan2(p);
+            let done = (next - latitude).abs() < 1e-15;
+            latitude = next;
+            if done {
+                break;
+            }
+        }
+        let (sin_lat, cos_lat) = latitude.sin_cos();
+        let altitude = p * cos_lat + pos.y() * sin_lat - radius * (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        Self { longitude, latitude, altitude }
+    }
+    /// Converts into a body-fixed position, given the equatorial `radius` \[**m**\] and `flattening` of the ellipsoid
+    pub fn to_body_fixed(&self, radius: f64, flattening: f64) -> Vector3 {
+        let e2 = flattening * (2.0 - flattening);
+        let (sin_lat, cos_lat) = self.latitude.sin_cos();
+        let (sin_lng, cos_lng) = self.longitude.sin_cos();
+        let n = radius / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        V!(
+            (n + self.altitude) * cos_lat * cos_lng,
+            (n * (1.0 - e2) + self.altitude) * sin_lat,
+            (n + self.altitude) * cos_lat * sin_lng
+        )
+    }
+    /// Returns the rotation matrix from the local horizon frame to the body-fixed frame at this position
+    ///
+    /// The `y` axis of the horizon frame is the normal to the ellipsoid.
+    pub fn horizon_matrix(&self) -> Matrix3 {
+        horizon_matrix(self.longitude, self.latitude)
+    }
+}
+
+/// Unit vector of the body-fixed frame at `longitude` and `latitude`
+fn spherical(longitude: f64, latitude: f64) -> Vector3 {
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let (sin_lng, cos_lng) = longitude.sin_cos();
+    V!(cos_lat * cos_lng, sin_lat, cos_lat * sin_lng)
+}
+
+/// Returns the rotation matrix from the local horizon frame (`x` east, `y` up, `z` north) to the body-fixed
+/// frame at `longitude` and `latitude` \[**rad**\]
+///
+/// Its transpose converts from the body-fixed frame to the horizon frame. At the poles, north is taken
+/// along the meridian of `longitude`.
+pub fn horizon_matrix(longitude: f64, latitude: f64) -> Matrix3 {
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let (sin_lng, cos_lng) = longitude.sin_cos();
+    let east = V!(-sin_lng, 0.0, cos_lng);
+    let up = spherical(longitude, latitude);
+    let north = V!(-sin_lat * cos_lng, cos_lat, -sin_lat * sin_lng);
+    Matrix3::from_cols(&east, &up, &north)
+}
+
+/// Returns the heading of a horizon frame vector, clockwise from north, in `[0, 2π)` \[**rad**\]
+pub fn heading(horizon: &Vector3) -> f64 {
+    horizon.x().atan2(horizon.z()).rem_euclid(TAU)
+}
+
+/// Returns the flight path angle of a horizon frame vector above the local horizontal \[**rad**\]
+pub fn flight_path_angle(horizon: &Vector3) -> f64 {
+    horizon.y().atan2(horizon.x().hypot(horizon.z()))
+}
+
+/// Typed versions of the `GetEquPos` and `GetHorizonAirspeedVector` methods of [VesselApi]
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselSurface: VesselApi {
+    /// Returns the position of the vessel in the body-fixed frame of its reference body
+    fn equ_pos(&self) -> EquPos {
+        let mut pos = EquPos::default();
+        self.GetEquPos(&mut pos.longitude, &mut pos.latitude, &mut pos.radius);
+        pos
+    }
+    /// Returns the airspeed vector in the local horizon frame, or `None` without a reference body
+    fn horizon_airspeed_vector(&self) -> Option<Vec3<Horizon>> {
+        let mut v = Vector3::default();
+        self.GetHorizonAirspeedVector(&mut v).then(|| Vec3::from_vector(v))
+    }
+    /// Returns the heading of the airspeed vector, clockwise from north \[**rad**\]
+    fn airspeed_heading(&self) -> Option<f64> {
+        self.horizon_airspeed_vector().map(|v| heading(&v.vector()))
+    }
+}
+impl<V: VesselApi + ?Sized> VesselSurface for V {}
@@ -0,0 +1,176 @@
+//! Conversions of the Modified Julian Date used by Orbiter
+//!
+//! Orbiter measures simulation time as a Modified Julian Date (MJD), e.g. the `mjd` argument of
+//! [OrbiterVessel::on_pre_step](crate::OrbiterVessel::on_pre_step) or the `Date MJD` line of a scenario.
+//! Orbiter does not model leap seconds, so MJDs are treated as UTC throughout this module.
+//!
+//! ```
+//! use orbiter_rs::time::{DateTime, ElapsedTime};
+//!
+//! let date = DateTime::from_mjd(51982.6699);
+//! assert_eq!(date.to_string(), "2001-03-14 16:04:39 UTC");
+//! assert_eq!(date.day_of_year(), 73);
+//!
+//! let launch = DateTime::new(2001, 3, 14, 14, 30, 0.0).to_mjd();
+//! assert_eq!(ElapsedTime::since(launch, 51982.6699).to_string(), "T+01:34:39");
+//! ```
+use std::f64::consts::TAU;
+use std::fmt;
+
+use crate::units::Time;
+
+/// Seconds per day
+pub const SECONDS_PER_DAY: f64 = 86400.0;
+/// Difference between Julian Date and Modified Julian Date
+pub const JD_MJD_OFFSET: f64 = 2400000.5;
+/// MJD of the J2000 epoch, 2000-01-01 12:00 UTC
+pub const MJD_J2000: f64 = 51544.5;
+
+/// Converts a Modified Julian Date to a Julian Date
+pub fn mjd_to_jd(mjd: f64) -> f64 {
+    mjd + JD_MJD_OFFSET
+}
+/// Converts a Julian Date to a Modified Julian Date
+pub fn jd_to_mjd(jd: f64) -> f64 {
+    jd - JD_MJD_OFFSET
+}
+/// Returns the seconds elapsed since the J2000 epoch at `mjd`
+pub fn seconds_since_j2000(mjd: f64) -> f64 {
+    (mjd - MJD_J2000) * SECONDS_PER_DAY
+}
+/// Returns the MJD `seconds` after the J2000 epoch
+pub fn mjd_from_j2000_seconds(seconds: f64) -> f64 {
+    MJD_J2000 + seconds / SECONDS_PER_DAY
+}
+/// Returns the Greenwich mean sidereal time at `mjd` as an angle in `[0, 2π)` \[**rad**\]
+///
+/// Uses the IAU 1982 expression.
+pub fn gmst(mjd: f64) -> f64 {
+    let t = (mjd - MJD_J2000) / 36525.0;
+    let seconds = 67310.54841 + (876600.0 * 3600.0 + 8640184.812866) * t + 0.093104 * t * t - 6.2e-6 * t * t * t;
+    (seconds.rem_euclid(SECONDS_PER_DAY) / SECONDS_PER_DAY) * TAU
+}
+
+/// A UTC calendar date and time of day in the proleptic Gregorian calendar
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DateTime {
+    /// Year, e.g. 2001
+    pub year: i32,
+    /// Month of the year, starting at 1
+    pub month: u32,
+    /// Day of the month, starting at 1
+    pub day: u32,
+    /// Hour of the day
+    pub hour: u32,
+    /// Minute of the hour
+    pub minute: u32,
+    /// Second of the minute, including fractions
+    pub second: f64,
+}
+
+impl DateTime {
+    /// Build a new `DateTime` from its components
+    pub const fn new(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: f64) -> Self {
+        Self { year, month, day, hour, minute, second }
+    }
+    /// Converts a Modified Julian Date, rounded to the millisecond
+    pub fn from_mjd(mjd: f64) -> Self {
+        let mut days = mjd.floor() as i64;
+        let mut millis = ((mjd - mjd.floor()) * SECONDS_PER_DAY * 1e3).round() as i64;
+        if millis >= 86_400_000 {
+            days += 1;
+            millis -= 86_400_000;
+        }
+        // Fliegel and Van Flandern's algorithm, from the Julian Day Number at noon
+        let mut l = days + 2400001 + 68569;
+        let n = 4 * l / 146097;
+        l -= (146097 * n + 3) / 4;
+        let i = 4000 * (l + 1) / 1461001;
+        l = l - 1461 * i / 4 + 31;
+        let j = 80 * l / 2447;
+        let day = l - 2447 * j / 80;
+        l = j / 11;
+        let month = j + 2 - 12 * l;
+        let year = 100 * (n - 49) + i + l;
+        Self {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+            hour: (millis / 3_600_000) as u32,
+            minute: (millis / 60_000 % 60) as u32,
+            second: (millis % 60_000) as f64 / 1e3,
+        }
+    }
+    /// Converts into a Modified Julian Date
+    pub fn to_mjd(&self) -> f64 {
+        let (y, m, d) = (self.year as i64, self.month as i64, self.day as i64);
+        let a = (m - 14) / 12;
+        let jdn = 1461 * (y + 4800 + a) / 4 + 367 * (m - 2 - 12 * a) / 12 - 3 * ((y + 4900 + a) / 100) / 4 + d - 32075;
+        let seconds = self.hour as f64 * 3600.0 + self.minute as f64 * 60.0 + self.second;
+        (jdn - 2400001) as f64 + seconds / SECONDS_PER_DAY
+    }
+    /// Returns the day of the year, starting at 1 for January 1st
+    pub fn day_of_year(&self) -> u32 {
+        let jan1 = Self::new(self.year, 1, 1, 0, 0, 0.0).to_mjd();
+        let date = Self::new(self.year, self.month, self.day, 0, 0, 0.0).to_mjd();
+        (date - jan1) as u32 + 1
+    }
+}
+
+/// Formats as `YYYY-MM-DD hh:mm:ss UTC`, with fractions of a second if a precision is given
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02} {:02}:{:02}:", self.year, self.month, self.day, self.hour, self.minute)?;
+        write_seconds(f, self.second)?;
+        write!(f, " UTC")
+    }
+}
+
+/// A mission elapsed time, e.g. since launch or since the start of a burn
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct ElapsedTime(pub f64);
+
+impl ElapsedTime {
+    /// Time elapsed from `start_mjd` to `mjd`, negative before the start
+    pub fn since(start_mjd: f64, mjd: f64) -> Self {
+        Self((mjd - start_mjd) * SECONDS_PER_DAY)
+    }
+    /// Returns the elapsed time in seconds
+    pub fn seconds(&self) -> f64 {
+        self.0
+    }
+}
+impl From<Time> for ElapsedTime {
+    fn from(t: Time) -> Self {
+        Self(t.in_seconds())
+    }
+}
+
+/// Formats as `T+hh:mm:ss`, or `T-` before the start, prefixed with the number of days once it reaches a
+/// full day (`T+2d 03:04:05`), with fractions of a second if a precision is given
+impl fmt::Display for ElapsedTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.0 < 0.0 { '-' } else { '+' };
+        let total = self.0.abs();
+        let (days, rest) = ((total / SECONDS_PER_DAY).floor(), total % SECONDS_PER_DAY);
+        write!(f, "T{}", sign)?;
+        if days > 0.0 {
+            write!(f, "{}d ", days)?;
+        }
+        write!(f, "{:02}:{:02}:", (rest / 3600.0).floor(), (rest % 3600.0 / 60.0).floor())?;
+        write_seconds(f, rest % 60.0)
+    }
+}
+
+/// Writes the seconds of a time with two integer digits, truncated to the precision of `f` if any
+fn write_seconds(f: &mut fmt::Formatter, seconds: f64) -> fmt::Result {
+    match f.precision() {
+        Some(precision) => {
+            let scale = 10f64.powi(precision as i32);
+            let seconds = (seconds * scale).floor() / scale;
+            let width = if precision > 0 { precision + 3 } else { 2 };
+            write!(f, "{:0width$.precision$}", seconds, width = width, precision = precision)
+        }
+        None => write!(f, "{:02}", seconds.floor()),
+    }
+}
@@ -0,0 +1,1379 @@
+//! Recording and replay of vessel callback traces
+//!
+//! A trace captures everything Orbiter feeds into an addon during a session: the `OrbiterVessel`
+//! callbacks with their arguments, the configuration items read in `set_class_caps`, the scenario lines
+//! and the key presses. The [SDKVessel](crate::SDKVessel) handed to the addon is a [RecordingVessel], so
+//! the calls the addon makes and the values returned by the getters are captured as well.
+//!
+//! Recording is enabled at runtime with [start_recording], e.g. from the addon's `init` function, and
+//! writes one line per event:
+//!
+//! ```text
+//! caps
+//! cfg Size 2.5
+//! call SetSize 2.5
+//! pre 10.0 0.02 51544.5
+//! call GetAltitude
+//! ret GetAltitude 1523.25
+//! key 38 1 42
+//! ```
+//!
+//! With the in-memory backend, `replay` feeds a trace back into an addon and compares the calls it makes
+//! against the recorded ones, so a session captured by a pilot can be reproduced deterministically in a test.
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::{
+    Elements, Key, KeyStates, Matrix3, OrbitParam, PropellantHandle, ReferenceFrame,
+    ThrustGroupHandle, ThrusterGroupType, ThrusterHandle, Vector3, VesselApi, VesselStatus,
+    OBJHANDLE, PROPELLANT_HANDLE, THGROUP_HANDLE, THRUSTER_HANDLE,
+};
+
+/// A call made by the addon through the `VESSEL` interface
+///
+/// Thruster, propellant and thruster group handles are numbered from one in order of creation.
+#[derive(Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum VesselCall {
+    SetSize(f64),
+    SetPMI(Vector3),
+    SetEmptyMass(f64),
+    SetCameraOffset(Vector3),
+    SetTouchdownPoints(Vector3, Vector3, Vector3),
+    SetThrusterDir(ThrusterHandle, Vector3),
+    SetThrusterLevel(ThrusterHandle, f64),
+    SetThrusterLevelSingleStep(ThrusterHandle, f64),
+    SetThrusterGroupLevelByType(ThrusterGroupType, f64),
+    AddMesh(String),
+    AddMeshWithOffset(String, Vector3),
+    AddExhaust(ThrusterHandle, f64, f64),
+    CreatePropellantResource(f64),
+    CreateThruster {
+        pos: Vector3,
+        dir: Vector3,
+        maxth0: f64,
+        ph: PropellantHandle,
+        isp: f64,
+    },
+    CreateThrusterGroup(Vec<ThrusterHandle>, ThrusterGroupType),
+    ClearMeshes,
+    Local2Rel(Vector3),
+    Global2Local(Vector3),
+    Local2Global(Vector3),
+    GetName,
+    GetStatus,
+    GetPropellantMass(PropellantHandle),
+    GetAngularVel,
+    GetAirspeedVector(ReferenceFrame),
+    GetThrustVector,
+    GetTotalPropellantMass,
+    GetEmptyMass,
+    GetThrusterGroupLevelByType(ThrusterGroupType),
+    GetThrusterGroupLevel(ThrustGroupHandle),
+    GetThrusterMax0(ThrusterHandle),
+    GetThrusterIsp0(ThrusterHandle),
+    GroundContact,
+    GetSurfaceRef,
+    GetSurfaceElevation,
+    GetAltitude,
+    GetAtmTemperature,
+    GetAtmDensity,
+    GetAtmPressure,
+    GetRelativeVel(OBJHANDLE),
+    GetEquPos,
+    GetHorizonAirspeedVector,
+    GetRotationMatrix,
+    GetRotDrag,
+    GetElements(OBJHANDLE, f64, i32),
+    SetElements(OBJHANDLE, Elements, f64, i32),
+    ParseScenarioLineEx(String),
+}
+
+/// A value returned to the addon by one of the `VESSEL` getters
+#[derive(Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum VesselReturn {
+    Local2Rel(Vector3),
+    Global2Local(Vector3),
+    Local2Global(Vector3),
+    GetName(String),
+    GetStatus(Box<VesselStatus>),
+    GetPropellantMass(f64),
+    GetAngularVel(Vector3),
+    GetAirspeedVector(bool, Vector3),
+    GetThrustVector(bool, Vector3),
+    GetTotalPropellantMass(f64),
+    GetEmptyMass(f64),
+    GetThrusterGroupLevelByType(f64),
+    GetThrusterGroupLevel(f64),
+    GetThrusterMax0(f64),
+    GetThrusterIsp0(f64),
+    GroundContact(bool),
+    GetSurfaceRef(OBJHANDLE),
+    GetSurfaceElevation(f64),
+    GetAltitude(f64),
+    GetAtmTemperature(f64),
+    GetAtmDensity(f64),
+    GetAtmPressure(f64),
+    GetRelativeVel(Vector3),
+    GetEquPos(f64, f64, f64),
+    GetHorizonAirspeedVector(bool, Vector3),
+    GetRotationMatrix(Matrix3),
+    GetRotDrag(Vector3),
+    GetElements(bool, Elements, OrbitParam),
+    SetElements(bool, OrbitParam),
+}
+
+/// A single line of a trace
+#[derive(Debug, PartialEq)]
+pub enum TraceEvent {
+    /// [OrbiterVessel::set_class_caps](crate::OrbiterVessel::set_class_caps) was called
+    SetClassCaps,
+    /// The addon read `item` from its configuration file
+    ConfigItem {
+        /// Name of the item
+        item: String,
+        /// Value as written in the configuration file
+        value: String,
+    },
+    /// [OrbiterVessel::on_pre_step](crate::OrbiterVessel::on_pre_step) was called
+    PreStep {
+        /// Simulation time \[**s**\]
+        sim_t: f64,
+        /// Length of the time step \[**s**\]
+        sim_dt: f64,
+        /// Modified Julian Date
+        mjd: f64,
+    },
+    /// [OrbiterVessel::on_post_step](crate::OrbiterVessel::on_post_step) was called
+    PostStep {
+        /// Simulation time \[**s**\]
+        sim_t: f64,
+        /// Length of the time step \[**s**\]
+        sim_dt: f64,
+        /// Modified Julian Date
+        mjd: f64,
+    },
+    /// [OrbiterVessel::consume_buffered_key](crate::OrbiterVessel::consume_buffered_key) was called
+    Key {
+        /// Key that was pressed or released
+        key: Key,
+        /// `true` if the key was pressed
+        down: bool,
+        /// Keys held down at the time
+        held: Vec<Key>,
+    },
+    /// A scenario line was passed to [OrbiterVessel::on_load_param](crate::OrbiterVessel::on_load_param)
+    LoadParam(String),
+    /// [OrbiterVessel::on_save_state](crate::OrbiterVessel::on_save_state) was called
+    SaveState,
+    /// The addon made a call through a [RecordingVessel]
+    Call(VesselCall),
+    /// A getter called through a [RecordingVessel] returned a value
+    Return(VesselReturn),
+}
+
+/// Error returned when parsing a malformed trace
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseTraceError {
+    /// Line number, starting at one
+    pub line: usize,
+    /// Description of the problem
+    pub message: String,
+}
+impl fmt::Display for ParseTraceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+impl std::error::Error for ParseTraceError {}
+
+/// A sequence of recorded events
+#[derive(Debug, Default, PartialEq)]
+pub struct Trace {
+    /// Events in the order they happened
+    pub events: Vec<TraceEvent>,
+}
+impl Trace {
+    /// Reads a trace from a file written by the recorder
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        std::fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    /// Writes the trace to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+    /// Returns the calls made by the addon
+    pub fn calls(&self) -> impl Iterator<Item = &VesselCall> {
+        self.events.iter().filter_map(|event| match event {
+            TraceEvent::Call(call) => Some(call),
+            _ => None,
+        })
+    }
+}
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for event in self.events.iter() {
+            writeln!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+impl FromStr for Trace {
+    type Err = ParseTraceError;
+
+    /// Parses a trace, one event per line. Empty lines and lines starting with `#` are skipped.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut events = Vec::new();
+        for (idx, line) in s.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let event = parse_event(line).map_err(|message| ParseTraceError {
+                line: idx + 1,
+                message,
+            })?;
+            events.push(event);
+        }
+        Ok(Self { events })
+    }
+}
+
+/// A value that is written to a trace as one or more space-separated tokens
+trait Token: Sized {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result;
+    fn read(tokens: &mut Tokens) -> Result<Self, String>;
+}
+
+/// Splits a trace line into tokens
+struct Tokens<'a>(&'a str);
+impl<'a> Tokens<'a> {
+    fn next_str(&mut self) -> Result<&'a str, String> {
+        let s = self.0.trim_start_matches(' ');
+        if s.is_empty() {
+            return Err("missing value".into());
+        }
+        let end = s.find(' ').unwrap_or(s.len());
+        self.0 = &s[end..];
+        Ok(&s[..end])
+    }
+    fn next<T: Token>(&mut self) -> Result<T, String> {
+        T::read(self)
+    }
+    /// Returns the remainder of the line, used for values that may contain spaces
+    fn rest(&mut self) -> String {
+        let s = self.0.strip_prefix(' ').unwrap_or(self.0);
+        self.0 = "";
+        s.to_owned()
+    }
+    fn finish(&self) -> Result<(), String> {
+        if self.0.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(format!("unexpected value `{}`", self.0.trim()))
+        }
+    }
+}
+
+fn parse_number<T: FromStr>(token: &str) -> Result<T, String> {
+    token.parse().map_err(|_| format!("invalid number `{}`", token))
+}
+
+impl Token for f64 {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `Debug` prints the shortest representation that reads back as the same value
+        write!(f, " {:?}", self)
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        parse_number(tokens.next_str()?)
+    }
+}
+impl Token for i32 {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, " {}", self)
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        parse_number(tokens.next_str()?)
+    }
+}
+impl Token for usize {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, " {}", self)
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        parse_number(tokens.next_str()?)
+    }
+}
+impl Token for bool {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, " {}", *self as u8)
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        match tokens.next_str()? {
+            "0" => Ok(false),
+            "1" => Ok(true),
+            other => Err(format!("invalid flag `{}`", other)),
+        }
+    }
+}
+impl Token for Vector3 {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.x().write(f)?;
+        self.y().write(f)?;
+        self.z().write(f)
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        Ok(Vector3::new(tokens.next()?, tokens.next()?, tokens.next()?))
+    }
+}
+impl Token for Matrix3 {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (0..3).try_for_each(|i| self.row(i).write(f))
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        let (r1, r2, r3): (Vector3, Vector3, Vector3) =
+            (tokens.next()?, tokens.next()?, tokens.next()?);
+        Ok(Matrix3::from_rows(&r1, &r2, &r3))
+    }
+}
+impl Token for Key {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, " {}", self.as_u8())
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        Ok(Key::from(parse_number(tokens.next_str()?)?))
+    }
+}
+macro_rules! handle_token {
+    ($($handle:ident),*) => {
+        $(
+            impl Token for $handle {
+                fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    self.0.write(f)
+                }
+                fn read(tokens: &mut Tokens) -> Result<Self, String> {
+                    Ok(Self(tokens.next()?))
+                }
+            }
+        )*
+    };
+}
+handle_token!(ThrusterHandle, PropellantHandle, ThrustGroupHandle, OBJHANDLE);
+
+/// Writes an enum value by its name and reads it back by looking it up in `$all`
+macro_rules! named_token {
+    ($ty:ty, $all:expr) => {
+        impl Token for $ty {
+            fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, " {:?}", self)
+            }
+            fn read(tokens: &mut Tokens) -> Result<Self, String> {
+                let name = tokens.next_str()?;
+                $all.into_iter()
+                    .find(|value| format!("{:?}", value) == name)
+                    .ok_or_else(|| format!("unknown {} `{}`", stringify!($ty), name))
+            }
+        }
+    };
+}
+named_token!(
+    ReferenceFrame,
+    [
+        ReferenceFrame::Global,
+        ReferenceFrame::Local,
+        ReferenceFrame::RefLocal,
+        ReferenceFrame::Horizon,
+    ]
+);
+named_token!(
+    ThrusterGroupType,
+    [
+        ThrusterGroupType::Main,
+        ThrusterGroupType::Retro,
+        ThrusterGroupType::Hover,
+        ThrusterGroupType::AttPitchup,
+        ThrusterGroupType::AttPitchdown,
+        ThrusterGroupType::AttYawleft,
+        ThrusterGroupType::AttYawright,
+        ThrusterGroupType::AttBankleft,
+        ThrusterGroupType::AttBankright,
+        ThrusterGroupType::AttRight,
+        ThrusterGroupType::AttLeft,
+        ThrusterGroupType::AttUp,
+        ThrusterGroupType::AttDown,
+        ThrusterGroupType::AttForward,
+        ThrusterGroupType::AttBack,
+        ThrusterGroupType::User,
+    ]
+);
+
+impl Token for Elements {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        [self.a, self.e, self.i, self.theta, self.omegab, self.L]
+            .iter()
+            .try_for_each(|value| value.write(f))
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        Ok(Elements {
+            a: tokens.next()?,
+            e: tokens.next()?,
+            i: tokens.next()?,
+            theta: tokens.next()?,
+            omegab: tokens.next()?,
+            L: tokens.next()?,
+        })
+    }
+}
+impl Token for OrbitParam {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        [
+            self.SMi, self.PeD, self.ApD, self.MnA, self.TrA, self.MnL, self.TrL, self.EcA, self.Lec,
+            self.T, self.PeT, self.ApT,
+        ]
+        .iter()
+        .try_for_each(|value| value.write(f))
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        Ok(OrbitParam {
+            SMi: tokens.next()?,
+            PeD: tokens.next()?,
+            ApD: tokens.next()?,
+            MnA: tokens.next()?,
+            TrA: tokens.next()?,
+            MnL: tokens.next()?,
+            TrL: tokens.next()?,
+            EcA: tokens.next()?,
+            Lec: tokens.next()?,
+            T: tokens.next()?,
+            PeT: tokens.next()?,
+            ApT: tokens.next()?,
+        })
+    }
+}
+
+// Only the state vectors, engine settings and landing parameters are kept
+impl Token for VesselStatus {
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.rpos.write(f)?;
+        self.rvel.write(f)?;
+        self.vrot.write(f)?;
+        self.arot.write(f)?;
+        self.fuel.write(f)?;
+        self.eng_main.write(f)?;
+        self.eng_hovr.write(f)?;
+        self.rbody.write(f)?;
+        self.base.write(f)?;
+        self.port.write(f)?;
+        self.status.write(f)?;
+        self.vdata[0].write(f)
+    }
+    fn read(tokens: &mut Tokens) -> Result<Self, String> {
+        let mut status = VesselStatus {
+            rpos: tokens.next()?,
+            rvel: tokens.next()?,
+            vrot: tokens.next()?,
+            arot: tokens.next()?,
+            fuel: tokens.next()?,
+            eng_main: tokens.next()?,
+            eng_hovr: tokens.next()?,
+            rbody: tokens.next()?,
+            base: tokens.next()?,
+            port: tokens.next()?,
+            status: tokens.next()?,
+            ..Default::default()
+        };
+        status.vdata[0] = tokens.next()?;
+        Ok(status)
+    }
+}
+
+/// Writes `$name` followed by the tokens of each value
+macro_rules! write_tokens {
+    ($f:expr, $name:expr $(, $value:expr)*) => {{
+        write!($f, "{}", $name)?;
+        $( Token::write($value, $f)?; )*
+        Ok(())
+    }};
+}
+
+impl fmt::Display for VesselCall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use VesselCall::*;
+        match self {
+            SetSize(size) => write_tokens!(f, "SetSize", size),
+            SetPMI(pmi) => write_tokens!(f, "SetPMI", pmi),
+            SetEmptyMass(mass) => write_tokens!(f, "SetEmptyMass", mass),
+            SetCameraOffset(ofs) => write_tokens!(f, "SetCameraOffset", ofs),
+            SetTouchdownPoints(pt1, pt2, pt3) => {
+                write_tokens!(f, "SetTouchdownPoints", pt1, pt2, pt3)
+            }
+            SetThrusterDir(th, dir) => write_tokens!(f, "SetThrusterDir", th, dir),
+            SetThrusterLevel(th, level) => write_tokens!(f, "SetThrusterLevel", th, level),
+            SetThrusterLevelSingleStep(th, level) => {
+                write_tokens!(f, "SetThrusterLevelSingleStep", th, level)
+            }
+            SetThrusterGroupLevelByType(thgroup_type, level) => {
+                write_tokens!(f, "SetThrusterGroupLevelByType", thgroup_type, level)
+            }
+            AddMesh(name) => write!(f, "AddMesh {}", name),
+            AddMeshWithOffset(name, ofs) => {
+                write_tokens!(f, "AddMeshWithOffset", ofs)?;
+                write!(f, " {}", name)
+            }
+            AddExhaust(th, lscale, wscale) => write_tokens!(f, "AddExhaust", th, lscale, wscale),
+            CreatePropellantResource(mass) => write_tokens!(f, "CreatePropellantResource", mass),
+            CreateThruster {
+                pos,
+                dir,
+                maxth0,
+                ph,
+                isp,
+            } => write_tokens!(f, "CreateThruster", pos, dir, maxth0, ph, isp),
+            CreateThrusterGroup(thrusters, thgroup_type) => {
+                write_tokens!(f, "CreateThrusterGroup", thgroup_type)?;
+                thrusters.iter().try_for_each(|th| th.write(f))
+            }
+            ClearMeshes => write!(f, "ClearMeshes"),
+            Local2Rel(local) => write_tokens!(f, "Local2Rel", local),
+            Global2Local(global) => write_tokens!(f, "Global2Local", global),
+            Local2Global(local) => write_tokens!(f, "Local2Global", local),
+            GetName => write!(f, "GetName"),
+            GetStatus => write!(f, "GetStatus"),
+            GetPropellantMass(ph) => write_tokens!(f, "GetPropellantMass", ph),
+            GetAngularVel => write!(f, "GetAngularVel"),
+            GetAirspeedVector(frame) => write_tokens!(f, "GetAirspeedVector", frame),
+            GetThrustVector => write!(f, "GetThrustVector"),
+            GetTotalPropellantMass => write!(f, "GetTotalPropellantMass"),
+            GetEmptyMass => write!(f, "GetEmptyMass"),
+            GetThrusterGroupLevelByType(thgroup_type) => {
+                write_tokens!(f, "GetThrusterGroupLevelByType", thgroup_type)
+            }
+            GetThrusterGroupLevel(th) => write_tokens!(f, "GetThrusterGroupLevel", th),
+            GetThrusterMax0(th) => write_tokens!(f, "GetThrusterMax0", th),
+            GetThrusterIsp0(th) => write_tokens!(f, "GetThrusterIsp0", th),
+            GroundContact => write!(f, "GroundContact"),
+            GetSurfaceRef => write!(f, "GetSurfaceRef"),
+            GetSurfaceElevation => write!(f, "GetSurfaceElevation"),
+            GetAltitude => write!(f, "GetAltitude"),
+            GetAtmTemperature => write!(f, "GetAtmTemperature"),
+            GetAtmDensity => write!(f, "GetAtmDensity"),
+            GetAtmPressure => write!(f, "GetAtmPressure"),
+            GetRelativeVel(href) => write_tokens!(f, "GetRelativeVel", href),
+            GetEquPos => write!(f, "GetEquPos"),
+            GetHorizonAirspeedVector => write!(f, "GetHorizonAirspeedVector"),
+            GetRotationMatrix => write!(f, "GetRotationMatrix"),
+            GetRotDrag => write!(f, "GetRotDrag"),
+            GetElements(href, mjd_ref, frame) => write_tokens!(f, "GetElements", href, mjd_ref, frame),
+            SetElements(href, el, mjd_ref, frame) => {
+                write_tokens!(f, "SetElements", href, el, mjd_ref, frame)
+            }
+            ParseScenarioLineEx(line) => write!(f, "ParseScenarioLineEx {}", line),
+        }
+    }
+}
+fn parse_call(t: &mut Tokens) -> Result<VesselCall, String> {
+    use VesselCall::*;
+    let call = match t.next_str()? {
+        "SetSize" => SetSize(t.next()?),
+        "SetPMI" => SetPMI(t.next()?),
+        "SetEmptyMass" => SetEmptyMass(t.next()?),
+        "SetCameraOffset" => SetCameraOffset(t.next()?),
+        "SetTouchdownPoints" => SetTouchdownPoints(t.next()?, t.next()?, t.next()?),
+        "SetThrusterDir" => SetThrusterDir(t.next()?, t.next()?),
+        "SetThrusterLevel" => SetThrusterLevel(t.next()?, t.next()?),
+        "SetThrusterLevelSingleStep" => SetThrusterLevelSingleStep(t.next()?, t.next()?),
+        "SetThrusterGroupLevelByType" => SetThrusterGroupLevelByType(t.next()?, t.next()?),
+        "AddMesh" => AddMesh(t.rest()),
+        "AddMeshWithOffset" => {
+            let ofs = t.next()?;
+            AddMeshWithOffset(t.rest(), ofs)
+        }
+        "AddExhaust" => AddExhaust(t.next()?, t.next()?, t.next()?),
+        "CreatePropellantResource" => CreatePropellantResource(t.next()?),
+        "CreateThruster" => CreateThruster {
+            pos: t.next()?,
+            dir: t.next()?,
+            maxth0: t.next()?,
+            ph: t.next()?,
+            isp: t.next()?,
+        },
+        "CreateThrusterGroup" => {
+            let thgroup_type = t.next()?;
+            let mut thrusters = Vec::new();
+            while !t.0.trim().is_empty() {
+                thrusters.push(t.next()?);
+            }
+            CreateThrusterGroup(thrusters, thgroup_type)
+        }
+        "ClearMeshes" => ClearMeshes,
+        "Local2Rel" => Local2Rel(t.next()?),
+        "Global2Local" => Global2Local(t.next()?),
+        "Local2Global" => Local2Global(t.next()?),
+        "GetName" => GetName,
+        "GetStatus" => GetStatus,
+        "GetPropellantMass" => GetPropellantMass(t.next()?),
+        "GetAngularVel" => GetAngularVel,
+        "GetAirspeedVector" => GetAirspeedVector(t.next()?),
+        "GetThrustVector" => GetThrustVector,
+        "GetTotalPropellantMass" => GetTotalPropellantMass,
+        "GetEmptyMass" => GetEmptyMass,
+        "GetThrusterGroupLevelByType" => GetThrusterGroupLevelByType(t.next()?),
+        "GetThrusterGroupLevel" => GetThrusterGroupLevel(t.next()?),
+        "GetThrusterMax0" => GetThrusterMax0(t.next()?),
+        "GetThrusterIsp0" => GetThrusterIsp0(t.next()?),
+        "GroundContact" => GroundContact,
+        "GetSurfaceRef" => GetSurfaceRef,
+        "GetSurfaceElevation" => GetSurfaceElevation,
+        "GetAltitude" => GetAltitude,
+        "GetAtmTemperature" => GetAtmTemperature,
+        "GetAtmDensity" => GetAtmDensity,
+        "GetAtmPressure" => GetAtmPressure,
+        "GetRelativeVel" => GetRelativeVel(t.next()?),
+        "GetEquPos" => GetEquPos,
+        "GetHorizonAirspeedVector" => GetHorizonAirspeedVector,
+        "GetRotationMatrix" => GetRotationMatrix,
+        "GetRotDrag" => GetRotDrag,
+        "GetElements" => GetElements(t.next()?, t.next()?, t.next()?),
+        "SetElements" => SetElements(t.next()?, t.next()?, t.next()?, t.next()?),
+        "ParseScenarioLineEx" => ParseScenarioLineEx(t.rest()),
+        other => return Err(format!("unknown call `{}`", other)),
+    };
+    Ok(call)
+}
+
+impl fmt::Display for VesselReturn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use VesselReturn::*;
+        match self {
+            Local2Rel(rel) => write_tokens!(f, "Local2Rel", rel),
+            Global2Local(local) => write_tokens!(f, "Global2Local", local),
+            Local2Global(global) => write_tokens!(f, "Local2Global", global),
+            GetName(name) => write!(f, "GetName {}", name),
+            GetStatus(status) => write_tokens!(f, "GetStatus", &**status),
+            GetPropellantMass(mass) => write_tokens!(f, "GetPropellantMass", mass),
+            GetAngularVel(a_vel) => write_tokens!(f, "GetAngularVel", a_vel),
+            GetAirspeedVector(valid, airspeed) => {
+                write_tokens!(f, "GetAirspeedVector", valid, airspeed)
+            }
+            GetThrustVector(thrusting, thrust_vec) => {
+                write_tokens!(f, "GetThrustVector", thrusting, thrust_vec)
+            }
+            GetTotalPropellantMass(mass) => write_tokens!(f, "GetTotalPropellantMass", mass),
+            GetEmptyMass(mass) => write_tokens!(f, "GetEmptyMass", mass),
+            GetThrusterGroupLevelByType(level) => {
+                write_tokens!(f, "GetThrusterGroupLevelByType", level)
+            }
+            GetThrusterGroupLevel(level) => write_tokens!(f, "GetThrusterGroupLevel", level),
+            GetThrusterMax0(maxth) => write_tokens!(f, "GetThrusterMax0", maxth),
+            GetThrusterIsp0(isp) => write_tokens!(f, "GetThrusterIsp0", isp),
+            GroundContact(contact) => write_tokens!(f, "GroundContact", contact),
+            GetSurfaceRef(href) => write_tokens!(f, "GetSurfaceRef", href),
+            GetSurfaceElevation(elev) => write_tokens!(f, "GetSurfaceElevation", elev),
+            GetAltitude(alt) => write_tokens!(f, "GetAltitude", alt),
+            GetAtmTemperature(temp) => write_tokens!(f, "GetAtmTemperature", temp),
+            GetAtmDensity(density) => write_tokens!(f, "GetAtmDensity", density),
+            GetAtmPressure(pressure) => write_tokens!(f, "GetAtmPressure", pressure),
+            GetRelativeVel(rel_vel) => write_tokens!(f, "GetRelativeVel", rel_vel),
+            GetEquPos(lng, lat, rad) => write_tokens!(f, "GetEquPos", lng, lat, rad),
+            GetHorizonAirspeedVector(valid, airspeed) => {
+                write_tokens!(f, "GetHorizonAirspeedVector", valid, airspeed)
+            }
+            GetRotationMatrix(r) => write_tokens!(f, "GetRotationMatrix", r),
+            GetRotDrag(rd) => write_tokens!(f, "GetRotDrag", rd),
+            GetElements(valid, el, prm) => write_tokens!(f, "GetElements", valid, el, prm),
+            SetElements(valid, prm) => write_tokens!(f, "SetElements", valid, prm),
+        }
+    }
+}
+fn parse_return(t: &mut Tokens) -> Result<VesselReturn, String> {
+    use VesselReturn::*;
+    let ret = match t.next_str()? {
+        "Local2Rel" => Local2Rel(t.next()?),
+        "Global2Local" => Global2Local(t.next()?),
+        "Local2Global" => Local2Global(t.next()?),
+        "GetName" => GetName(t.rest()),
+        "GetStatus" => GetStatus(Box::new(t.next()?)),
+        "GetPropellantMass" => GetPropellantMass(t.next()?),
+        "GetAngularVel" => GetAngularVel(t.next()?),
+        "GetAirspeedVector" => GetAirspeedVector(t.next()?, t.next()?),
+        "GetThrustVector" => GetThrustVector(t.next()?, t.next()?),
+        "GetTotalPropellantMass" => GetTotalPropellantMass(t.next()?),
+        "GetEmptyMass" => GetEmptyMass(t.next()?),
+        "GetThrusterGroupLevelByType" => GetThrusterGroupLevelByType(t.next()?),
+        "GetThrusterGroupLevel" => GetThrusterGroupLevel(t.next()?),
+        "GetThrusterMax0" => GetThrusterMax0(t.next()?),
+        "GetThrusterIsp0" => GetThrusterIsp0(t.next()?),
+        "GroundContact" => GroundContact(t.next()?),
+        "GetSurfaceRef" => GetSurfaceRef(t.next()?),
+        "GetSurfaceElevation" => GetSurfaceElevation(t.next()?),
+        "GetAltitude" => GetAltitude(t.next()?),
+        "GetAtmTemperature" => GetAtmTemperature(t.next()?),
+        "GetAtmDensity" => GetAtmDensity(t.next()?),
+        "GetAtmPressure" => GetAtmPressure(t.next()?),
+        "GetRelativeVel" => GetRelativeVel(t.next()?),
+        "GetEquPos" => GetEquPos(t.next()?, t.next()?, t.next()?),
+        "GetHorizonAirspeedVector" => GetHorizonAirspeedVector(t.next()?, t.next()?),
+        "GetRotationMatrix" => GetRotationMatrix(t.next()?),
+        "GetRotDrag" => GetRotDrag(t.next()?),
+        "GetElements" => GetElements(t.next()?, t.next()?, t.next()?),
+        "SetElements" => SetElements(t.next()?, t.next()?),
+        other => return Err(format!("unknown getter `{}`", other)),
+    };
+    Ok(ret)
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TraceEvent::SetClassCaps => write!(f, "caps"),
+            TraceEvent::ConfigItem { item, value } => write!(f, "cfg {} {}", item, value),
+            TraceEvent::PreStep { sim_t, sim_dt, mjd } => write_tokens!(f, "pre", sim_t, sim_dt, mjd),
+            TraceEvent::PostStep { sim_t, sim_dt, mjd } => {
+                write_tokens!(f, "post", sim_t, sim_dt, mjd)
+            }
+            TraceEvent::Key { key, down, held } => {
+                write_tokens!(f, "key", key, down)?;
+                held.iter().try_for_each(|key| key.write(f))
+            }
+            TraceEvent::LoadParam(line) => write!(f, "param {}", line),
+            TraceEvent::SaveState => write!(f, "save"),
+            TraceEvent::Call(call) => write!(f, "call {}", call),
+            TraceEvent::Return(ret) => write!(f, "ret {}", ret),
+        }
+    }
+}
+fn parse_event(line: &str) -> Result<TraceEvent, String> {
+    let mut t = Tokens(line);
+    let event = match t.next_str()? {
+        "caps" => TraceEvent::SetClassCaps,
+        "cfg" => TraceEvent::ConfigItem {
+            item: t.next_str()?.to_owned(),
+            value: t.rest(),
+        },
+        "pre" => TraceEvent::PreStep {
+            sim_t: t.next()?,
+            sim_dt: t.next()?,
+            mjd: t.next()?,
+        },
+        "post" => TraceEvent::PostStep {
+            sim_t: t.next()?,
+            sim_dt: t.next()?,
+            mjd: t.next()?,
+        },
+        "key" => {
+            let key = t.next()?;
+            let down = t.next()?;
+            let mut held = Vec::new();
+            while !t.0.trim().is_empty() {
+                held.push(t.next()?);
+            }
+            TraceEvent::Key { key, down, held }
+        }
+        "param" => TraceEvent::LoadParam(t.rest()),
+        "save" => TraceEvent::SaveState,
+        "call" => TraceEvent::Call(parse_call(&mut t)?),
+        "ret" => TraceEvent::Return(parse_return(&mut t)?),
+        other => return Err(format!("unknown event `{}`", other)),
+    };
+    t.finish()?;
+    Ok(event)
+}
+
+static RECORDER: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+/// Starts writing trace events to `out`
+///
+/// Any previously active recording is stopped. Wrap `out` in a [BufWriter](std::io::BufWriter) if the
+/// addon makes many calls per frame; the buffer is flushed by [stop_recording].
+pub fn start_recording(out: impl Write + Send + 'static) {
+    stop_recording();
+    *RECORDER.lock().unwrap() = Some(Box::new(out));
+}
+/// Stops the active recording, if any
+pub fn stop_recording() {
+    if let Some(mut out) = RECORDER.lock().unwrap().take() {
+        let _ = out.flush();
+    }
+}
+/// Returns `true` while a recording is active
+pub fn is_recording() -> bool {
+    RECORDER.lock().unwrap().is_some()
+}
+/// Writes the event built by `event` if a recording is active
+///
+/// The recording is stopped if the event cannot be written.
+pub(crate) fn record(event: impl FnOnce() -> TraceEvent) {
+    let mut recorder = RECORDER.lock().unwrap();
+    if let Some(out) = recorder.as_mut() {
+        if let Err(e) = writeln!(out, "{}", event()) {
+            log::warn!("Trace recording stopped: {}", e);
+            *recorder = None;
+        }
+    }
+}
+
+/// Writes a [TraceEvent::Key] for `key` with the keys held down in `kstate` if a recording is active
+pub(crate) fn record_key(key: Key, down: bool, kstate: &KeyStates) {
+    record(|| TraceEvent::Key {
+        key,
+        down,
+        held: (0..=255).map(Key::from).filter(|&k| kstate.is_down(k)).collect(),
+    });
+}
+
+/// Maps the handles handed out by Orbiter to their creation order
+#[derive(Debug, Default)]
+struct HandleMap {
+    thrusters: Vec<usize>,
+    propellants: Vec<usize>,
+    thruster_groups: Vec<usize>,
+}
+fn handle_id(handles: &[usize], raw: usize) -> usize {
+    handles.iter().position(|&h| h == raw).map_or(0, |idx| idx + 1)
+}
+
+/// [VesselApi] wrapper that records every call and returned value to the active trace
+///
+/// The [SDKVessel](crate::SDKVessel) handed to the addon is a `RecordingVessel` around the
+/// [VesselContext](crate::VesselContext), so everything an addon does through it ends up in the trace.
+/// The wrapper has the same methods as [VesselContext](crate::VesselContext) and forwards them as-is
+/// while no recording is active.
+///
+/// Handles created through the wrapper are recorded by their creation order instead of the addresses
+/// handed out by Orbiter, so the recorded calls can be compared across sessions.
+#[derive(Debug)]
+pub struct RecordingVessel<V: VesselApi> {
+    inner: V,
+    handles: RefCell<HandleMap>,
+}
+impl<V: VesselApi> RecordingVessel<V> {
+    /// Wraps `inner`
+    pub fn new(inner: V) -> Self {
+        Self {
+            inner,
+            handles: RefCell::default(),
+        }
+    }
+    /// Returns the wrapped vessel
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+    fn thruster(&self, th: ThrusterHandle) -> ThrusterHandle {
+        THRUSTER_HANDLE(handle_id(&self.handles.borrow().thrusters, th.0))
+    }
+    fn propellant(&self, ph: PropellantHandle) -> PropellantHandle {
+        PROPELLANT_HANDLE(handle_id(&self.handles.borrow().propellants, ph.0))
+    }
+    fn thruster_group(&self, th: ThrustGroupHandle) -> ThrustGroupHandle {
+        THGROUP_HANDLE(handle_id(&self.handles.borrow().thruster_groups, th.0))
+    }
+}
+
+fn record_call(call: impl FnOnce() -> VesselCall) {
+    record(|| TraceEvent::Call(call()))
+}
+fn record_return(ret: impl FnOnce() -> VesselReturn) {
+    record(|| TraceEvent::Return(ret()))
+}
+
+#[allow(non_snake_case)]
+impl<V: VesselApi> RecordingVessel<V> {
+    /// Performs a transformation from local vessel coordinates to the ecliptic frame centered at the vessel's reference body
+    pub fn Local2Rel(&self, local: &Vector3, rel: &mut Vector3) {
+        record_call(|| VesselCall::Local2Rel(*local));
+        self.inner.Local2Rel(local, rel);
+        record_return(|| VesselReturn::Local2Rel(*rel));
+    }
+    /// Performs a transformation from global (ecliptic) to local vessel coordinates
+    pub fn Global2Local(&self, global: &Vector3, local: &mut Vector3) {
+        record_call(|| VesselCall::Global2Local(*global));
+        self.inner.Global2Local(global, local);
+        record_return(|| VesselReturn::Global2Local(*local));
+    }
+    /// Performs a transformation from local vessel to global (ecliptic) coordinates
+    pub fn Local2Global(&self, local: &Vector3, global: &mut Vector3) {
+        record_call(|| VesselCall::Local2Global(*local));
+        self.inner.Local2Global(local, global);
+        record_return(|| VesselReturn::Local2Global(*global));
+    }
+    /// Set the vessel's mean radius
+    pub fn SetSize(&self, size: f64) {
+        record_call(|| VesselCall::SetSize(size));
+        self.inner.SetSize(size)
+    }
+    /// Set the vessel's mass-normalised principal moments of inertia (PMI)
+    pub fn SetPMI(&self, pmi: &Vector3) {
+        record_call(|| VesselCall::SetPMI(*pmi));
+        self.inner.SetPMI(pmi)
+    }
+    /// Set the vessel's empty mass (excluding propellants)
+    pub fn SetEmptyMass(&self, empty_mass: f64) {
+        record_call(|| VesselCall::SetEmptyMass(empty_mass));
+        self.inner.SetEmptyMass(empty_mass)
+    }
+    /// Set the camera position for internal (cockpit) view.
+    pub fn SetCameraOffset(&self, camera_offset: &Vector3) {
+        record_call(|| VesselCall::SetCameraOffset(*camera_offset));
+        self.inner.SetCameraOffset(camera_offset)
+    }
+    /// Defines the three points defining the vessel's ground contact plane
+    pub fn SetTouchdownPoints(&self, pt1: &Vector3, pt2: &Vector3, pt3: &Vector3) {
+        record_call(|| {
+            VesselCall::SetTouchdownPoints(*pt1, *pt2, *pt3)
+        });
+        self.inner.SetTouchdownPoints(pt1, pt2, pt3)
+    }
+    /// Reset the force direction of a thruster
+    pub fn SetThrusterDir(&self, th: ThrusterHandle, dir: &Vector3) {
+        record_call(|| VesselCall::SetThrusterDir(self.thruster(th), *dir));
+        self.inner.SetThrusterDir(th, dir)
+    }
+    /// Set thrust level for a thruster
+    pub fn SetThrusterLevel(&self, th: ThrusterHandle, level: f64) {
+        record_call(|| VesselCall::SetThrusterLevel(self.thruster(th), level));
+        self.inner.SetThrusterLevel(th, level)
+    }
+    /// Set the thrust level of a thruster for the current time step only
+    pub fn SetThrusterLevel_SingleStep(&self, th: ThrusterHandle, level: f64) {
+        record_call(|| VesselCall::SetThrusterLevelSingleStep(self.thruster(th), level));
+        self.inner.SetThrusterLevel_SingleStep(th, level)
+    }
+    /// Set the thrust level for all thrusters of a default thruster group type
+    pub fn SetThrusterGroupLevelByType(&self, thgroup_type: ThrusterGroupType, level: f64) {
+        record_call(|| VesselCall::SetThrusterGroupLevelByType(thgroup_type, level));
+        self.inner.SetThrusterGroupLevelByType(thgroup_type, level)
+    }
+    /// Load a mesh definition for the vessel from a file
+    pub fn AddMesh(&self, mesh_name: String) {
+        record_call(|| VesselCall::AddMesh(mesh_name.clone()));
+        self.inner.AddMesh(mesh_name)
+    }
+    /// Load a mesh definition for the vessel from a file displaced by offset `ofs`
+    pub fn AddMeshWithOffset(&self, mesh_name: String, ofs: &Vector3) {
+        record_call(|| VesselCall::AddMeshWithOffset(mesh_name.clone(), *ofs));
+        self.inner.AddMeshWithOffset(mesh_name, ofs)
+    }
+    /// Add an exhaust render definition for a thruster
+    pub fn AddExhaust(&self, th: ThrusterHandle, lscale: f64, wscale: f64) -> usize {
+        record_call(|| VesselCall::AddExhaust(self.thruster(th), lscale, wscale));
+        self.inner.AddExhaust(th, lscale, wscale)
+    }
+    /// Remove all mesh definitions for the vessel
+    pub fn ClearMeshes(&self) {
+        record_call(|| VesselCall::ClearMeshes);
+        self.inner.ClearMeshes()
+    }
+    /// Create a new propellant resource ("fuel tank")
+    pub fn CreatePropellantResource(&self, mass: f64) -> PropellantHandle {
+        record_call(|| VesselCall::CreatePropellantResource(mass));
+        let ph = self.inner.CreatePropellantResource(mass);
+        self.handles.borrow_mut().propellants.push(ph.0);
+        ph
+    }
+    /// Add a logical thruster definition for the vessel
+    pub fn CreateThruster(
+        &self,
+        pos: &Vector3,
+        dir: &Vector3,
+        maxth0: f64,
+        ph: PropellantHandle,
+        isp: f64,
+    ) -> ThrusterHandle {
+        record_call(|| VesselCall::CreateThruster {
+            pos: *pos,
+            dir: *dir,
+            maxth0,
+            ph: self.propellant(ph),
+            isp,
+        });
+        let th = self.inner.CreateThruster(pos, dir, maxth0, ph, isp);
+        self.handles.borrow_mut().thrusters.push(th.0);
+        th
+    }
+    /// Combine thrusters into a logical thruster group
+    pub fn CreateThrusterGroup(
+        &self,
+        thrusters: &[ThrusterHandle],
+        thgroup_type: ThrusterGroupType,
+    ) -> ThrustGroupHandle {
+        record_call(|| {
+            let thrusters = thrusters.iter().map(|&th| self.thruster(th)).collect();
+            VesselCall::CreateThrusterGroup(thrusters, thgroup_type)
+        });
+        let th = self.inner.CreateThrusterGroup(thrusters, thgroup_type);
+        self.handles.borrow_mut().thruster_groups.push(th.0);
+        th
+    }
+    /// Returns the vessel's name
+    pub fn GetName(&self) -> &str {
+        record_call(|| VesselCall::GetName);
+        let name = self.inner.GetName();
+        record_return(|| VesselReturn::GetName(name.to_owned()));
+        name
+    }
+    /// Returns the vessel's current status parameters in a [VesselStatus] structure
+    pub fn GetStatus(&self, status: &mut VesselStatus) {
+        record_call(|| VesselCall::GetStatus);
+        self.inner.GetStatus(status);
+        record_return(|| VesselReturn::GetStatus(Box::new(*status)));
+    }
+    /// Returns the current mass of a propellant resource specified by `ph`
+    pub fn GetPropellantMass(&self, ph: PropellantHandle) -> f64 {
+        record_call(|| VesselCall::GetPropellantMass(self.propellant(ph)));
+        let mass = self.inner.GetPropellantMass(ph);
+        record_return(|| VesselReturn::GetPropellantMass(mass));
+        mass
+    }
+    /// Get angular velocity (in rad/s) of the spacecraft around its principal axes and store it in `a_vel`
+    pub fn GetAngularVel(&self, a_vel: &mut Vector3) {
+        record_call(|| VesselCall::GetAngularVel);
+        self.inner.GetAngularVel(a_vel);
+        record_return(|| VesselReturn::GetAngularVel(*a_vel));
+    }
+    /// Returns the vessel's true "airspeed" vector in the requested frame of reference
+    pub fn GetAirspeedVector(&self, ref_frame: ReferenceFrame, airspeed: &mut Vector3) -> bool {
+        record_call(|| VesselCall::GetAirspeedVector(ref_frame));
+        let valid = self.inner.GetAirspeedVector(ref_frame, airspeed);
+        record_return(|| VesselReturn::GetAirspeedVector(valid, *airspeed));
+        valid
+    }
+    /// Returns thrust force vector in local vessel coordinates
+    pub fn GetThrustVector(&self, thrust_vec: &mut Vector3) -> bool {
+        record_call(|| VesselCall::GetThrustVector);
+        let thrusting = self.inner.GetThrustVector(thrust_vec);
+        record_return(|| VesselReturn::GetThrustVector(thrusting, *thrust_vec));
+        thrusting
+    }
+    /// Returns the vessel's current total propellant mass
+    pub fn GetTotalPropellantMass(&self) -> f64 {
+        record_call(|| VesselCall::GetTotalPropellantMass);
+        let mass = self.inner.GetTotalPropellantMass();
+        record_return(|| VesselReturn::GetTotalPropellantMass(mass));
+        mass
+    }
+    /// Returns the vessel's empty mass, excluding propellants
+    pub fn GetEmptyMass(&self) -> f64 {
+        record_call(|| VesselCall::GetEmptyMass);
+        let mass = self.inner.GetEmptyMass();
+        record_return(|| VesselReturn::GetEmptyMass(mass));
+        mass
+    }
+    /// Returns the mean thrust level for a default thruster group type
+    pub fn GetThrusterGroupLevelByType(&self, thgroup_type: ThrusterGroupType) -> f64 {
+        record_call(|| VesselCall::GetThrusterGroupLevelByType(thgroup_type));
+        let level = self.inner.GetThrusterGroupLevelByType(thgroup_type);
+        record_return(|| VesselReturn::GetThrusterGroupLevelByType(level));
+        level
+    }
+    /// Returns the mean thrust level for a default thruster group specified by `th`
+    pub fn GetThrusterGroupLevel(&self, th: ThrustGroupHandle) -> f64 {
+        record_call(|| VesselCall::GetThrusterGroupLevel(self.thruster_group(th)));
+        let level = self.inner.GetThrusterGroupLevel(th);
+        record_return(|| VesselReturn::GetThrusterGroupLevel(level));
+        level
+    }
+    /// Returns the maximum vacuum thrust rating of a thruster
+    pub fn GetThrusterMax0(&self, th: ThrusterHandle) -> f64 {
+        record_call(|| VesselCall::GetThrusterMax0(self.thruster(th)));
+        let maxth = self.inner.GetThrusterMax0(th);
+        record_return(|| VesselReturn::GetThrusterMax0(maxth));
+        maxth
+    }
+    /// Returns the vacuum fuel-specific impulse (ISP) rating of a thruster
+    pub fn GetThrusterIsp0(&self, th: ThrusterHandle) -> f64 {
+        record_call(|| VesselCall::GetThrusterIsp0(self.thruster(th)));
+        let isp = self.inner.GetThrusterIsp0(th);
+        record_return(|| VesselReturn::GetThrusterIsp0(isp));
+        isp
+    }
+    /// Returns a flag indicating contact with a planetary surface
+    pub fn GroundContact(&self) -> bool {
+        record_call(|| VesselCall::GroundContact);
+        let contact = self.inner.GroundContact();
+        record_return(|| VesselReturn::GroundContact(contact));
+        contact
+    }
+    /// Returns a handle to the surface reference object (planet or moon)
+    pub fn GetSurfaceRef(&self) -> OBJHANDLE {
+        record_call(|| VesselCall::GetSurfaceRef);
+        let href = self.inner.GetSurfaceRef();
+        record_return(|| VesselReturn::GetSurfaceRef(href));
+        href
+    }
+    /// Returns the elevation of the surface at the vessel's current longitude/latitude above the reference radius
+    pub fn GetSurfaceElevation(&self) -> f64 {
+        record_call(|| VesselCall::GetSurfaceElevation);
+        let elev = self.inner.GetSurfaceElevation();
+        record_return(|| VesselReturn::GetSurfaceElevation(elev));
+        elev
+    }
+    /// Returns altitude above mean ellipsoid
+    pub fn GetAltitude(&self) -> f64 {
+        record_call(|| VesselCall::GetAltitude);
+        let alt = self.inner.GetAltitude();
+        record_return(|| VesselReturn::GetAltitude(alt));
+        alt
+    }
+    /// Returns the atmospheric temperature at the vessel's position, or 0 outside an atmosphere
+    pub fn GetAtmTemperature(&self) -> f64 {
+        record_call(|| VesselCall::GetAtmTemperature);
+        let temp = self.inner.GetAtmTemperature();
+        record_return(|| VesselReturn::GetAtmTemperature(temp));
+        temp
+    }
+    /// Returns the atmospheric density at the vessel's position, or 0 outside an atmosphere
+    pub fn GetAtmDensity(&self) -> f64 {
+        record_call(|| VesselCall::GetAtmDensity);
+        let density = self.inner.GetAtmDensity();
+        record_return(|| VesselReturn::GetAtmDensity(density));
+        density
+    }
+    /// Returns the static atmospheric pressure at the vessel's position, or 0 outside an atmosphere
+    pub fn GetAtmPressure(&self) -> f64 {
+        record_call(|| VesselCall::GetAtmPressure);
+        let pressure = self.inner.GetAtmPressure();
+        record_return(|| VesselReturn::GetAtmPressure(pressure));
+        pressure
+    }
+    /// Returns the vessel's current velocity relative to another object in the ecliptic frame
+    pub fn GetRelativeVel(&self, href: OBJHANDLE, rel_vel: &mut Vector3) {
+        record_call(|| VesselCall::GetRelativeVel(href));
+        self.inner.GetRelativeVel(href, rel_vel);
+        record_return(|| VesselReturn::GetRelativeVel(*rel_vel));
+    }
+    /// Returns the vessel's current longitude, latitude and distance from the centre of its reference body
+    pub fn GetEquPos(&self, longitude: &mut f64, latitude: &mut f64, radius: &mut f64) {
+        record_call(|| VesselCall::GetEquPos);
+        self.inner.GetEquPos(longitude, latitude, radius);
+        record_return(|| VesselReturn::GetEquPos(*longitude, *latitude, *radius));
+    }
+    /// Returns the vessel's airspeed vector in the local horizon frame (x east, y up, z north)
+    pub fn GetHorizonAirspeedVector(&self, airspeed: &mut Vector3) -> bool {
+        record_call(|| VesselCall::GetHorizonAirspeedVector);
+        let valid = self.inner.GetHorizonAirspeedVector(airspeed);
+        record_return(|| VesselReturn::GetHorizonAirspeedVector(valid, *airspeed));
+        valid
+    }
+    /// Returns the vessel's current rotation matrix for transforming from local vessel coordinates to the global frame
+    pub fn GetRotationMatrix(&self, r: &mut Matrix3) {
+        record_call(|| VesselCall::GetRotationMatrix);
+        self.inner.GetRotationMatrix(r);
+        record_return(|| VesselReturn::GetRotationMatrix(*r));
+    }
+    /// Returns the vessel's rotation drag coefficients for rotation around its principal axes
+    pub fn GetRotDrag(&self, rd: &mut Vector3) {
+        record_call(|| VesselCall::GetRotDrag);
+        self.inner.GetRotDrag(rd);
+        record_return(|| VesselReturn::GetRotDrag(*rd));
+    }
+    /// Returns the osculating orbital elements of the vessel relative to the body `href`
+    ///
+    /// `L` in `el` is the mean longitude at `mjd_ref`, or at the current simulation time if `mjd_ref` is 0.
+    /// `frame` is [FRAME_ECL](crate::FRAME_ECL) or [FRAME_EQU](crate::FRAME_EQU).
+    pub fn GetElements(
+        &self,
+        href: OBJHANDLE,
+        el: &mut Elements,
+        prm: &mut OrbitParam,
+        mjd_ref: f64,
+        frame: i32,
+    ) -> bool {
+        record_call(|| VesselCall::GetElements(href, mjd_ref, frame));
+        let valid = self.inner.GetElements(href, el, prm, mjd_ref, frame);
+        record_return(|| VesselReturn::GetElements(valid, *el, *prm));
+        valid
+    }
+    /// Moves the vessel onto the orbit around the body `href` described by `el`
+    pub fn SetElements(
+        &self,
+        href: OBJHANDLE,
+        el: &Elements,
+        prm: &mut OrbitParam,
+        mjd_ref: f64,
+        frame: i32,
+    ) -> bool {
+        record_call(|| VesselCall::SetElements(href, *el, mjd_ref, frame));
+        let valid = self.inner.SetElements(href, el, prm, mjd_ref, frame);
+        record_return(|| VesselReturn::SetElements(valid, *prm));
+        valid
+    }
+}
+
+/// Result of replaying a trace with `replay`
+#[derive(Debug, Default, PartialEq)]
+pub struct ReplayReport {
+    /// Calls recorded in the trace
+    pub expected: Vec<VesselCall>,
+    /// Calls made by the addon during the replay
+    pub actual: Vec<VesselCall>,
+}
+impl ReplayReport {
+    /// Returns `true` if the addon made exactly the recorded calls
+    pub fn is_match(&self) -> bool {
+        self.expected == self.actual
+    }
+    /// Returns the index of the first call that differs from the trace
+    pub fn first_mismatch(&self) -> Option<usize> {
+        let len = self.expected.len().max(self.actual.len());
+        (0..len).find(|&idx| self.expected.get(idx) != self.actual.get(idx))
+    }
+}
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let idx = match self.first_mismatch() {
+            Some(idx) => idx,
+            None => return write!(f, "all {} calls match the trace", self.expected.len()),
+        };
+        writeln!(f, "calls differ from the trace at #{}:", idx)?;
+        for i in idx.saturating_sub(2)..idx {
+            writeln!(f, "  {}", self.expected[i])?;
+        }
+        let len = self.expected.len().max(self.actual.len());
+        for i in idx..len.min(idx + 3) {
+            match (self.expected.get(i), self.actual.get(i)) {
+                (Some(expected), Some(actual)) if expected == actual => {
+                    writeln!(f, "  {}", expected)?
+                }
+                (expected, actual) => {
+                    if let Some(expected) = expected {
+                        writeln!(f, "- {}", expected)?;
+                    }
+                    if let Some(actual) = actual {
+                        writeln!(f, "+ {}", actual)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Feeds the callbacks recorded in `trace` into the addon created by `init` and collects the calls it makes
+///
+/// Getters called by the addon return the values recorded during the same callback, falling back to the
+/// [MockState](crate::mock::MockState) if the addon asks for something that was not recorded.
+/// Only available with the in-memory backend.
+///
+/// ```
+/// use orbiter_rs::trace::{replay, Trace};
+/// use orbiter_rs::{FileHandle, OrbiterVessel, SDKVessel, ThrusterHandle, V};
+///
+/// struct Hopper {
+///     ctx: SDKVessel,
+///     th_main: ThrusterHandle,
+/// }
+/// impl OrbiterVessel for Hopper {
+///     fn set_class_caps(&mut self, cfg: &FileHandle) {
+///         self.ctx.SetSize(cfg.read_f64("Size"));
+///         let ph = self.ctx.CreatePropellantResource(100.0);
+///         self.th_main = self.ctx.CreateThruster(&V!(0., 0., 0.), &V!(0., 1., 0.), 500.0, ph, 3000.0);
+///     }
+///     fn on_pre_step(&mut self, _sim_t: f64, _sim_dt: f64, _mjd: f64) {
+///         if self.ctx.GetAltitude() < 1000.0 {
+///             self.ctx.SetThrusterLevel(self.th_main, 1.0);
+///         }
+///     }
+/// }
+///
+/// let trace: Trace = "
+/// caps
+/// cfg Size 2.5
+/// call SetSize 2.5
+/// call CreatePropellantResource 100.0
+/// call CreateThruster 0.0 0.0 0.0 0.0 1.0 0.0 500.0 1 3000.0
+/// pre 0.0 0.1 51544.5
+/// call GetAltitude
+/// ret GetAltitude 900.0
+/// call SetThrusterLevel 1 1.0
+/// ".parse().unwrap();
+///
+/// let report = replay(trace, |ctx| Hopper { ctx, th_main: ThrusterHandle::default() });
+/// assert!(report.is_match(), "{}", report);
+/// ```
+#[cfg(not(orbiter_sdk))]
+pub fn replay<T, F>(trace: Trace, init: F) -> ReplayReport
+where
+    T: crate::OrbiterVessel,
+    F: FnOnce(crate::SDKVessel) -> T,
+{
+    use crate::mock::{KeyboardState, MockVessel};
+
+    // Split the trace at each callback. Events before the first callback were recorded while the addon was created.
+    let mut initial = Vec::new();
+    let mut callbacks: Vec<(TraceEvent, Vec<TraceEvent>)> = Vec::new();
+    for event in trace.events {
+        match event {
+            TraceEvent::ConfigItem { .. } | TraceEvent::Call(_) | TraceEvent::Return(_) => {
+                match callbacks.last_mut() {
+                    Some((_, body)) => body.push(event),
+                    None => initial.push(event),
+                }
+            }
+            _ => callbacks.push((event, Vec::new())),
+        }
+    }
+    let name = initial
+        .iter()
+        .chain(callbacks.iter().flat_map(|(_, body)| body.iter()))
+        .find_map(|event| match event {
+            TraceEvent::Return(VesselReturn::GetName(name)) => Some(name.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| "Replay".to_owned());
+
+    // Queues the recorded return values of a callback and returns the configuration items it read
+    let load = |mock: &MockVessel, body: Vec<TraceEvent>, expected: &mut Vec<VesselCall>| {
+        let mut config = String::new();
+        let mut state = mock.state_mut();
+        state.returns.clear();
+        for event in body {
+            match event {
+                TraceEvent::ConfigItem { item, value } => {
+                    config.push_str(&format!("{} = {}\n", item, value))
+                }
+                TraceEvent::Call(call) => expected.push(call),
+                // The name is fixed when the mock is created
+                TraceEvent::Return(VesselReturn::GetName(_)) => {}
+                TraceEvent::Return(ret) => state.returns.push_back(ret),
+                _ => unreachable!(),
+            }
+        }
+        config
+    };
+
+    let mock = MockVessel::new(&name);
+    let mut report = ReplayReport::default();
+    load(&mock, initial, &mut report.expected);
+    let mut vessel = init(mock.sdk_vessel());
+    report.actual.extend(mock.take_calls());
+
+    for (callback, body) in callbacks {
+        let config = load(&mock, body, &mut report.expected);
+        match callback {
+            TraceEvent::SetClassCaps => vessel.set_class_caps(&mock.config_file(&config)),
+            TraceEvent::PreStep { sim_t, sim_dt, mjd } => vessel.on_pre_step(sim_t, sim_dt, mjd),
+            TraceEvent::PostStep { sim_t, sim_dt, mjd } => vessel.on_post_step(sim_t, sim_dt, mjd),
+            TraceEvent::Key { key, down, held } => {
+                let mut keyboard = KeyboardState::new();
+                for key in held {
+                    keyboard.press(key);
+                }
+                mock.press_key(&mut vessel, key, down, &mut keyboard);
+            }
+            TraceEvent::LoadParam(line) => mock.load_scenario(&mut vessel, &[&line]),
+            TraceEvent::SaveState => {
+                mock.save_scenario(&mut vessel);
+            }
+            _ => unreachable!(),
+        }
+        report.actual.extend(mock.take_calls());
+    }
+    mock.state_mut().returns.clear();
+    report
+}
@@ -0,0 +1,149 @@
+//! Plain data types shared with the Orbiter SDK
+//!
+//! These have the same memory layout as their C++ counterparts but do not depend on the SDK,
+//! so they are available with any backend.
+use crate::ctype_wrapper;
+use crate::matrix::Matrix3;
+use crate::vector::Vector3;
+
+/// Type alias for [VECTOR3](VECTOR3)
+pub type VECTOR3 = Vector3;
+/// Type alias for [Matrix3]
+pub type MATRIX3 = Matrix3;
+
+ctype_wrapper!(THRUSTER_HANDLE, usize, ThrusterHandle);
+ctype_wrapper!(PROPELLANT_HANDLE, usize, PropellantHandle);
+ctype_wrapper!(THGROUP_HANDLE, usize, ThrustGroupHandle);
+ctype_wrapper!(OBJHANDLE, usize);
+ctype_wrapper!(DWORD, u32);
+
+pub type FILEHANDLE = crate::io::FileHandle;
+
+/// Binding for OrbiterSDK's `VESSELSTATUS` struct
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct VESSELSTATUS {
+    /// Position relative to rbody in ecliptic frame \[**m**\]
+    pub rpos: VECTOR3,
+
+    /// Velocity relative to rbody in ecliptic frame \[**m/s**\]
+    pub rvel: VECTOR3,
+
+    /// Rotation velocity about principal axes in ecliptic frame \[**rad/s**\]
+    pub vrot: VECTOR3,
+
+    /// Vessel orientation against ecliptic frame
+    pub arot: VECTOR3,
+
+    /// Fuel level. Between 0 and 1.
+    pub fuel: f64,
+
+    /// Main/retro engine setting. Between -1 and 1.
+    pub eng_main: f64,
+
+    /// Hover engine setting. Between 0 and 1.
+    pub eng_hovr: f64,
+
+    /// Handle of reference body
+    pub rbody: OBJHANDLE,
+
+    /// Handle of docking or landing target
+    pub base: OBJHANDLE,
+
+    /// Index of designated docking or landing port
+    pub port: i32,
+
+    /// Flight status indicator
+    /// 
+    /// - 0 = active (freeflight)
+    /// - 1 = inactive (landed)
+    pub status: i32,
+
+    /// Additional vector parameters
+    /// 
+    /// - `vdata[0]`: contains landing parameters 
+    /// 
+    ///    if `status` is equal to 1, `vdata[0]` contains the longitude, latitude, and heading of landed vessel
+    /// 
+    /// - `vdata[1]` - `vdata[9]`: not used
+    pub vdata: [VECTOR3; 10],
+
+    /// additional floating point parameters (not used)
+    pub fdata: [f64; 10],
+
+    /// Additional integer and bitflag parameters
+    ///
+    /// - `flag[0] & 1`:
+    ///   - 0: ingore eng_main and eng_hovr entries, do not change thruster settings
+    ///   - 1: set [ThrusterGroupType::Main](crate::ThrusterGroupType::Main) and [ThrusterGroupType::Retro](crate::ThrusterGroupType::Retro) thruster groups from `eng_main`, and [ThrusterGroupType::Hover](crate::ThrusterGroupType::Hover) from `eng_hovr`.
+    ///
+    /// - `flag[0] & 2`:
+    ///   - 0: ignore fuel level, do not change fuel levels
+    ///   - 1: set fuel level of first propellant resource from fuel
+    /// 
+    /// - `flag[1]` - `flag[9]`: not used
+    pub flag: [DWORD; 10],
+}
+/// Type alias for [VESSELSTATUS]
+pub type VesselStatus = VESSELSTATUS;
+
+/// Frame for [ELEMENTS]: ecliptic and equinox of J2000
+pub const FRAME_ECL: i32 = 0;
+/// Frame for [ELEMENTS]: equator of the reference body
+pub const FRAME_EQU: i32 = 1;
+
+/// Binding for OrbiterSDK's `ELEMENTS` struct
+///
+/// See [OrbitalElements](crate::orbit::OrbitalElements) for conversions to and from state vectors.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[allow(non_snake_case)]
+pub struct ELEMENTS {
+    /// Semi-major axis \[**m**\]
+    pub a: f64,
+    /// Eccentricity
+    pub e: f64,
+    /// Inclination \[**rad**\]
+    pub i: f64,
+    /// Longitude of ascending node \[**rad**\]
+    pub theta: f64,
+    /// Longitude of periapsis \[**rad**\]
+    pub omegab: f64,
+    /// Mean longitude at epoch \[**rad**\]
+    pub L: f64,
+}
+/// Type alias for [ELEMENTS]
+pub type Elements = ELEMENTS;
+
+/// Binding for OrbiterSDK's `ORBITPARAM` struct, secondary orbital parameters derived from [ELEMENTS]
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[allow(non_snake_case)]
+pub struct ORBITPARAM {
+    /// Semi-minor axis \[**m**\]
+    pub SMi: f64,
+    /// Periapsis distance \[**m**\]
+    pub PeD: f64,
+    /// Apoapsis distance \[**m**\]
+    pub ApD: f64,
+    /// Mean anomaly \[**rad**\]
+    pub MnA: f64,
+    /// True anomaly \[**rad**\]
+    pub TrA: f64,
+    /// Mean longitude \[**rad**\]
+    pub MnL: f64,
+    /// True longitude \[**rad**\]
+    pub TrL: f64,
+    /// Eccentric anomaly \[**rad**\]
+    pub EcA: f64,
+    /// Linear eccentricity \[**m**\]
+    pub Lec: f64,
+    /// Orbit period \[**s**\]
+    pub T: f64,
+    /// Time to next periapsis passage \[**s**\]
+    pub PeT: f64,
+    /// Time to next apoapsis passage \[**s**\]
+    pub ApT: f64,
+}
+/// Type alias for [ORBITPARAM]
+pub type OrbitParam = ORBITPARAM;
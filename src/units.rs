@@ -0,0 +1,266 @@
+//! Physical quantities with units
+//!
+//! The Orbiter SDK expects SI units throughout: masses in kg, forces in N, lengths in m and specific impulse
+//! as an effective exhaust velocity in m/s. The newtypes in this module hold their value in those units and
+//! can only be created through constructors that name the unit, so that a thrust in kN or an ISP in seconds
+//! cannot be passed where Orbiter expects N and m/s by accident.
+//!
+//! The [VesselUnits] extension trait provides typed versions of the mass, propellant and thruster methods of
+//! [VesselApi]:
+//!
+//! ```no_run
+//! use orbiter_rs::units::{Force, Mass, Time, VesselUnits};
+//! use orbiter_rs::{VesselApi, V};
+//!
+//! fn create_retro<V: VesselApi>(ctx: &V) {
+//!     let propellant = Mass::kilograms(560.64);
+//!     let thrust = Force::kilonewtons(39.14);
+//!     let isp = thrust * Time::seconds(40.5) / propellant;
+//!
+//!     let ph = ctx.create_propellant_resource(propellant);
+//!     ctx.create_thruster(&V!(0.0, 0.0, -0.75), &V!(0.0, 0.0, 1.0), thrust, ph, isp);
+//! }
+//! ```
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use crate::{PropellantHandle, ThrusterHandle, Vector3, VesselApi};
+
+/// Standard gravity used to convert specific impulse between seconds and m/s \[**m/s²**\]
+pub const G0: f64 = 9.80665;
+
+/// Defines a quantity stored in SI units together with its arithmetic and formatting
+macro_rules! quantity {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $symbol:literal, $si:ident, $in_si:ident
+        $(, $ctor:ident / $getter:ident = $factor:expr)*
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $name(f64);
+
+        impl $name {
+            #[doc = concat!("Creates a quantity from a value in ", $symbol)]
+            pub const fn $si(value: f64) -> Self {
+                Self(value)
+            }
+            #[doc = concat!("Returns the value in ", $symbol, ", as expected by the Orbiter SDK")]
+            pub const fn $in_si(self) -> f64 {
+                self.0
+            }
+            $(
+                #[doc = concat!("Creates a quantity from a value in ", stringify!($ctor))]
+                pub const fn $ctor(value: f64) -> Self {
+                    Self(value * $factor)
+                }
+                #[doc = concat!("Returns the value in ", stringify!($ctor))]
+                pub const fn $getter(self) -> f64 {
+                    self.0 / $factor
+                }
+            )*
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(self.0 + rhs.0)
+            }
+        }
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(self.0 - rhs.0)
+            }
+        }
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self::Output {
+                Self(-self.0)
+            }
+        }
+        impl Mul<f64> for $name {
+            type Output = Self;
+            fn mul(self, rhs: f64) -> Self::Output {
+                Self(self.0 * rhs)
+            }
+        }
+        impl Mul<$name> for f64 {
+            type Output = $name;
+            fn mul(self, rhs: $name) -> Self::Output {
+                $name(self * rhs.0)
+            }
+        }
+        impl Div<f64> for $name {
+            type Output = Self;
+            fn div(self, rhs: f64) -> Self::Output {
+                Self(self.0 / rhs)
+            }
+        }
+        /// The ratio of two quantities of the same kind is a plain number
+        impl Div for $name {
+            type Output = f64;
+            fn div(self, rhs: Self) -> Self::Output {
+                self.0 / rhs.0
+            }
+        }
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self(iter.map(|q| q.0).sum())
+            }
+        }
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)?;
+                write!(f, " {}", $symbol)
+            }
+        }
+    };
+}
+
+quantity!(
+    /// Mass \[**kg**\]
+    Mass, "kg", kilograms, in_kilograms,
+    grams / in_grams = 1e-3,
+    tonnes / in_tonnes = 1e3,
+    pounds / in_pounds = 0.453_592_37
+);
+quantity!(
+    /// Force \[**N**\]
+    Force, "N", newtons, in_newtons,
+    kilonewtons / in_kilonewtons = 1e3,
+    pounds_force / in_pounds_force = 4.448_221_615_260_5
+);
+quantity!(
+    /// Specific impulse, stored as effective exhaust velocity \[**m/s**\]
+    ///
+    /// Values given in seconds are converted with the standard gravity [G0].
+    SpecificImpulse, "m/s", meters_per_second, in_meters_per_second,
+    seconds / in_seconds = G0
+);
+quantity!(
+    /// Impulse \[**N s**\]
+    Impulse, "N s", newton_seconds, in_newton_seconds,
+    kilonewton_seconds / in_kilonewton_seconds = 1e3
+);
+quantity!(
+    /// Length \[**m**\]
+    Length, "m", meters, in_meters,
+    kilometers / in_kilometers = 1e3,
+    feet / in_feet = 0.3048
+);
+quantity!(
+    /// Time \[**s**\]
+    Time, "s", seconds, in_seconds,
+    minutes / in_minutes = 60.0,
+    hours / in_hours = 3600.0,
+    days / in_days = 86400.0
+);
+quantity!(
+    /// Angle \[**rad**\]
+    Angle, "rad", radians, in_radians,
+    degrees / in_degrees = std::f64::consts::PI / 180.0
+);
+
+/// Total impulse of a constant force acting over a duration
+impl Mul<Time> for Force {
+    type Output = Impulse;
+    fn mul(self, rhs: Time) -> Self::Output {
+        Impulse(self.0 * rhs.0)
+    }
+}
+/// Specific impulse from the total impulse delivered by a propellant mass
+impl Div<Mass> for Impulse {
+    type Output = SpecificImpulse;
+    fn div(self, rhs: Mass) -> Self::Output {
+        SpecificImpulse(self.0 / rhs.0)
+    }
+}
+/// Propellant mass consumed while delivering an impulse
+impl Div<SpecificImpulse> for Impulse {
+    type Output = Mass;
+    fn div(self, rhs: SpecificImpulse) -> Self::Output {
+        Mass(self.0 / rhs.0)
+    }
+}
+/// Total impulse delivered by a propellant mass
+impl Mul<SpecificImpulse> for Mass {
+    type Output = Impulse;
+    fn mul(self, rhs: SpecificImpulse) -> Self::Output {
+        Impulse(self.0 * rhs.0)
+    }
+}
+/// Duration over which a constant force delivers an impulse
+impl Div<Force> for Impulse {
+    type Output = Time;
+    fn div(self, rhs: Force) -> Self::Output {
+        Time(self.0 / rhs.0)
+    }
+}
+
+/// Unit-checked versions of the mass, propellant and thruster methods of [VesselApi]
+///
+/// This trait is implemented for every [VesselApi].
+pub trait VesselUnits: VesselApi {
+    /// Set the vessel's mean radius
+    fn set_size(&self, size: Length) {
+        self.SetSize(size.in_meters());
+    }
+    /// Set the vessel's empty mass (excluding propellants)
+    fn set_empty_mass(&self, empty_mass: Mass) {
+        self.SetEmptyMass(empty_mass.in_kilograms());
+    }
+    /// Create a new propellant resource ("fuel tank") with capacity `max_mass`
+    fn create_propellant_resource(&self, max_mass: Mass) -> PropellantHandle {
+        self.CreatePropellantResource(max_mass.in_kilograms())
+    }
+    /// Add a logical thruster definition for the vessel
+    ///
+    /// `pos` and `dir` are in local vessel coordinates, `dir` must be a unit vector.
+    fn create_thruster(
+        &self,
+        pos: &Vector3,
+        dir: &Vector3,
+        max_thrust: Force,
+        ph: PropellantHandle,
+        isp: SpecificImpulse,
+    ) -> ThrusterHandle {
+        self.CreateThruster(pos, dir, max_thrust.in_newtons(), ph, isp.in_meters_per_second())
+    }
+    /// Returns the current mass of a propellant resource
+    fn propellant_mass(&self, ph: PropellantHandle) -> Mass {
+        Mass::kilograms(self.GetPropellantMass(ph))
+    }
+    /// Returns the vessel's current total propellant mass
+    fn total_propellant_mass(&self) -> Mass {
+        Mass::kilograms(self.GetTotalPropellantMass())
+    }
+    /// Returns the vessel's empty mass (excluding propellants)
+    fn empty_mass(&self) -> Mass {
+        Mass::kilograms(self.GetEmptyMass())
+    }
+    /// Returns the maximum vacuum thrust rating of a thruster
+    fn thruster_max_thrust(&self, th: ThrusterHandle) -> Force {
+        Force::newtons(self.GetThrusterMax0(th))
+    }
+    /// Returns the vacuum fuel-specific impulse of a thruster
+    fn thruster_isp(&self, th: ThrusterHandle) -> SpecificImpulse {
+        SpecificImpulse::meters_per_second(self.GetThrusterIsp0(th))
+    }
+    /// Returns the vessel's altitude above the mean ellipsoid
+    fn altitude(&self) -> Length {
+        Length::meters(self.GetAltitude())
+    }
+}
+impl<V: VesselApi + ?Sized> VesselUnits for V {}
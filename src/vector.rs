@@ -1,5 +1,29 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
 use crate::V;
-#[derive(Debug, Default, PartialEq)]
+
+/// A 3-vector with the same memory layout as `VECTOR3` in the Orbiter SDK
+///
+/// `Vector3` is `Copy` and supports the usual arithmetic by value and by reference, so it can
+/// be used in `const` items and passed around freely:
+///
+/// ```
+/// use orbiter_rs::{Vector3, V};
+///
+/// const THRUSTER_POS: Vector3 = V!(0.0, 0.86, -0.5);
+///
+/// let mut pos = THRUSTER_POS;
+/// pos += 2.0 * V!(0.0, 0.0, 0.25);
+/// assert_eq!(pos[2], 0.0);
+/// assert!(pos.approx_eq(&V!(0.0, 0.86, 0.0), 1e-12));
+/// assert_eq!(format!("{:.2}", pos), "(0.00, 0.86, 0.00)");
+///
+/// let total: Vector3 = [THRUSTER_POS, -THRUSTER_POS].iter().sum();
+/// assert_eq!(<[f64; 3]>::from(total), [0.0; 3]);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 #[repr(C)]
 pub struct Vector3(pub [f64; 3]);
 
@@ -54,80 +78,194 @@ impl Vector3 {
     {
         self / self.length()
     }
+    /// Computes the component-wise product with `other`
+    pub fn component_mul(&self, other: &Vector3) -> Vector3
+    {
+        V!(self.x() * other.x(), self.y() * other.y(), self.z() * other.z())
+    }
+    /// Computes the distance between the points `self` and `other`
+    pub fn distance(&self, other: &Vector3) -> f64
+    {
+        (self - other).length()
+    }
+    /// Computes the angle between this vector and `other` \[**rad**\]
+    ///
+    /// The result is in `[0, pi]`.
+    pub fn angle(&self, other: &Vector3) -> f64
+    {
+        self.cross(other).length().atan2(self.dot(other))
+    }
+    /// Computes the projection of this vector onto the direction of `other`
+    pub fn project_onto(&self, other: &Vector3) -> Vector3
+    {
+        other * (self.dot(other) / other.dot(other))
+    }
+    /// Linear interpolation between `self` (`t = 0`) and `other` (`t = 1`)
+    pub fn lerp(&self, other: &Vector3, t: f64) -> Vector3
+    {
+        self + (other - self) * t
+    }
+    /// Returns `true` if every component differs from the one in `other` by at most `tol`
+    pub fn approx_eq(&self, other: &Vector3, tol: f64) -> bool
+    {
+        (0..3).all(|i| (self[i] - other[i]).abs() <= tol)
+    }
 }
 
 /// Implement negation operator for `Vector3`
-impl std::ops::Neg for Vector3 {
+impl Neg for Vector3 {
     type Output = Self;
     fn neg(self) -> Self::Output
     {
         V!(-self.x(), -self.y(), -self.z())
     }
 }
-/// Implement addition operator for `Vector3`
-impl std::ops::Add for Vector3 {
-    type Output = Self;
-    fn add(self, other: Self) -> Self::Output {
-        V!(self.x() + other.x(), self.y() + other.y(), self.z() + other.z())
+/// Implement negation operator for `&Vector3`
+impl Neg for &Vector3 {
+    type Output = Vector3;
+    fn neg(self) -> Self::Output
+    {
+        -*self
     }
 }
-/// Implement subtraction operator for `Vector3`
-impl std::ops::Sub for Vector3 {
-    type Output = Self;
-    fn sub(self, other: Self) -> Self::Output {
-        V!(self.x() - other.x(), self.y() - other.y(), self.z() - other.z())
+
+/// Implements a component-wise binary operator and its assigning variant for all combinations of
+/// `Vector3` and `&Vector3`
+macro_rules! vector_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl $trait for Vector3 {
+            type Output = Vector3;
+            fn $method(self, other: Vector3) -> Self::Output {
+                V!(self.x() $op other.x(), self.y() $op other.y(), self.z() $op other.z())
+            }
+        }
+        impl $trait<&Vector3> for Vector3 {
+            type Output = Vector3;
+            fn $method(self, other: &Vector3) -> Self::Output {
+                self $op *other
+            }
+        }
+        impl $trait<Vector3> for &Vector3 {
+            type Output = Vector3;
+            fn $method(self, other: Vector3) -> Self::Output {
+                *self $op other
+            }
+        }
+        impl $trait<&Vector3> for &Vector3 {
+            type Output = Vector3;
+            fn $method(self, other: &Vector3) -> Self::Output {
+                *self $op *other
+            }
+        }
+        impl $assign_trait for Vector3 {
+            fn $assign_method(&mut self, other: Vector3) {
+                *self = *self $op other;
+            }
+        }
+        impl $assign_trait<&Vector3> for Vector3 {
+            fn $assign_method(&mut self, other: &Vector3) {
+                *self = *self $op *other;
+            }
+        }
+    };
+}
+vector_op!(Add, add, AddAssign, add_assign, +);
+vector_op!(Sub, sub, SubAssign, sub_assign, -);
+
+/// Implements scalar multiplication or division and its assigning variant for `Vector3` and `&Vector3`
+macro_rules! scalar_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl $trait<f64> for Vector3 {
+            type Output = Vector3;
+            fn $method(self, rhs: f64) -> Self::Output {
+                V!(self.x() $op rhs, self.y() $op rhs, self.z() $op rhs)
+            }
+        }
+        impl $trait<f64> for &Vector3 {
+            type Output = Vector3;
+            fn $method(self, rhs: f64) -> Self::Output {
+                *self $op rhs
+            }
+        }
+        impl $assign_trait<f64> for Vector3 {
+            fn $assign_method(&mut self, rhs: f64) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+scalar_op!(Mul, mul, MulAssign, mul_assign, *);
+scalar_op!(Div, div, DivAssign, div_assign, /);
+
+/// Scalar multiplication for `Vector3` with the scalar on the left
+impl Mul<Vector3> for f64 {
+    type Output = Vector3;
+    fn mul(self, rhs: Vector3) -> Self::Output
+    {
+        rhs * self
     }
 }
-/// Implement addition operator for `Vector3`
-impl std::ops::Add<&Vector3> for Vector3 {
-    type Output = Self;
-    fn add(self, other: &Self) -> Self::Output {
-        V!(self.x() + other.x(), self.y() + other.y(), self.z() + other.z())
+/// Scalar multiplication for `&Vector3` with the scalar on the left
+impl Mul<&Vector3> for f64 {
+    type Output = Vector3;
+    fn mul(self, rhs: &Vector3) -> Self::Output
+    {
+        rhs * self
     }
 }
-/// Implement subtraction operator for `Vector3`
-impl std::ops::Sub<&Vector3> for Vector3 {
-    type Output = Self;
-    fn sub(self, other: &Self) -> Self::Output {
-        V!(self.x() - other.x(), self.y() - other.y(), self.z() - other.z())
+
+/// Access the coordinates of a `Vector3` by index (`0` for `x`, `1` for `y` and `2` for `z`)
+impl Index<usize> for Vector3 {
+    type Output = f64;
+    fn index(&self, index: usize) -> &Self::Output
+    {
+        &self.0[index]
     }
 }
-/// Implement subtraction operator for `Vector3`
-impl std::ops::Sub<Vector3> for &Vector3 {
-    type Output = Vector3;
-    fn sub(self, other: Vector3) -> Self::Output {
-        V!(self.x() - other.x(), self.y() - other.y(), self.z() - other.z())
+/// Modify the coordinates of a `Vector3` by index (`0` for `x`, `1` for `y` and `2` for `z`)
+impl IndexMut<usize> for Vector3 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output
+    {
+        &mut self.0[index]
     }
 }
-/// Scalar multiplication for `Vector3`
-impl std::ops::Mul<f64> for Vector3 {
-    type Output = Vector3;
-    fn mul(self, rhs: f64) -> Self::Output
+
+/// Formats the vector as `(x, y, z)`, applying the precision and width to each coordinate
+impl fmt::Display for Vector3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        V!(self.x()*rhs, self.y()*rhs, self.z()*rhs)
+        write!(f, "(")?;
+        fmt::Display::fmt(&self.x(), f)?;
+        write!(f, ", ")?;
+        fmt::Display::fmt(&self.y(), f)?;
+        write!(f, ", ")?;
+        fmt::Display::fmt(&self.z(), f)?;
+        write!(f, ")")
     }
 }
-/// Scalar multiplication for `&Vector3`
-impl std::ops::Mul<f64> for &Vector3 {
-    type Output = Vector3;
-    fn mul(self, rhs: f64) -> Self::Output
+
+impl From<[f64; 3]> for Vector3 {
+    fn from(v: [f64; 3]) -> Self
     {
-        V!(self.x()*rhs, self.y()*rhs, self.z()*rhs)
+        Self(v)
     }
 }
-/// Scalar division for `Vector3`
-impl std::ops::Div<f64> for Vector3 {
-    type Output = Vector3;
-    fn div(self, rhs: f64) -> Self::Output
+impl From<Vector3> for [f64; 3] {
+    fn from(v: Vector3) -> Self
     {
-        V!(self.x()/rhs, self.y()/rhs, self.z()/rhs)
+        v.0
     }
 }
-/// Scalar division for `&Vector3`
-impl std::ops::Div<f64> for &Vector3 {
-    type Output = Vector3;
-    fn div(self, rhs: f64) -> Self::Output
+
+impl Sum for Vector3 {
+    fn sum<I: Iterator<Item = Vector3>>(iter: I) -> Self
+    {
+        iter.fold(Vector3::default(), |acc, v| acc + v)
+    }
+}
+impl<'a> Sum<&'a Vector3> for Vector3 {
+    fn sum<I: Iterator<Item = &'a Vector3>>(iter: I) -> Self
     {
-        V!(self.x()/rhs, self.y()/rhs, self.z()/rhs)
+        iter.fold(Vector3::default(), |acc, v| acc + v)
     }
 }
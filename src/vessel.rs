@@ -21,3 +21,26 @@ pub trait OrbiterVessel {
     /// Use the FileHandle::write_scenario_*() methods here
     fn on_save_state(&mut self, _scn: &FileHandle){}
 }
+
+/// Forwarding impl so that boxed addons, such as the ones created by [init_vessel!](crate::init_vessel), can be used
+/// wherever an `OrbiterVessel` is expected
+impl<T: OrbiterVessel + ?Sized> OrbiterVessel for Box<T> {
+    fn set_class_caps(&mut self, cfg: &FileHandle) {
+        (**self).set_class_caps(cfg)
+    }
+    fn on_pre_step(&mut self, sim_t: f64, sim_dt: f64, mjd: f64) {
+        (**self).on_pre_step(sim_t, sim_dt, mjd)
+    }
+    fn on_post_step(&mut self, sim_t: f64, sim_dt: f64, mjd: f64) {
+        (**self).on_post_step(sim_t, sim_dt, mjd)
+    }
+    fn consume_buffered_key(&mut self, key: Key, down: bool, kstate: KeyStates) -> i32 {
+        (**self).consume_buffered_key(key, down, kstate)
+    }
+    fn on_load_param(&mut self, param_data: &str) -> bool {
+        (**self).on_load_param(param_data)
+    }
+    fn on_save_state(&mut self, scn: &FileHandle) {
+        (**self).on_save_state(scn)
+    }
+}
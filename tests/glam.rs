@@ -0,0 +1,46 @@
+//! Conversions between the SDK types and `glam`
+#![cfg(all(feature = "glam", not(orbiter_sdk)))]
+
+use glam::{DMat3, DQuat, DVec3};
+use orbiter_rs::{Matrix3, Quaternion, Vector3, V};
+
+fn matrix() -> Matrix3 {
+    Matrix3::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 10.0]])
+}
+
+#[test]
+fn vector_round_trips() {
+    let v = V!(1.0, -2.0, 3.5);
+    let g: DVec3 = v.into();
+    assert_eq!(g, DVec3::new(1.0, -2.0, 3.5));
+    assert_eq!(Vector3::from(g), v);
+}
+
+#[test]
+fn matrix_keeps_rows_and_columns() {
+    let m = matrix();
+    let g: DMat3 = m.into();
+    // `glam` indexes columns first
+    assert_eq!(g.col(1).x, 2.0);
+    assert_eq!(g.row(1).x, 4.0);
+    assert_eq!(Matrix3::from(g), m);
+
+    let v = V!(1.0, -2.0, 3.5);
+    let product: Vector3 = (g * DVec3::from(v)).into();
+    assert_eq!(product, m * v);
+}
+
+#[test]
+fn quaternion_round_trips() {
+    // `glam` stores x, y, z, w
+    let q = Quaternion::new(0.5, 0.1, -0.7, 0.3);
+    let g: DQuat = q.into();
+    assert_eq!(g.to_array(), [0.1, -0.7, 0.3, 0.5]);
+    assert_eq!(Quaternion::from(g), q);
+
+    // Both rotate vectors the same way
+    let q = q.unit();
+    let v = V!(1.0, -2.0, 3.5);
+    let rotated: Vector3 = (DQuat::from(q) * DVec3::from(v)).into();
+    assert!((rotated - q.rotate(&v)).length() < 1e-12);
+}
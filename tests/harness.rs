@@ -0,0 +1,75 @@
+//! Rigid-body dynamics and surface contact of the headless harness
+#![cfg(not(orbiter_sdk))]
+
+use orbiter_rs::harness::Harness;
+use orbiter_rs::{Body, FileHandle, OrbiterVessel, SDKVessel, Vector3, V};
+
+/// A vessel with a single thruster, always firing
+struct Probe {
+    ctx: SDKVessel,
+    pos: Vector3,
+    thrust: f64,
+}
+impl OrbiterVessel for Probe {
+    fn set_class_caps(&mut self, _cfg: &FileHandle) {
+        self.ctx.SetEmptyMass(100.0);
+        self.ctx.SetPMI(&V!(2.0, 2.0, 2.0));
+        self.ctx.SetTouchdownPoints(&V!(0.0, -1.0, 1.0), &V!(1.0, -1.0, -1.0), &V!(-1.0, -1.0, -1.0));
+        let ph = self.ctx.CreatePropellantResource(10.0);
+        let th = self.ctx.CreateThruster(&self.pos, &V!(0.0, 0.0, 1.0), self.thrust, ph, 1e12);
+        self.ctx.SetThrusterLevel(th, 1.0);
+    }
+}
+
+fn probe(pos: Vector3, thrust: f64) -> Harness<Probe> {
+    Harness::new("Probe", Body::MOON, |ctx| Probe { ctx, pos, thrust })
+}
+
+#[test]
+fn offset_thruster_produces_angular_acceleration() {
+    // 10 N pushing forward 1 m above the centre of mass, far from any gravity
+    let mut harness = probe(V!(0.0, 1.0, 0.0), 10.0);
+    harness.set_position(V!(1e15, 0.0, 0.0));
+    harness.run(1.0, 0.001);
+
+    // Torque F × r = (-10, 0, 0) N·m, i.e. pitching down, over a mass of 110 kg with a PMI of 2 m²
+    let alpha = -10.0 / 110.0 / 2.0;
+    let angular_vel = harness.state().angular_vel;
+    assert!((angular_vel.x() - alpha).abs() < 1e-6, "{:?}", angular_vel);
+    assert!(angular_vel.y().abs() < 1e-12 && angular_vel.z().abs() < 1e-12);
+
+    // The nose has turned down by α t² / 2
+    let nose = harness.state().rotation * V!(0.0, 0.0, 1.0);
+    assert!((nose.y() + (alpha / 2.0).abs().sin()).abs() < 1e-4, "{:?}", nose);
+    assert!((harness.state().vel.length() - 10.0 / 110.0).abs() < 1e-4);
+}
+
+#[test]
+fn centred_thruster_produces_no_rotation() {
+    let mut harness = probe(V!(0.0, 0.0, -1.0), 10.0);
+    harness.set_position(V!(1e15, 0.0, 0.0));
+    harness.run(1.0, 0.01);
+    assert_eq!(harness.state().angular_vel, V!(0.0, 0.0, 0.0));
+    assert!((harness.state().vel.z() - 10.0 / 110.0).abs() < 1e-9);
+}
+
+#[test]
+fn touchdown_stops_the_vessel() {
+    // Falling at 5 m/s, 10 m above the surface with the touchdown points 1 m below the centre
+    let mut harness = probe(V!(0.0, 0.0, 0.0), 0.0);
+    harness.set_position(V!(0.0, Body::MOON.radius + 10.0, 0.0));
+    harness.set_velocity(V!(0.0, -5.0, 0.0));
+    assert!(harness.run_until(10.0, 0.01, |h| h.touchdown().is_some()));
+
+    let g = Body::MOON.gm() / Body::MOON.radius.powi(2);
+    let touchdown = harness.touchdown().unwrap();
+    assert!((touchdown.speed - (25.0 + 2.0 * g * 9.0).sqrt()).abs() < 0.05, "{:?}", touchdown);
+    assert!(harness.mock().state().ground_contact);
+    assert_eq!(harness.mock().state().status.status, 1);
+
+    // The vessel rests on its touchdown points
+    harness.run(5.0, 0.01);
+    assert_eq!(harness.state().vel, V!(0.0, 0.0, 0.0));
+    assert_eq!(harness.state().angular_vel, V!(0.0, 0.0, 0.0));
+    assert!((harness.altitude() - 1.0).abs() < 1e-6, "{}", harness.altitude());
+}
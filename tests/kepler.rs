@@ -0,0 +1,106 @@
+//! Two-body propagation of open and near-parabolic orbits, and points the orbit never reaches
+use std::f64::consts::PI;
+
+use orbiter_rs::kepler::{propagate, time_to_radius, time_to_true_anomaly};
+use orbiter_rs::orbit::OrbitalElements;
+use orbiter_rs::{Body, Vector3, V};
+
+fn gm() -> f64 {
+    Body::EARTH.gm()
+}
+
+/// Specific orbital energy \[**J/kg**\]
+fn energy(pos: &Vector3, vel: &Vector3) -> f64 {
+    vel.dot(vel) / 2.0 - gm() / pos.length()
+}
+
+/// State 7000 km from the centre, inclined, with `speed` times the escape velocity
+fn state(speed: f64) -> (Vector3, Vector3) {
+    let pos = V!(7e6, 0.0, 0.0);
+    let v_esc = (2.0 * gm() / 7e6).sqrt();
+    // 20° above the local horizontal and out of the x-y plane
+    let (sin, cos) = 20f64.to_radians().sin_cos();
+    let dir = V!(sin, 0.6 * cos, 0.8 * cos);
+    (pos, dir * (speed * v_esc))
+}
+
+/// Propagates by `dt` then back by `-dt`, checking that energy is conserved and the state is restored
+fn assert_round_trip(pos: &Vector3, vel: &Vector3, dt: f64) {
+    let (pos1, vel1) = propagate(pos, vel, gm(), dt);
+    assert!((pos1 - *pos).length() > 1e5, "the state did not move in {} s", dt);
+    let (e0, e1) = (energy(pos, vel), energy(&pos1, &vel1));
+    assert!((e1 - e0).abs() < 1e-6 * gm() / pos.length(), "{} != {}", e1, e0);
+
+    let (pos2, vel2) = propagate(&pos1, &vel1, gm(), -dt);
+    assert!((pos2 - *pos).length() < 1e-3, "{:?} != {:?}", pos2, pos);
+    assert!((vel2 - *vel).length() < 1e-6, "{:?} != {:?}", vel2, vel);
+}
+
+#[test]
+fn hyperbolic_round_trips() {
+    let (pos, vel) = state(1.5);
+    assert!(OrbitalElements::from_state(&pos, &vel, gm()).e > 1.0);
+    for dt in [60.0, 3600.0, 86400.0] {
+        assert_round_trip(&pos, &vel, dt);
+        assert_round_trip(&pos, &vel, -dt);
+    }
+}
+
+#[test]
+fn near_parabolic_round_trips() {
+    for speed in [1.0 - 1e-10, 1.0, 1.0 + 1e-10] {
+        let (pos, vel) = state(speed);
+        let e = OrbitalElements::from_state(&pos, &vel, gm()).e;
+        assert!((e - 1.0).abs() < 1e-9, "e = {}", e);
+        for dt in [60.0, 3600.0, 86400.0] {
+            assert_round_trip(&pos, &vel, dt);
+            assert_round_trip(&pos, &vel, -dt);
+        }
+    }
+}
+
+#[test]
+fn radius_never_reached() {
+    // Ellipse between about 5500 km and 13900 km
+    let (pos, vel) = state(0.8);
+    let el = OrbitalElements::from_state(&pos, &vel, gm());
+    assert!(el.e > 0.0 && el.e < 1.0);
+    assert!(time_to_radius(&pos, &vel, gm(), el.apoapsis() * 1.01).is_none());
+    assert!(time_to_radius(&pos, &vel, gm(), el.periapsis() * 0.99).is_none());
+    assert!(time_to_radius(&pos, &vel, gm(), el.apoapsis() * 0.99).is_some());
+
+    // Circular orbits never change radius
+    let v_circ = (gm() / 7e6).sqrt();
+    assert!(time_to_radius(&pos, &V!(0.0, v_circ, 0.0), gm(), 7e6).is_none());
+    assert!(time_to_radius(&pos, &V!(0.0, v_circ, 0.0), gm(), 8e6).is_none());
+
+    // Past periapsis on an escape trajectory, the vessel only gets further away
+    let (pos, vel) = state(1.5);
+    assert!(pos.dot(&vel) > 0.0);
+    assert!(time_to_radius(&pos, &vel, gm(), 6.9e6).is_none());
+    let t = time_to_radius(&pos, &vel, gm(), 1e8).unwrap();
+    let (pos_far, _) = propagate(&pos, &vel, gm(), t);
+    assert!((pos_far.length() - 1e8).abs() < 1e-2);
+}
+
+#[test]
+fn true_anomaly_never_reached() {
+    // Open orbits only reach true anomalies between their asymptotes, and not the ones already passed
+    let (pos, vel) = state(1.5);
+    let el = OrbitalElements::from_state(&pos, &vel, gm());
+    let asymptote = (-1.0 / el.e).acos();
+    assert!(time_to_true_anomaly(&pos, &vel, gm(), asymptote + 0.01).is_none());
+    assert!(time_to_true_anomaly(&pos, &vel, gm(), -asymptote + 0.01).is_none());
+    assert!(time_to_true_anomaly(&pos, &vel, gm(), el.true_anomaly / 2.0).is_none());
+    assert!(time_to_true_anomaly(&pos, &vel, gm(), asymptote - 0.01).is_some());
+
+    // A parabola goes to infinity at 180°
+    let (pos, vel) = state(1.0);
+    assert!(time_to_true_anomaly(&pos, &vel, gm(), PI).is_none());
+    let el = OrbitalElements::from_state(&pos, &vel, gm());
+    assert!(time_to_true_anomaly(&pos, &vel, gm(), el.true_anomaly - 0.1).is_none());
+    let t = time_to_true_anomaly(&pos, &vel, gm(), el.true_anomaly + 1.0).unwrap();
+    let (pos1, vel1) = propagate(&pos, &vel, gm(), t);
+    let nu = OrbitalElements::from_state(&pos1, &vel1, gm()).true_anomaly;
+    assert!((nu - (el.true_anomaly + 1.0)).abs() < 1e-6, "{}", nu);
+}
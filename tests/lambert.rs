@@ -0,0 +1,142 @@
+//! Lambert transfers checked by propagating every solution to the arrival point
+use orbiter_rs::kepler::propagate;
+use orbiter_rs::lambert::{intercept, solve, Direction, LambertSolution};
+use orbiter_rs::{Body, Vector3, V};
+
+fn gm() -> f64 {
+    Body::EARTH.gm()
+}
+
+/// Checks that departing `r1` with `sol.v1` reaches `r2` with `sol.v2` after `tof` seconds
+fn assert_reaches(r1: &Vector3, r2: &Vector3, tof: f64, sol: &LambertSolution) {
+    let (pos, vel) = propagate(r1, &sol.v1, gm(), tof);
+    assert!((pos - *r2).length() < 1e-2, "{:?}: arrives at {:?}", sol, pos);
+    assert!((vel - sol.v2).length() < 1e-5, "{:?}: arrives with {:?}", sol, vel);
+}
+
+/// Angular momentum about the ecliptic north pole (+y), positive for prograde motion
+fn north_momentum(pos: &Vector3, vel: &Vector3) -> f64 {
+    pos.x() * vel.z() - pos.z() * vel.x()
+}
+
+#[test]
+fn multi_revolution_transfers_in_both_directions() {
+    let r1 = V!(7.0e6, 0.0, 0.0);
+    let r2 = V!(-2.0e6, 1.0e6, 7.5e6);
+    // About four periods of a low orbit, long enough for up to three complete revolutions
+    let tof = 24000.0;
+    for direction in [Direction::Prograde, Direction::Retrograde] {
+        let solutions = solve(&r1, &r2, tof, gm(), direction, 3);
+        let revolutions: Vec<u32> = solutions.iter().map(|sol| sol.revolutions).collect();
+        assert_eq!(revolutions, [0, 1, 1, 2, 2, 3, 3], "{:?}", direction);
+        for sol in &solutions {
+            assert_reaches(&r1, &r2, tof, sol);
+            let momentum = north_momentum(&r1, &sol.v1);
+            match direction {
+                Direction::Prograde => assert!(momentum > 0.0, "{:?}", sol),
+                Direction::Retrograde => assert!(momentum < 0.0, "{:?}", sol),
+            }
+        }
+        // The left and right branches are different orbits
+        for pair in solutions[1..].chunks(2) {
+            assert!((pair[0].v1 - pair[1].v1).length() > 1.0, "{:?}", pair);
+        }
+
+        // Fewer revolutions are only searched when asked for
+        assert_eq!(solve(&r1, &r2, tof, gm(), direction, 1), solutions[..3]);
+        assert_eq!(solve(&r1, &r2, tof, gm(), direction, 0), solutions[..1]);
+    }
+}
+
+#[test]
+fn transfer_angle_over_180_degrees() {
+    // Seen from the ecliptic north pole, r2 lies more than 180° ahead of r1 along a prograde orbit
+    let r1 = V!(7.0e6, 0.0, 0.0);
+    let r2 = V!(1.0e6, 5.0e5, -8.0e6);
+    assert!(north_momentum(&r1, &r2) < 0.0);
+    let cases = [(Direction::Prograde, 4000.0), (Direction::Prograde, 1000.0), (Direction::Retrograde, 2000.0)];
+    for (direction, tof) in cases {
+        let solutions = solve(&r1, &r2, tof, gm(), direction, 0);
+        assert_eq!(solutions.len(), 1, "{:?} in {} s", direction, tof);
+        assert_reaches(&r1, &r2, tof, &solutions[0]);
+        let momentum = north_momentum(&r1, &solutions[0].v1);
+        assert_eq!(momentum > 0.0, direction == Direction::Prograde);
+    }
+}
+
+#[test]
+fn revolutions_appear_at_the_minimum_time_of_flight() {
+    let r1 = V!(7.0e6, 0.0, 0.0);
+    let r2 = V!(-2.0e6, 1.0e6, 7.5e6);
+    let has_one_revolution = |tof: f64| solve(&r1, &r2, tof, gm(), Direction::Prograde, 1).len() > 1;
+
+    // Bisect the shortest time of flight with a complete revolution
+    let (mut low, mut high) = (1000.0, 24000.0);
+    assert!(!has_one_revolution(low) && has_one_revolution(high));
+    for _ in 0..40 {
+        let mid = (low + high) / 2.0;
+        if has_one_revolution(mid) {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    assert!(high - low < 1e-6);
+    // Longer than one period of the orbit through both points, which has a semi-major axis of at least s / 2
+    let s = (r1.length() + r2.length() + (r2 - r1).length()) / 2.0;
+    assert!(high > std::f64::consts::TAU * ((s / 2.0).powi(3) / gm()).sqrt());
+
+    // Just above the minimum, both branches exist and nearly coincide
+    let tof = high * (1.0 + 1e-6);
+    let solutions = solve(&r1, &r2, tof, gm(), Direction::Prograde, 1);
+    assert_eq!(solutions.len(), 3);
+    for sol in &solutions {
+        assert_reaches(&r1, &r2, tof, sol);
+    }
+    assert!((solutions[1].v1 - solutions[2].v1).length() < 0.1 * solutions[1].v1.length());
+}
+
+#[test]
+fn undefined_transfers_have_no_solution() {
+    let r1 = V!(7.0e6, 0.0, 0.0);
+    // Collinear with the central body, on either side
+    for r2 in [V!(-8.0e6, 0.0, 0.0), V!(9.0e6, 0.0, 0.0)] {
+        for direction in [Direction::Prograde, Direction::Retrograde] {
+            assert!(solve(&r1, &r2, 3000.0, gm(), direction, 2).is_empty());
+        }
+    }
+    // No time to get there
+    let r2 = V!(0.0, 1.0e6, 8.0e6);
+    assert!(solve(&r1, &r2, 0.0, gm(), Direction::Prograde, 2).is_empty());
+    assert!(solve(&r1, &r2, -3000.0, gm(), Direction::Prograde, 2).is_empty());
+}
+
+#[test]
+fn intercept_matches_the_target_at_arrival() {
+    // Vessel in a circular prograde orbit, target on an inclined orbit ahead of it
+    let pos = V!(7.0e6, 0.0, 0.0);
+    let v_circ = (gm() / 7.0e6).sqrt();
+    let vel = V!(0.0, 0.0, v_circ);
+    assert!(north_momentum(&pos, &vel) > 0.0);
+    let target_pos = V!(0.0, 1.0e6, 8.0e6);
+    let target_vel = V!(-7000.0, 500.0, 0.0);
+    let tof = 15000.0;
+
+    let plan = intercept(&pos, &vel, &target_pos, &target_vel, gm(), tof, 2).unwrap();
+    let (arrival_pos, arrival_vel) = propagate(&target_pos, &target_vel, gm(), tof);
+    assert_eq!(plan.arrival_pos, arrival_pos);
+    let (pos1, vel1) = propagate(&pos, &(vel + plan.departure_dv), gm(), tof);
+    assert!((pos1 - arrival_pos).length() < 1e-2);
+    assert!((vel1 + plan.arrival_dv - arrival_vel).length() < 1e-5);
+
+    // The cheapest of the prograde transfers
+    let total = |departure: &Vector3, arrival: &Vector3| departure.length() + arrival.length();
+    let solutions = solve(&pos, &arrival_pos, tof, gm(), Direction::Prograde, 2);
+    assert!(solutions.len() > 1);
+    let cheapest = solutions
+        .iter()
+        .map(|sol| total(&(sol.v1 - vel), &(arrival_vel - sol.v2)))
+        .fold(f64::INFINITY, f64::min);
+    assert_eq!(total(&plan.departure_dv, &plan.arrival_dv), cheapest);
+    assert!(solutions.iter().any(|sol| sol.revolutions == plan.revolutions && sol.v1 == vel + plan.departure_dv));
+}
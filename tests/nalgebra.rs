@@ -0,0 +1,59 @@
+//! Conversions between the SDK types and `nalgebra`
+#![cfg(all(feature = "nalgebra", not(orbiter_sdk)))]
+
+use orbiter_rs::{Matrix3, Quaternion, Vector3, V};
+
+fn matrix() -> Matrix3 {
+    Matrix3::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 10.0]])
+}
+
+#[test]
+fn vector_round_trips() {
+    let v = V!(1.0, -2.0, 3.5);
+    let n: nalgebra::Vector3<f64> = v.into();
+    assert_eq!((n.x, n.y, n.z), (1.0, -2.0, 3.5));
+    assert_eq!(Vector3::from(n), v);
+    let p: nalgebra::Point3<f64> = v.into();
+    assert_eq!((p.x, p.y, p.z), (1.0, -2.0, 3.5));
+    assert_eq!(Vector3::from(p), v);
+}
+
+#[test]
+fn matrix_keeps_rows_and_columns() {
+    let m = matrix();
+    let n: nalgebra::Matrix3<f64> = m.into();
+    assert_eq!(n[(0, 1)], 2.0);
+    assert_eq!(n[(1, 0)], 4.0);
+    assert_eq!(Matrix3::from(n), m);
+
+    let v = V!(1.0, -2.0, 3.5);
+    let product: Vector3 = (n * nalgebra::Vector3::from(v)).into();
+    assert_eq!(product, m * v);
+}
+
+#[test]
+fn rotation_converts_to_matrix() {
+    let axis = nalgebra::Vector3::y_axis();
+    let r = nalgebra::Rotation3::from_axis_angle(&axis, 0.5);
+    let m = Matrix3::from(r);
+    let v = V!(1.0, -2.0, 3.5);
+    let expected: Vector3 = (r * nalgebra::Vector3::from(v)).into();
+    assert!((m * v - expected).length() < 1e-12);
+}
+
+#[test]
+fn quaternion_round_trips() {
+    // w first on both sides
+    let q = Quaternion::new(0.5, 0.1, -0.7, 0.3);
+    let n: nalgebra::Quaternion<f64> = q.into();
+    assert_eq!((n.w, n.i, n.j, n.k), (0.5, 0.1, -0.7, 0.3));
+    assert_eq!(Quaternion::from(n), q);
+
+    // Both rotate vectors the same way
+    let q = q.unit();
+    let u: nalgebra::UnitQuaternion<f64> = q.into();
+    let v = V!(1.0, -2.0, 3.5);
+    let rotated: Vector3 = (u * nalgebra::Vector3::from(v)).into();
+    assert!((rotated - q.rotate(&v)).length() < 1e-12);
+    assert!((Quaternion::from(u).dot(&q) - 1.0).abs() < 1e-12);
+}
@@ -0,0 +1,155 @@
+//! Flies the Surveyor example through its retro and vernier sequence on the headless harness
+#![cfg(not(orbiter_sdk))]
+
+#[allow(dead_code)]
+#[path = "../examples/Surveyor/surveyor.rs"]
+mod surveyor;
+
+use orbiter_rs::harness::Harness;
+use orbiter_rs::mock::{self, created_vessels};
+use orbiter_rs::{Body, Key, SDKVessel, ThrusterGroupType, V};
+use surveyor::Surveyor;
+
+const RETRO_PROP_MASS: f64 = 560.64;
+const LANDER_EMPTY_MASS: f64 = 289.10;
+const RETRO_EMPTY_MASS: f64 = 64.88;
+const AMR_MASS: f64 = 3.82;
+
+// Propellant resources and thrusters in the order Surveyor creates them
+const PH_VERNIER: usize = 0;
+const PH_RETRO: usize = 2;
+const TH_VERNIER: [usize; 3] = [0, 1, 2];
+const TH_RETRO: usize = 9;
+
+fn surveyor_harness() -> Harness<Surveyor<SDKVessel>> {
+    mock::reset();
+    let mut harness = Harness::new("Surveyor", Body::MOON, Surveyor::new);
+    // Falling towards the Moon from 100 km, with the retro pointing down
+    harness.set_position(V!(0.0, 0.0, Body::MOON.radius + 100e3));
+    harness.set_velocity(V!(0.0, 0.0, -2000.0));
+    harness
+}
+
+fn propellant(harness: &Harness<Surveyor<SDKVessel>>, idx: usize) -> f64 {
+    harness.mock().state().propellants[idx].mass
+}
+
+fn meshes(harness: &Harness<Surveyor<SDKVessel>>) -> Vec<String> {
+    harness.mock().state().meshes.iter().map(|mesh| mesh.name.clone()).collect()
+}
+
+#[test]
+fn retro_sequence_jettisons_amr_then_retro_case() {
+    let mut harness = surveyor_harness();
+    assert_eq!(meshes(&harness), ["Surveyor-AMR", "Surveyor-Retro", "Surveyor-Lander"]);
+
+    // Nothing happens before the retro is lit
+    harness.run(1.0, 0.1);
+    assert_eq!(propellant(&harness, PH_RETRO), RETRO_PROP_MASS);
+    assert_eq!(harness.mock().state().empty_mass, LANDER_EMPTY_MASS + RETRO_EMPTY_MASS + AMR_MASS);
+    assert!(created_vessels().is_empty());
+
+    assert_eq!(harness.press_key(Key::L), 1);
+    assert_eq!(harness.mock().state().thrusters[TH_RETRO].level, 1.0);
+
+    // The AMR goes on the first step after the retro propellant drops below 99.9%
+    for _ in 0..100 {
+        let before = propellant(&harness, PH_RETRO);
+        harness.step(0.01);
+        if created_vessels().is_empty() {
+            assert!(before >= 0.999 * RETRO_PROP_MASS);
+        } else {
+            assert!(before < 0.999 * RETRO_PROP_MASS);
+            break;
+        }
+    }
+    let created = created_vessels();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].name, "Surveyor-AMR");
+    assert_eq!(created[0].classname, "Surveyor_AMR");
+    assert_eq!(meshes(&harness), ["Surveyor-Retro", "Surveyor-Lander"]);
+    assert_eq!(harness.mock().state().empty_mass, LANDER_EMPTY_MASS + RETRO_EMPTY_MASS);
+    assert_eq!(harness.mock().state().thrusters[TH_RETRO].level, 1.0);
+
+    // The retro burns for about 40.5 s, then its case is jettisoned
+    let vel = harness.state().vel;
+    assert!(harness.run_until(60.0, 0.1, |_| created_vessels().len() == 2));
+    assert!((harness.sim_t() - 41.5).abs() < 0.5, "retro burnout at {}", harness.sim_t());
+    assert!(propellant(&harness, PH_RETRO) < 1.0);
+    let created = created_vessels();
+    assert_eq!(created[1].name, "Surveyor-Retro");
+    assert_eq!(created[1].classname, "Surveyor_Retro");
+    assert_eq!(meshes(&harness), ["Surveyor-Lander"]);
+    assert_eq!(harness.mock().state().empty_mass, LANDER_EMPTY_MASS);
+
+    // The retro slowed the descent by well over a kilometre per second
+    assert!(harness.state().vel.z() - vel.z() > 1500.0);
+    assert!(harness.touchdown().is_none());
+}
+
+#[test]
+fn verniers_throttle_and_steer_by_differential_thrust() {
+    let mut harness = surveyor_harness();
+    harness.step(0.1);
+
+    // Main engine at half throttle: the three verniers draw 3 × 463 N × 0.5 / 3200 m/s
+    harness.mock().state_mut().set_thruster_group_level(ThrusterGroupType::Main, 0.5);
+    let vernier = propellant(&harness, PH_VERNIER);
+    let (pos, vel) = (harness.state().pos, harness.state().vel);
+    harness.run(10.0, 0.1);
+    let consumed = vernier - propellant(&harness, PH_VERNIER);
+    assert!((consumed - 3.0 * 463.0 * 0.5 / 3200.0 * 10.0).abs() < 1e-9);
+    // Thrust along the vessel's z axis, which points away from the Moon, at the mean mass and gravity of
+    // the burn
+    let mass = harness.mass() + consumed / 2.0;
+    let gravity = |r: f64| Body::MOON.gm() / (r * r);
+    let gravity = (gravity(pos.length()) + gravity(harness.state().pos.length())) / 2.0;
+    let expected = 3.0 * 463.0 * 0.5 / mass - gravity;
+    let accel = (harness.state().vel.z() - vel.z()) / 10.0;
+    assert!((accel - expected).abs() < 1e-3, "{} != {}", accel, expected);
+    assert_eq!(harness.state().angular_vel, V!(0.0, 0.0, 0.0));
+
+    // Pitching up tilts the thrust of the two lower verniers
+    harness.mock().state_mut().set_thruster_group_level(ThrusterGroupType::AttPitchup, 1.0);
+    harness.step(0.1);
+    {
+        let state = harness.mock().state();
+        let dirs: Vec<_> = TH_VERNIER.iter().map(|&th| state.thrusters[th].dir).collect();
+        assert_eq!(dirs, [V!(0.0, 0.0, 1.0), V!(0.0, 0.0, 1.05), V!(0.0, 0.0, 1.05)]);
+    }
+    harness.run(1.0, 0.1);
+    let angular_vel = harness.state().angular_vel;
+    assert!(angular_vel.x() > 0.0);
+    assert!(angular_vel.y().abs() < 1e-12 && angular_vel.z().abs() < 1e-12);
+
+    // Rolling offsets the first vernier sideways
+    harness.mock().state_mut().set_thruster_group_level(ThrusterGroupType::AttPitchup, 0.0);
+    harness.mock().state_mut().set_thruster_group_level(ThrusterGroupType::AttBankright, 1.0);
+    harness.step(0.1);
+    let dir = harness.mock().state().thrusters[TH_VERNIER[0]].dir;
+    assert!((dir.x() - 5.0f64.to_radians().sin()).abs() < 1e-12);
+}
+
+#[test]
+fn rcs_jets_turn_the_lander_about_a_single_axis() {
+    let mut harness = surveyor_harness();
+    // Far from the Moon, so only the jets act on the lander
+    harness.set_position(V!(1e15, 0.0, 0.0));
+    harness.step(0.1);
+
+    // The -Z jets pitch the lander down
+    harness.mock().state_mut().set_thruster_group_level(ThrusterGroupType::AttPitchdown, 1.0);
+    harness.run(1.0, 0.1);
+    let angular_vel = harness.state().angular_vel;
+    assert!(angular_vel.x() < 0.0, "{:?}", angular_vel);
+    assert_eq!((angular_vel.y(), angular_vel.z()), (0.0, 0.0));
+
+    harness.mock().state_mut().set_thruster_group_level(ThrusterGroupType::AttPitchdown, 0.0);
+    harness.set_angular_vel(V!(0.0, 0.0, 0.0));
+    harness.mock().state_mut().set_thruster_group_level(ThrusterGroupType::AttBankleft, 1.0);
+    harness.run(1.0, 0.1);
+    // The -X jet alone rolls the lander left, without pitching it
+    let angular_vel = harness.state().angular_vel;
+    assert!(angular_vel.z() < 0.0, "{:?}", angular_vel);
+    assert_eq!(angular_vel.x(), 0.0);
+}
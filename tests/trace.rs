@@ -0,0 +1,486 @@
+//! Writing and parsing traces, and recording through `SDKVessel`
+#![cfg(not(orbiter_sdk))]
+
+#[allow(dead_code)]
+#[path = "../examples/Surveyor/surveyor.rs"]
+mod surveyor;
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use orbiter_rs::harness::Harness;
+use orbiter_rs::mock::{self, created_vessels, MockVessel};
+use orbiter_rs::trace::{replay, start_recording, stop_recording, Trace, TraceEvent, VesselCall, VesselReturn};
+use orbiter_rs::{
+    Body, Elements, Key, Matrix3, OrbitParam, ReferenceFrame, ThrusterGroupType, VesselStatus, OBJHANDLE,
+    PROPELLANT_HANDLE, THGROUP_HANDLE, THRUSTER_HANDLE, V,
+};
+use surveyor::Surveyor;
+
+/// Number of variants of [VesselCall], [VesselReturn] and [TraceEvent]
+const CALL_VARIANTS: usize = 46;
+const RETURN_VARIANTS: usize = 29;
+const EVENT_VARIANTS: usize = 9;
+
+/// Position of `call` in the declaration of [VesselCall]
+///
+/// The match has no wildcard, so a new variant does not compile until it is added here and to [calls].
+fn call_index(call: &VesselCall) -> usize {
+    use VesselCall::*;
+    match call {
+        SetSize(..) => 0,
+        SetPMI(..) => 1,
+        SetEmptyMass(..) => 2,
+        SetCameraOffset(..) => 3,
+        SetTouchdownPoints(..) => 4,
+        SetThrusterDir(..) => 5,
+        SetThrusterLevel(..) => 6,
+        SetThrusterLevelSingleStep(..) => 7,
+        SetThrusterGroupLevelByType(..) => 8,
+        AddMesh(..) => 9,
+        AddMeshWithOffset(..) => 10,
+        AddExhaust(..) => 11,
+        CreatePropellantResource(..) => 12,
+        CreateThruster { .. } => 13,
+        CreateThrusterGroup(..) => 14,
+        ClearMeshes => 15,
+        Local2Rel(..) => 16,
+        Global2Local(..) => 17,
+        Local2Global(..) => 18,
+        GetName => 19,
+        GetStatus => 20,
+        GetPropellantMass(..) => 21,
+        GetAngularVel => 22,
+        GetAirspeedVector(..) => 23,
+        GetThrustVector => 24,
+        GetTotalPropellantMass => 25,
+        GetEmptyMass => 26,
+        GetThrusterGroupLevelByType(..) => 27,
+        GetThrusterGroupLevel(..) => 28,
+        GetThrusterMax0(..) => 29,
+        GetThrusterIsp0(..) => 30,
+        GroundContact => 31,
+        GetSurfaceRef => 32,
+        GetSurfaceElevation => 33,
+        GetAltitude => 34,
+        GetAtmTemperature => 35,
+        GetAtmDensity => 36,
+        GetAtmPressure => 37,
+        GetRelativeVel(..) => 38,
+        GetEquPos => 39,
+        GetHorizonAirspeedVector => 40,
+        GetRotationMatrix => 41,
+        GetRotDrag => 42,
+        GetElements(..) => 43,
+        SetElements(..) => 44,
+        ParseScenarioLineEx(..) => 45,
+    }
+}
+
+/// Position of `ret` in the declaration of [VesselReturn]
+fn return_index(ret: &VesselReturn) -> usize {
+    use VesselReturn::*;
+    match ret {
+        Local2Rel(..) => 0,
+        Global2Local(..) => 1,
+        Local2Global(..) => 2,
+        GetName(..) => 3,
+        GetStatus(..) => 4,
+        GetPropellantMass(..) => 5,
+        GetAngularVel(..) => 6,
+        GetAirspeedVector(..) => 7,
+        GetThrustVector(..) => 8,
+        GetTotalPropellantMass(..) => 9,
+        GetEmptyMass(..) => 10,
+        GetThrusterGroupLevelByType(..) => 11,
+        GetThrusterGroupLevel(..) => 12,
+        GetThrusterMax0(..) => 13,
+        GetThrusterIsp0(..) => 14,
+        GroundContact(..) => 15,
+        GetSurfaceRef(..) => 16,
+        GetSurfaceElevation(..) => 17,
+        GetAltitude(..) => 18,
+        GetAtmTemperature(..) => 19,
+        GetAtmDensity(..) => 20,
+        GetAtmPressure(..) => 21,
+        GetRelativeVel(..) => 22,
+        GetEquPos(..) => 23,
+        GetHorizonAirspeedVector(..) => 24,
+        GetRotationMatrix(..) => 25,
+        GetRotDrag(..) => 26,
+        GetElements(..) => 27,
+        SetElements(..) => 28,
+    }
+}
+
+/// Position of `event` in the declaration of [TraceEvent]
+fn event_index(event: &TraceEvent) -> usize {
+    match event {
+        TraceEvent::SetClassCaps => 0,
+        TraceEvent::ConfigItem { .. } => 1,
+        TraceEvent::PreStep { .. } => 2,
+        TraceEvent::PostStep { .. } => 3,
+        TraceEvent::Key { .. } => 4,
+        TraceEvent::LoadParam(..) => 5,
+        TraceEvent::SaveState => 6,
+        TraceEvent::Call(..) => 7,
+        TraceEvent::Return(..) => 8,
+    }
+}
+
+fn elements() -> Elements {
+    Elements {
+        a: 6.771e6,
+        e: 0.0012,
+        i: 0.9012,
+        theta: -2.5,
+        omegab: 1e-300,
+        L: std::f64::consts::TAU,
+    }
+}
+
+fn orbit_param() -> OrbitParam {
+    OrbitParam {
+        SMi: 6.770995e6,
+        PeD: 6.7629e6,
+        ApD: 6.7791e6,
+        MnA: 0.1,
+        TrA: 0.1002,
+        MnL: 1.7,
+        TrL: 1.7002,
+        EcA: 0.1001,
+        Lec: 8125.2,
+        T: 5544.9,
+        PeT: -12.5,
+        ApT: 2759.95,
+    }
+}
+
+fn status() -> VesselStatus {
+    let mut status = VesselStatus {
+        rpos: V!(1.5e11, -2.25e7, 3.0),
+        rvel: V!(-29780.5, 0.1, 7.5e-5),
+        vrot: V!(0.01, -0.02, 0.03),
+        arot: V!(3.0, -1.5, 0.0),
+        fuel: 0.75,
+        eng_main: -0.5,
+        eng_hovr: 1.0,
+        rbody: OBJHANDLE(7),
+        base: OBJHANDLE(0),
+        port: -1,
+        status: 1,
+        ..Default::default()
+    };
+    status.vdata[0] = V!(0.25, 0.5, 0.75);
+    status
+}
+
+/// One value of every call, with the awkward values where the layout allows them
+fn calls() -> Vec<VesselCall> {
+    use VesselCall::*;
+    let v = V!(0.1, -2.5e-7, 1e300);
+    vec![
+        SetSize(2.5),
+        SetPMI(V!(0.5, 0.5, 0.25)),
+        SetEmptyMass(f64::MAX),
+        SetCameraOffset(V!(0.0, 0.8, -0.0)),
+        SetTouchdownPoints(V!(0.0, 1.5, -0.6), V!(1.3, -0.75, -0.6), V!(-1.3, -0.75, -0.6)),
+        SetThrusterDir(THRUSTER_HANDLE(3), V!(0.087, 0.0, 0.996)),
+        SetThrusterLevel(THRUSTER_HANDLE(1), 1.0),
+        SetThrusterLevelSingleStep(THRUSTER_HANDLE(0), 1.0 / 3.0),
+        SetThrusterGroupLevelByType(ThrusterGroupType::AttBankleft, -0.0),
+        AddMesh("Surveyor Lander  (v2) ".into()),
+        AddMeshWithOffset(" Surveyor-AMR 1.0 2.0".into(), V!(0.0, 0.0, -0.6)),
+        AddExhaust(THRUSTER_HANDLE(10), 2.0, 0.3),
+        CreatePropellantResource(560.64),
+        CreateThruster {
+            pos: V!(0.0, 0.86, -0.5),
+            dir: V!(0.0, 0.0, 1.0),
+            maxth0: 463.0,
+            ph: PROPELLANT_HANDLE(1),
+            isp: 3200.0,
+        },
+        CreateThrusterGroup(vec![THRUSTER_HANDLE(1), THRUSTER_HANDLE(2), THRUSTER_HANDLE(3)], ThrusterGroupType::Main),
+        ClearMeshes,
+        Local2Rel(v),
+        Global2Local(V!(f64::INFINITY, f64::NEG_INFINITY, f64::MIN_POSITIVE)),
+        Local2Global(v),
+        GetName,
+        GetStatus,
+        GetPropellantMass(PROPELLANT_HANDLE(2)),
+        GetAngularVel,
+        GetAirspeedVector(ReferenceFrame::Horizon),
+        GetThrustVector,
+        GetTotalPropellantMass,
+        GetEmptyMass,
+        GetThrusterGroupLevelByType(ThrusterGroupType::User),
+        GetThrusterGroupLevel(THGROUP_HANDLE(4)),
+        GetThrusterMax0(THRUSTER_HANDLE(2)),
+        GetThrusterIsp0(THRUSTER_HANDLE(4)),
+        GroundContact,
+        GetSurfaceRef,
+        GetSurfaceElevation,
+        GetAltitude,
+        GetAtmTemperature,
+        GetAtmDensity,
+        GetAtmPressure,
+        GetRelativeVel(OBJHANDLE(usize::MAX)),
+        GetEquPos,
+        GetHorizonAirspeedVector,
+        GetRotationMatrix,
+        GetRotDrag,
+        GetElements(OBJHANDLE(3), 51544.5, -1),
+        SetElements(OBJHANDLE(3), elements(), 0.0, 2),
+        ParseScenarioLineEx("  CONFIGURATION 1 # with comments ".into()),
+    ]
+}
+
+/// One value of every getter return
+fn returns() -> Vec<VesselReturn> {
+    use VesselReturn::*;
+    let rotation = Matrix3::new([[0.36, 0.48, -0.8], [-0.8, 0.6, 0.0], [0.48, 0.64, 0.6]]);
+    vec![
+        Local2Rel(V!(1.0, 2.0, 3.0)),
+        Global2Local(V!(-1e-12, 0.0, 5e12)),
+        Local2Global(V!(1.5e11, 2.5, -3.5)),
+        GetName("Surveyor 1 - Lander".into()),
+        GetStatus(Box::new(status())),
+        GetPropellantMass(70.98),
+        GetAngularVel(V!(0.001, -0.002, 0.003)),
+        GetAirspeedVector(true, V!(10.0, -20.0, 30.0)),
+        GetThrustVector(false, V!(0.0, 0.0, 0.0)),
+        GetTotalPropellantMass(633.62),
+        GetEmptyMass(289.1),
+        GetThrusterGroupLevelByType(0.25),
+        GetThrusterGroupLevel(1.0),
+        GetThrusterMax0(39140.0),
+        GetThrusterIsp0(2827.4),
+        GroundContact(true),
+        GetSurfaceRef(OBJHANDLE(12)),
+        GetSurfaceElevation(-410.5),
+        GetAltitude(1523.25),
+        GetAtmTemperature(216.65),
+        GetAtmDensity(1.225),
+        GetAtmPressure(101325.0),
+        GetRelativeVel(V!(-1633.2, 0.0, 12.7)),
+        GetEquPos(-3.0, 0.0001, 1737530.0),
+        GetHorizonAirspeedVector(true, V!(1.0, -2.0, 3.0)),
+        GetRotationMatrix(rotation),
+        GetRotDrag(V!(0.0, 0.0, 0.0)),
+        GetElements(true, elements(), orbit_param()),
+        SetElements(false, orbit_param()),
+    ]
+}
+
+/// One value of every event, wrapping every call and return
+fn events() -> Vec<TraceEvent> {
+    let mut events = vec![
+        TraceEvent::SetClassCaps,
+        TraceEvent::ConfigItem {
+            item: "ClassName".into(),
+            value: "Surveyor  Lander ".into(),
+        },
+        TraceEvent::ConfigItem {
+            item: "Empty".into(),
+            value: "".into(),
+        },
+        TraceEvent::PreStep {
+            sim_t: 10.0,
+            sim_dt: 0.02,
+            mjd: 51544.5,
+        },
+        TraceEvent::PostStep {
+            sim_t: 10.02,
+            sim_dt: 1e-3,
+            mjd: 51544.500000231,
+        },
+        TraceEvent::Key {
+            key: Key::L,
+            down: true,
+            held: vec![Key::LeftShift, Key::LeftControl, Key::RightAlt],
+        },
+        TraceEvent::Key {
+            key: Key::Escape,
+            down: false,
+            held: vec![],
+        },
+        TraceEvent::LoadParam("TESTPARAM Hello World".into()),
+        TraceEvent::LoadParam("".into()),
+        TraceEvent::SaveState,
+    ];
+    events.extend(calls().into_iter().map(TraceEvent::Call));
+    events.extend(returns().into_iter().map(TraceEvent::Return));
+    events
+}
+
+#[test]
+fn test_values_cover_every_variant() {
+    let mut covered: Vec<usize> = calls().iter().map(call_index).collect();
+    covered.dedup();
+    assert_eq!(covered, (0..CALL_VARIANTS).collect::<Vec<_>>());
+
+    let mut covered: Vec<usize> = returns().iter().map(return_index).collect();
+    covered.dedup();
+    assert_eq!(covered, (0..RETURN_VARIANTS).collect::<Vec<_>>());
+
+    let mut covered: Vec<usize> = events().iter().map(event_index).collect();
+    covered.dedup();
+    assert_eq!(covered, (0..EVENT_VARIANTS).collect::<Vec<_>>());
+}
+
+#[test]
+fn every_event_round_trips() {
+    for event in events() {
+        let line = event.to_string();
+        let parsed: Trace = line.parse().unwrap_or_else(|e| panic!("`{}`: {}", line, e));
+        assert_eq!(parsed.events, [event], "`{}`", line);
+    }
+}
+
+#[test]
+fn trace_round_trips() {
+    let trace = Trace { events: events() };
+    let text = trace.to_string();
+    assert_eq!(text.lines().count(), trace.events.len());
+    assert_eq!(text.parse::<Trace>().unwrap(), trace);
+}
+
+#[test]
+fn free_text_keeps_its_spaces() {
+    let trace: Trace = "call AddMeshWithOffset 0.0 0.0 -0.6  two  spaces \nret GetName  Lander 1\nparam A  B\n"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        trace.events,
+        [
+            TraceEvent::Call(VesselCall::AddMeshWithOffset(" two  spaces ".into(), V!(0.0, 0.0, -0.6))),
+            TraceEvent::Return(VesselReturn::GetName(" Lander 1".into())),
+            TraceEvent::LoadParam("A  B".into()),
+        ]
+    );
+}
+
+#[test]
+fn malformed_lines_report_their_number() {
+    let err = "caps\n\n# comment\ncall SetSize\n".parse::<Trace>().unwrap_err();
+    assert_eq!(err.line, 4);
+    let err = "pre 1.0 0.1 51544.5 extra".parse::<Trace>().unwrap_err();
+    assert_eq!(err.line, 1);
+    assert!("call SetThrusterGroupLevelByType Sideways 1.0".parse::<Trace>().is_err());
+    assert!("ret GroundContact 2".parse::<Trace>().is_err());
+    assert!("launch".parse::<Trace>().is_err());
+}
+
+/// A writer whose contents can be read while the recorder owns it
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+impl SharedBuffer {
+    fn trace(&self) -> Trace {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap().parse().unwrap()
+    }
+}
+
+/// Serializes the tests that record, as there is a single recorder per process
+fn recording() -> MutexGuard<'static, ()> {
+    static RECORDING: Mutex<()> = Mutex::new(());
+    RECORDING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[test]
+fn sdk_vessel_records_calls_and_returns() {
+    let _recording = recording();
+    let mock = MockVessel::new("Surveyor 1");
+    // Created through another context, so the handles Orbiter hands out differ from the recorded creation order
+    let other = mock.sdk_vessel();
+    let ph_other = other.CreatePropellantResource(1.0);
+    let th_other = other.CreateThruster(&V!(0.0, 0.0, 0.0), &V!(1.0, 0.0, 0.0), 1.0, ph_other, 1.0);
+    other.CreateThrusterGroup(&[th_other], ThrusterGroupType::User);
+    let vessel = mock.sdk_vessel();
+
+    let buffer = SharedBuffer::default();
+    start_recording(buffer.clone());
+    let ph = vessel.CreatePropellantResource(70.98);
+    let th = vessel.CreateThruster(&V!(0.0, 0.86, -0.5), &V!(0.0, 0.0, 1.0), 463.0, ph, 3200.0);
+    let group = vessel.CreateThrusterGroup(&[th, th_other], ThrusterGroupType::Main);
+    vessel.SetThrusterLevel(th, 0.5);
+    let mass = vessel.GetPropellantMass(ph);
+    let level = vessel.GetThrusterGroupLevel(group);
+    let name = vessel.GetName().to_owned();
+    mock.state_mut().altitude = 1523.25;
+    let altitude = vessel.GetAltitude();
+    stop_recording();
+
+    // The addon sees the handles handed out by Orbiter
+    assert_eq!((ph, th, group), (PROPELLANT_HANDLE(2), THRUSTER_HANDLE(2), THGROUP_HANDLE(2)));
+    assert_eq!((mass, level, name.as_str(), altitude), (70.98, 0.25, "Surveyor 1", 1523.25));
+
+    // The trace numbers them by creation through the context, and 0 for handles it did not create
+    use TraceEvent::{Call, Return};
+    assert_eq!(
+        buffer.trace().events,
+        [
+            Call(VesselCall::CreatePropellantResource(70.98)),
+            Call(VesselCall::CreateThruster {
+                pos: V!(0.0, 0.86, -0.5),
+                dir: V!(0.0, 0.0, 1.0),
+                maxth0: 463.0,
+                ph: PROPELLANT_HANDLE(1),
+                isp: 3200.0,
+            }),
+            Call(VesselCall::CreateThrusterGroup(vec![THRUSTER_HANDLE(1), THRUSTER_HANDLE(0)], ThrusterGroupType::Main)),
+            Call(VesselCall::SetThrusterLevel(THRUSTER_HANDLE(1), 0.5)),
+            Call(VesselCall::GetPropellantMass(PROPELLANT_HANDLE(1))),
+            Return(VesselReturn::GetPropellantMass(70.98)),
+            Call(VesselCall::GetThrusterGroupLevel(THGROUP_HANDLE(1))),
+            Return(VesselReturn::GetThrusterGroupLevel(0.25)),
+            Call(VesselCall::GetName),
+            Return(VesselReturn::GetName("Surveyor 1".into())),
+            Call(VesselCall::GetAltitude),
+            Return(VesselReturn::GetAltitude(1523.25)),
+        ]
+    );
+
+    // The calls reached the mock
+    assert_eq!(mock.state().thrusters[1].level, 0.5);
+    assert_eq!(mock.state().thruster_groups[1].thrusters, [th, th_other]);
+}
+
+#[test]
+fn surveyor_session_replays_to_the_recorded_calls() {
+    let _recording = recording();
+    mock::reset();
+    let buffer = SharedBuffer::default();
+    start_recording(buffer.clone());
+    let mut harness = Harness::new("Surveyor", Body::MOON, Surveyor::new);
+    harness.set_position(V!(0.0, 0.0, Body::MOON.radius + 100e3));
+    harness.set_velocity(V!(0.0, 0.0, -2000.0));
+    harness.run(1.0, 0.1);
+    // Fire the retro until the case is jettisoned, then steer on the verniers
+    harness.press_key(Key::L);
+    assert!(harness.run_until(60.0, 0.1, |_| created_vessels().len() == 2));
+    harness.mock().state_mut().set_thruster_group_level(ThrusterGroupType::AttPitchup, 1.0);
+    harness.run(1.0, 0.1);
+    stop_recording();
+
+    let trace = buffer.trace();
+    let count = |f: fn(&TraceEvent) -> bool| trace.events.iter().filter(|&e| f(e)).count();
+    assert_eq!(count(|e| matches!(e, TraceEvent::SetClassCaps)), 1);
+    assert_eq!(count(|e| matches!(e, TraceEvent::Key { key: Key::L, down: true, .. })), 1);
+    assert!(count(|e| matches!(e, TraceEvent::PreStep { .. })) > 400);
+    assert!(count(|e| matches!(e, TraceEvent::Return(VesselReturn::GetPropellantMass(_)))) > 400);
+
+    // Replayed on a fresh mock, the getters return the recorded values and the addon makes the same calls
+    mock::reset();
+    let report = replay(trace, Surveyor::new);
+    assert!(report.expected.len() > 1000);
+    assert!(report.is_match(), "{}", report);
+    assert_eq!(created_vessels().len(), 2);
+}